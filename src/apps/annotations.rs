@@ -0,0 +1,218 @@
+use std::rc::Rc;
+
+use cgmath::Point3;
+
+use crate::data::{Annotation, AnnotationKind, Volume};
+
+use super::{slice_renderer::PointTool, SharedSliceState};
+
+/// Annotations shared by the slice views and the annotations panel.
+#[derive(Default)]
+pub struct Annotations {
+    pub items: Vec<Annotation>,
+    /// Kind of annotation that clicks in the slice views place, `None` when
+    /// clicks move the cursor instead.
+    pub tool: Option<AnnotationKind>,
+    /// Text of new annotations.
+    pub text: String,
+    /// Arrow whose head is placed, waiting for its tail.
+    pub pending: Option<Annotation>,
+    /// Highlighted in the slice views and the panel.
+    pub selected: Option<usize>,
+}
+
+impl Annotations {
+    /// Places an annotation of the selected kind at `position`, arrows take
+    /// a second click for their tail.
+    pub fn add_point(&mut self, position: Point3<f32>) {
+        let Some(kind) = self.tool else {
+            return;
+        };
+        match self.pending.take() {
+            Some(mut arrow) => {
+                arrow.tail = Some(position);
+                self.push(arrow);
+            }
+            None if kind == AnnotationKind::Arrow => {
+                self.pending = Some(Annotation::new(kind, position, self.text.clone()));
+            }
+            None => self.push(Annotation::new(kind, position, self.text.clone())),
+        }
+    }
+
+    fn push(&mut self, annotation: Annotation) {
+        if annotation.is_complete() {
+            self.items.push(annotation);
+            self.selected = Some(self.items.len() - 1);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.items.remove(index);
+        self.selected = match self.selected {
+            Some(selected) if selected > index => Some(selected - 1),
+            Some(selected) if selected == index => None,
+            selected => selected,
+        };
+    }
+
+    /// Returns whether a kind was picked, so other tools can be turned off.
+    pub fn tool_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let previous = self.tool;
+            ui.selectable_value(&mut self.tool, None, "Off");
+            for kind in AnnotationKind::ALL {
+                ui.selectable_value(&mut self.tool, Some(kind), kind.label());
+            }
+            if self.tool.is_some() {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.text)
+                        .hint_text("Text")
+                        .desired_width(120.0),
+                );
+            }
+            if self.tool != previous {
+                self.pending = None;
+            }
+            self.tool != previous && self.tool.is_some()
+        })
+        .inner
+    }
+}
+
+/// Lists the annotations and stores them in a sidecar file next to the volume.
+pub struct AnnotationsPanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+}
+
+impl AnnotationsPanel {
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        Self { volume, shared }
+    }
+
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    fn save(&self, path: &std::path::Path) {
+        let annotations = self.shared.annotations.borrow();
+        if let Err(err) = crate::io::save_annotations(path, &annotations.items, &self.volume) {
+            log::error!("Failed to save annotations: {err}");
+        }
+    }
+
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    fn load(&self, path: &std::path::Path) {
+        match crate::io::load_annotations(path, &self.volume) {
+            Ok(items) => {
+                let mut annotations = self.shared.annotations.borrow_mut();
+                annotations.items = items;
+                annotations.selected = None;
+            }
+            Err(err) => log::error!("Failed to load annotations: {err}"),
+        }
+    }
+
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    fn file_buttons(&self, ui: &mut egui::Ui) {
+        let sidecar = self
+            .volume
+            .path
+            .as_deref()
+            .map(crate::io::annotation_sidecar_path);
+        ui.horizontal(|ui| {
+            let hover_text = match &sidecar {
+                Some(path) => path.display().to_string(),
+                None => "The volume was not loaded from a file".to_owned(),
+            };
+            if ui
+                .add_enabled(sidecar.is_some(), egui::Button::new("Save"))
+                .on_hover_text(&hover_text)
+                .on_disabled_hover_text(&hover_text)
+                .clicked()
+            {
+                if let Some(path) = &sidecar {
+                    self.save(path);
+                }
+            }
+            let sidecar_exists = sidecar.as_ref().is_some_and(|path| path.exists());
+            if ui
+                .add_enabled(sidecar_exists, egui::Button::new("Reload"))
+                .on_hover_text(&hover_text)
+                .clicked()
+            {
+                if let Some(path) = &sidecar {
+                    self.load(path);
+                }
+            }
+            if ui.button("Save As...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Annotations", &["json"])
+                    .save_file()
+                {
+                    self.save(&path);
+                }
+            }
+            if ui.button("Load...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Annotations", &["json"])
+                    .pick_file()
+                {
+                    self.load(&path);
+                }
+            }
+        });
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.shared.annotations.borrow_mut().tool_ui(ui) {
+            self.shared.keep_only_tool(PointTool::Annotation);
+        }
+        ui.label("Click in a slice view to place an annotation, arrows take a second click for their tail.");
+        #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+        self.file_buttons(ui);
+        ui.separator();
+
+        let mut removed = None;
+        let mut annotations = self.shared.annotations.borrow_mut();
+        if annotations.items.is_empty() {
+            ui.label("No annotations");
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("annotations")
+                .striped(true)
+                .num_columns(5)
+                .show(ui, |ui| {
+                    for index in 0..annotations.items.len() {
+                        let selected = annotations.selected == Some(index);
+                        let position = annotations.items[index].position;
+                        if ui
+                            .selectable_label(selected, format!("{}", index + 1))
+                            .on_hover_text("Select and show in the slice views")
+                            .clicked()
+                        {
+                            annotations.selected = Some(index);
+                            self.shared.cursor.borrow_mut().set_position(position);
+                        }
+                        let annotation = &mut annotations.items[index];
+                        ui.label(annotation.kind.label());
+                        ui.add(
+                            egui::TextEdit::singleline(&mut annotation.text).desired_width(160.0),
+                        );
+                        let system = self.shared.coordinate_system.get();
+                        ui.label(
+                            system.format(
+                                self.volume
+                                    .position_in(self.volume.voxel_coordinates(position), system),
+                            ),
+                        );
+                        if ui.small_button("Delete").clicked() {
+                            removed = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some(index) = removed {
+            annotations.remove(index);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use cgmath::{InnerSpace, Matrix4, Point3, Rad, SquareMatrix, Vector3};
+
+/// Camera orbiting around a target point, used by the 3D views.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitCamera {
+    pub target: Point3<f32>,
+    pub distance: f32,
+    /// Rotation around the vertical axis in radians.
+    pub yaw: f32,
+    /// Elevation above the horizontal plane in radians.
+    pub pitch: f32,
+    pub fovy: Rad<f32>,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            target: Point3::new(0.0, 0.0, 0.0),
+            distance: 2.0,
+            yaw: std::f32::consts::FRAC_PI_4,
+            pitch: std::f32::consts::FRAC_PI_8,
+            fovy: Rad(std::f32::consts::FRAC_PI_4),
+        }
+    }
+}
+
+impl OrbitCamera {
+    const MIN_DISTANCE: f32 = 0.05;
+    const MAX_DISTANCE: f32 = 50.0;
+    const NEAR: f32 = 0.01;
+    const FAR: f32 = 100.0;
+
+    pub fn eye(&self) -> Point3<f32> {
+        let direction = Vector3::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        );
+        self.target + direction * self.distance
+    }
+
+    pub fn view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_rh(self.eye(), self.target, Vector3::unit_y())
+    }
+
+    pub fn projection(&self, aspect_ratio: f32) -> Matrix4<f32> {
+        cgmath::perspective(self.fovy, aspect_ratio, Self::NEAR, Self::FAR)
+    }
+
+    pub fn view_projection(&self, aspect_ratio: f32) -> Matrix4<f32> {
+        self.projection(aspect_ratio) * self.view()
+    }
+
+    pub fn inverse_view_projection(&self, aspect_ratio: f32) -> Matrix4<f32> {
+        self.view_projection(aspect_ratio)
+            .invert()
+            .unwrap_or_else(Matrix4::identity)
+    }
+
+    /// Rotates around the target, `delta` is given in radians.
+    pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        let limit = std::f32::consts::FRAC_PI_2 - 0.01;
+        self.yaw -= delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(-limit, limit);
+    }
+
+    /// Moves the target in the view plane, `delta` is given relative to the viewport height.
+    pub fn pan(&mut self, delta_x: f32, delta_y: f32) {
+        let forward = (self.target - self.eye()).normalize();
+        let right = forward.cross(Vector3::unit_y()).normalize();
+        let up = right.cross(forward);
+        let scale = 2.0 * self.distance * (self.fovy.0 / 2.0).tan();
+        self.target += (-right * delta_x + up * delta_y) * scale;
+    }
+
+    /// Moves towards the target for positive `delta`.
+    pub fn zoom(&mut self, delta: f32) {
+        self.distance =
+            (self.distance * (-delta).exp()).clamp(Self::MIN_DISTANCE, Self::MAX_DISTANCE);
+    }
+}
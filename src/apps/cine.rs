@@ -0,0 +1,178 @@
+/// What cine playback does after the last slice.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CineMode {
+    /// Starts over at the first slice.
+    Loop,
+    /// Reverses the direction at either end.
+    Bounce,
+}
+
+impl CineMode {
+    const ALL: [Self; 2] = [Self::Loop, Self::Bounce];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Loop => "Loop",
+            Self::Bounce => "Bounce",
+        }
+    }
+}
+
+/// Automatic playback through the slices of a view.
+pub struct Cine {
+    pub playing: bool,
+    /// Slices per second.
+    pub fps: f32,
+    pub mode: CineMode,
+    forward: bool,
+    /// Time of the last step in seconds.
+    last_step: Option<f64>,
+}
+
+impl Default for Cine {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            fps: 10.0,
+            mode: CineMode::Loop,
+            forward: true,
+            last_step: None,
+        }
+    }
+}
+
+impl Cine {
+    /// Returns the slice to show at `time` once the next step is due.
+    pub fn advance(&mut self, time: f64, index: u32, count: u32) -> Option<u32> {
+        if !self.playing || count < 2 {
+            self.last_step = None;
+            return None;
+        }
+
+        let interval = 1.0 / self.fps as f64;
+        let last_step = *self.last_step.get_or_insert(time);
+        if time - last_step < interval {
+            return None;
+        }
+        // keep the rate if a frame is late, but don't catch up after stalls
+        self.last_step = Some(if time - last_step < 2.0 * interval {
+            last_step + interval
+        } else {
+            time
+        });
+
+        let last = count - 1;
+        Some(match self.mode {
+            CineMode::Loop => {
+                if index >= last {
+                    0
+                } else {
+                    index + 1
+                }
+            }
+            CineMode::Bounce => {
+                if index >= last {
+                    self.forward = false;
+                } else if index == 0 {
+                    self.forward = true;
+                }
+                if self.forward {
+                    index + 1
+                } else {
+                    index - 1
+                }
+            }
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = if self.playing { "Pause" } else { "Play" };
+            if ui.button(label).clicked() {
+                self.playing = !self.playing;
+            }
+            ui.add(
+                egui::DragValue::new(&mut self.fps)
+                    .clamp_range(1.0..=60.0)
+                    .speed(0.2)
+                    .suffix(" fps"),
+            );
+            egui::ComboBox::from_id_source("cine_mode")
+                .width(70.0)
+                .selected_text(self.mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in CineMode::ALL {
+                        ui.selectable_value(&mut self.mode, mode, mode.label());
+                    }
+                });
+            ui.label("Cine");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cine at 4 fps, so steps are due every 0.25 s.
+    fn cine(mode: CineMode) -> Cine {
+        Cine {
+            playing: true,
+            fps: 4.0,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Slices shown when `cine` steps on time from `index`.
+    fn play(cine: &mut Cine, mut index: u32, count: u32, steps: usize) -> Vec<u32> {
+        assert_eq!(cine.advance(0.0, index, count), None);
+        (1..=steps)
+            .map(|step| {
+                index = cine.advance(step as f64 * 0.25, index, count).unwrap();
+                index
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loop_starts_over_and_bounce_reverses() {
+        assert_eq!(play(&mut cine(CineMode::Loop), 0, 3, 5), [1, 2, 0, 1, 2]);
+        assert_eq!(
+            play(&mut cine(CineMode::Bounce), 0, 3, 6),
+            [1, 2, 1, 0, 1, 2]
+        );
+        // both ends are reached every step with two slices
+        assert_eq!(play(&mut cine(CineMode::Loop), 0, 2, 4), [1, 0, 1, 0]);
+        assert_eq!(play(&mut cine(CineMode::Bounce), 0, 2, 4), [1, 0, 1, 0]);
+        assert_eq!(play(&mut cine(CineMode::Bounce), 1, 2, 3), [0, 1, 0]);
+    }
+
+    #[test]
+    fn steps_wait_for_the_interval() {
+        let mut cine = cine(CineMode::Loop);
+        assert_eq!(cine.advance(0.0, 0, 5), None);
+        assert_eq!(cine.advance(0.2, 0, 5), None);
+        // a late frame keeps the rate
+        assert_eq!(cine.advance(0.3, 0, 5), Some(1));
+        assert_eq!(cine.advance(0.5, 1, 5), Some(2));
+
+        // a stall steps once and restarts the interval
+        assert_eq!(cine.advance(10.0, 2, 5), Some(3));
+        assert_eq!(cine.advance(10.1, 3, 5), None);
+        assert_eq!(cine.advance(10.25, 3, 5), Some(4));
+    }
+
+    #[test]
+    fn nothing_plays_when_paused_or_on_a_single_slice() {
+        let mut cine = cine(CineMode::Loop);
+        assert_eq!(cine.advance(0.0, 0, 1), None);
+        assert_eq!(cine.advance(1.0, 0, 1), None);
+        cine.playing = false;
+        assert_eq!(cine.advance(2.0, 0, 5), None);
+        // playing again waits a full interval
+        cine.playing = true;
+        assert_eq!(cine.advance(3.0, 0, 5), None);
+        assert_eq!(cine.advance(3.25, 0, 5), Some(1));
+    }
+}
@@ -0,0 +1,116 @@
+use cgmath::{Point3, Vector3};
+
+/// 3D cursor shared by the slice views, each view shows the slice through it.
+///
+/// The position is given in mm relative to the corner of the volume.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor {
+    position: Point3<f32>,
+    dimensions: (u32, u32, u32),
+    spacing: (f32, f32, f32),
+}
+
+impl Cursor {
+    /// Places the cursor on the center voxel.
+    pub fn new(dimensions: (u32, u32, u32), spacing: (f32, f32, f32)) -> Self {
+        let mut cursor = Self {
+            position: Point3::new(0.0, 0.0, 0.0),
+            dimensions,
+            spacing,
+        };
+        for axis in 0..3 {
+            cursor.set_voxel(axis, cursor.dimension(axis) / 2);
+        }
+        cursor
+    }
+
+    pub fn position(&self) -> Point3<f32> {
+        self.position
+    }
+
+    /// Moves the cursor, keeping it inside the volume.
+    pub fn set_position(&mut self, position: Point3<f32>) {
+        let extent = self.extent();
+        self.position = Point3::new(
+            position.x.clamp(0.0, extent.x),
+            position.y.clamp(0.0, extent.y),
+            position.z.clamp(0.0, extent.z),
+        );
+    }
+
+    /// Index of the voxel the cursor is in along `axis` (0 = x, 1 = y, 2 = z).
+    pub fn voxel(&self, axis: usize) -> u32 {
+        let index = (self.position[axis] / self.spacing(axis)).floor() as u32;
+        index.min(self.dimension(axis).saturating_sub(1))
+    }
+
+    /// Moves the cursor to the center of voxel `index` along `axis`.
+    pub fn set_voxel(&mut self, axis: usize, index: u32) {
+        let index = index.min(self.dimension(axis).saturating_sub(1));
+        self.position[axis] = (index as f32 + 0.5) * self.spacing(axis);
+    }
+
+    /// Position in volume texture coordinates.
+    pub fn texture_coordinates(&self) -> Vector3<f32> {
+        let extent = self.extent();
+        Vector3::new(
+            self.position.x / extent.x,
+            self.position.y / extent.y,
+            self.position.z / extent.z,
+        )
+    }
+
+    fn extent(&self) -> Vector3<f32> {
+        Vector3::new(
+            self.dimensions.0 as f32 * self.spacing.0,
+            self.dimensions.1 as f32 * self.spacing.1,
+            self.dimensions.2 as f32 * self.spacing.2,
+        )
+    }
+
+    fn dimension(&self, axis: usize) -> u32 {
+        [self.dimensions.0, self.dimensions.1, self.dimensions.2][axis]
+    }
+
+    fn spacing(&self, axis: usize) -> f32 {
+        [self.spacing.0, self.spacing.1, self.spacing.2][axis]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMENSIONS: (u32, u32, u32) = (4, 5, 6);
+    const SPACING: (f32, f32, f32) = (0.5, 1.0, 2.0);
+
+    #[test]
+    fn voxels_round_trip_through_their_centers() {
+        let mut cursor = Cursor::new(DIMENSIONS, SPACING);
+        assert_eq!([0, 1, 2].map(|axis| cursor.voxel(axis)), [2, 2, 3]);
+        assert_eq!(cursor.position(), Point3::new(1.25, 2.5, 7.0));
+
+        for axis in 0..3 {
+            for index in 0..cursor.dimension(axis) {
+                cursor.set_voxel(axis, index);
+                assert_eq!(cursor.voxel(axis), index);
+                let center = (index as f32 + 0.5) * cursor.spacing(axis);
+                assert_eq!(cursor.position()[axis], center);
+            }
+        }
+    }
+
+    #[test]
+    fn cursor_stays_inside_the_volume() {
+        let mut cursor = Cursor::new(DIMENSIONS, SPACING);
+        cursor.set_voxel(0, 100);
+        assert_eq!(cursor.voxel(0), 3);
+        assert_eq!(cursor.position().x, 1.75);
+
+        cursor.set_position(Point3::new(-1.0, 10.0, 12.0));
+        assert_eq!(cursor.position(), Point3::new(0.0, 5.0, 12.0));
+        // the far faces belong to the last voxels
+        assert_eq!([0, 1, 2].map(|axis| cursor.voxel(axis)), [0, 4, 5]);
+        assert_eq!(cursor.texture_coordinates(), Vector3::new(0.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,177 @@
+use cgmath::{InnerSpace, Point3, Vector3};
+
+/// Path drawn in the axial view that the curved planar reformation follows.
+///
+/// Points are given in mm relative to the corner of the volume.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurvedPath {
+    pub points: Vec<Point3<f32>>,
+    /// Interpolate the points with a Catmull-Rom spline instead of straight lines.
+    pub smooth: bool,
+    /// Clicks in the axial view add points while set.
+    pub drawing: bool,
+}
+
+impl Default for CurvedPath {
+    fn default() -> Self {
+        Self {
+            points: Vec::new(),
+            smooth: true,
+            drawing: false,
+        }
+    }
+}
+
+impl CurvedPath {
+    /// Line segments per spline segment.
+    const SUBDIVISIONS: usize = 16;
+
+    pub fn is_empty(&self) -> bool {
+        self.points.len() < 2
+    }
+
+    /// The path as dense polyline, following the spline when `smooth` is set.
+    pub fn polyline(&self) -> Vec<Point3<f32>> {
+        if !self.smooth || self.points.len() < 3 {
+            return self.points.clone();
+        }
+
+        let point = |i: isize| self.points[i.clamp(0, self.points.len() as isize - 1) as usize];
+        let mut polyline = Vec::with_capacity((self.points.len() - 1) * Self::SUBDIVISIONS + 1);
+        for segment in 0..self.points.len() as isize - 1 {
+            let [p0, p1, p2, p3] = [-1, 0, 1, 2].map(|offset| point(segment + offset));
+            for step in 0..Self::SUBDIVISIONS {
+                let t = step as f32 / Self::SUBDIVISIONS as f32;
+                polyline.push(catmull_rom(p0, p1, p2, p3, t));
+            }
+        }
+        polyline.push(point(self.points.len() as isize - 1));
+        polyline
+    }
+
+    /// Length of the path in mm.
+    pub fn length(&self) -> f32 {
+        self.polyline()
+            .windows(2)
+            .map(|segment| (segment[1] - segment[0]).magnitude())
+            .sum()
+    }
+
+    /// Points along the path, `spacing` mm apart.
+    pub fn resample(&self, spacing: f32) -> Vec<[f32; 3]> {
+        let polyline = self.polyline();
+        let Some(&first) = polyline.first() else {
+            return Vec::new();
+        };
+
+        let mut samples = vec![first.into()];
+        // distance from the last sample to the start of the current segment
+        let mut covered = 0.0;
+        for segment in polyline.windows(2) {
+            let direction: Vector3<f32> = segment[1] - segment[0];
+            let length = direction.magnitude();
+            let mut along = spacing - covered;
+            while along <= length {
+                samples.push((segment[0] + direction * (along / length)).into());
+                along += spacing;
+            }
+            covered = length - (along - spacing);
+        }
+        samples
+    }
+}
+
+/// Uniform Catmull-Rom spline between `p1` and `p2`.
+fn catmull_rom(
+    p0: Point3<f32>,
+    p1: Point3<f32>,
+    p2: Point3<f32>,
+    p3: Point3<f32>,
+    t: f32,
+) -> Point3<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let [v0, v1, v2, v3] = [p0, p1, p2, p3].map(|p| p - Point3::new(0.0, 0.0, 0.0));
+    let v = (v1 * 2.0
+        + (v2 - v0) * t
+        + (v0 * 2.0 - v1 * 5.0 + v2 * 4.0 - v3) * t2
+        + (v1 * 3.0 - v0 - v2 * 3.0 + v3) * t3)
+        * 0.5;
+    Point3::new(0.0, 0.0, 0.0) + v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(points: &[[f32; 3]], smooth: bool) -> CurvedPath {
+        CurvedPath {
+            points: points.iter().map(|&point| Point3::from(point)).collect(),
+            smooth,
+            drawing: false,
+        }
+    }
+
+    fn assert_close(samples: &[[f32; 3]], expected: &[[f32; 3]]) {
+        assert_eq!(samples.len(), expected.len(), "{samples:?}");
+        for (sample, expected) in samples.iter().zip(expected) {
+            let distance = (Point3::from(*sample) - Point3::from(*expected)).magnitude();
+            assert!(distance < 1e-5, "{samples:?}");
+        }
+    }
+
+    #[test]
+    fn resampling_keeps_the_spacing_across_corners() {
+        let path = path(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 3.0, 0.0]], false);
+        assert_eq!(path.length(), 5.0);
+        assert_close(
+            &path.resample(1.5),
+            &[
+                [0.0, 0.0, 0.0],
+                [1.5, 0.0, 0.0],
+                [2.0, 1.0, 0.0],
+                [2.0, 2.5, 0.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn resampling_ends_on_the_last_point_only_at_a_full_step() {
+        let line = path(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0]], false);
+        // the rest of 1 mm is shorter than a step
+        assert_close(
+            &line.resample(3.0),
+            &[
+                [0.0, 0.0, 0.0],
+                [3.0, 0.0, 0.0],
+                [6.0, 0.0, 0.0],
+                [9.0, 0.0, 0.0],
+            ],
+        );
+        let samples = line.resample(2.5);
+        assert_eq!(samples.len(), 5);
+        assert_eq!(samples[4], [10.0, 0.0, 0.0]);
+
+        assert!(path(&[], true).resample(1.0).is_empty());
+        assert_eq!(
+            path(&[[1.0, 2.0, 3.0]], true).resample(1.0),
+            vec![[1.0, 2.0, 3.0]]
+        );
+    }
+
+    #[test]
+    fn smooth_polyline_passes_through_the_points() {
+        let points = [[0.0, 0.0, 0.0], [4.0, 4.0, 0.0], [8.0, 0.0, 0.0]];
+        let straight = path(&points, false).polyline();
+        assert_eq!(straight, path(&points, false).points);
+
+        let polyline = path(&points, true).polyline();
+        assert_eq!(polyline.len(), 2 * CurvedPath::SUBDIVISIONS + 1);
+        for (i, point) in points.iter().enumerate() {
+            assert_eq!(polyline[i * CurvedPath::SUBDIVISIONS], Point3::from(*point));
+        }
+        // the spline bulges out beyond the straight corner
+        let middle = polyline[CurvedPath::SUBDIVISIONS / 2];
+        assert!(middle.y > 2.0 && middle.x < 2.0, "{middle:?}");
+    }
+}
@@ -0,0 +1,244 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    data::{Image, TransferFunction, Volume},
+    processing::ReformationMode,
+};
+
+use super::{curved_path::CurvedPath, settings_overlay::settings_overlay};
+
+/// Everything the reformatted image depends on, to recompute it only on changes.
+#[derive(Clone, PartialEq)]
+struct ImageInputs {
+    points: Vec<cgmath::Point3<f32>>,
+    smooth: bool,
+    mode: ReformationMode,
+    height: f32,
+}
+
+/// Curved planar reformation along the path drawn in the axial view.
+///
+/// The image is resampled on the CPU from the original voxel values, so it can
+/// be exported as is.
+pub struct CurvedReformationView {
+    volume: Rc<Volume>,
+    curved_path: Rc<RefCell<CurvedPath>>,
+    transfer_function: Rc<RefCell<TransferFunction>>,
+    mode: ReformationMode,
+    /// Height of the image in mm.
+    height: f32,
+    use_transfer_function: bool,
+    image: Image,
+    inputs: Option<ImageInputs>,
+    texture: Option<egui::TextureHandle>,
+    /// Transfer function the texture was colored with.
+    texture_transfer_function: Option<TransferFunction>,
+    #[cfg(not(target_arch = "wasm32"))]
+    export_format: crate::io::ImageFileFormat,
+    pub show_settings_oberlay: bool,
+}
+
+impl CurvedReformationView {
+    pub fn new(
+        volume: Rc<Volume>,
+        curved_path: Rc<RefCell<CurvedPath>>,
+        transfer_function: Rc<RefCell<TransferFunction>>,
+    ) -> Self {
+        let height = volume.dimensions.2 as f32 * volume.spacing.2;
+        Self {
+            volume,
+            curved_path,
+            transfer_function,
+            mode: ReformationMode::Stretched,
+            height,
+            use_transfer_function: false,
+            image: Image::default(),
+            inputs: None,
+            texture: None,
+            texture_transfer_function: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            export_format: crate::io::ImageFileFormat::Pgm,
+            show_settings_oberlay: true,
+        }
+    }
+
+    /// Pixel size in mm, the finest voxel spacing.
+    fn pixel_spacing(&self) -> f32 {
+        let (x, y, z) = self.volume.spacing;
+        x.min(y).min(z)
+    }
+
+    /// Resamples the image if the path or the settings changed.
+    fn update_image(&mut self) {
+        let path = self.curved_path.borrow();
+        let inputs = ImageInputs {
+            points: path.points.clone(),
+            smooth: path.smooth,
+            mode: self.mode,
+            height: self.height,
+        };
+        if self.inputs.as_ref() == Some(&inputs) {
+            return;
+        }
+
+        let image = if path.is_empty() {
+            Image::default()
+        } else {
+            let spacing = self.pixel_spacing();
+            crate::processing::curved_reformation(
+                &self.volume,
+                &path.resample(spacing),
+                self.mode,
+                self.height,
+                spacing,
+            )
+        };
+        drop(path);
+        self.image = image;
+        self.inputs = Some(inputs);
+        self.texture = None;
+    }
+
+    /// Display colors of the image, the CPU counterpart of the slice shader.
+    fn colors(&self) -> Vec<[u8; 3]> {
+        let transfer_function = self.transfer_function.borrow();
+        self.image
+            .values
+            .iter()
+            .map(|&value| {
+                if self.use_transfer_function {
+                    let (color, opacity) = transfer_function.sample(value);
+                    let opacity = opacity.clamp(0.0, 1.0);
+                    color.map(|c| (c as f32 * opacity).round() as u8)
+                } else {
+                    [(value / u16::MAX as f32 * 255.0).round() as u8; 3]
+                }
+            })
+            .collect()
+    }
+
+    fn texture(&mut self, ctx: &egui::Context) -> Option<&egui::TextureHandle> {
+        let transfer_function =
+            Some(self.transfer_function.borrow().clone()).filter(|_| self.use_transfer_function);
+        if transfer_function != self.texture_transfer_function {
+            self.texture = None;
+        }
+
+        if self.texture.is_none() && !self.image.is_empty() {
+            let (width, height) = self.image.dimensions;
+            let pixels = self
+                .colors()
+                .into_iter()
+                .map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))
+                .collect();
+            let image = egui::ColorImage {
+                size: [width as usize, height as usize],
+                pixels,
+            };
+            self.texture =
+                Some(ctx.load_texture("curved_reformation", image, egui::TextureOptions::LINEAR));
+            self.texture_transfer_function = transfer_function;
+        }
+        self.texture.as_ref()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_image(&self) {
+        let format = self.export_format;
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(format.label(), &[format.extension()])
+            .set_file_name(&format!("curved_reformation.{}", format.extension()))
+            .save_file()
+        else {
+            return;
+        };
+
+        if let Err(err) = crate::io::export_image(&path, &self.image, format, &self.colors()) {
+            log::error!("Failed to export image: {err}");
+        }
+    }
+
+    pub fn custom_painting(&mut self, ui: &mut egui::Ui) {
+        let availbale_size = ui.available_size_before_wrap();
+        let (rect, _response) = ui.allocate_exact_size(availbale_size, egui::Sense::hover());
+
+        self.update_image();
+        let (width, height) = self.image.dimensions;
+        if let Some(texture) = self.texture(ui.ctx()) {
+            // fit the image into the view, pixels are square
+            let image_size = egui::vec2(width as f32, height as f32);
+            let scale = (rect.width() / image_size.x).min(rect.height() / image_size.y);
+            let image_rect = egui::Rect::from_center_size(rect.center(), image_size * scale);
+            ui.painter_at(rect).image(
+                texture.id(),
+                image_rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+        } else {
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Draw a path in the axial view",
+                egui::FontId::proportional(14.0),
+                egui::Color32::GRAY,
+            );
+        }
+
+        // Paint overlay
+        if self.show_settings_oberlay {
+            let max_height = {
+                let (x, y, z) = self.volume.dimensions;
+                let (sx, sy, sz) = self.volume.spacing;
+                (x as f32 * sx).hypot(y as f32 * sy).hypot(z as f32 * sz)
+            };
+            let min_height = self.pixel_spacing();
+            settings_overlay(ui, rect, |ui| {
+                egui::ComboBox::from_label("Mode")
+                    .selected_text(self.mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in ReformationMode::ALL {
+                            ui.selectable_value(&mut self.mode, mode, mode.label());
+                        }
+                    });
+                ui.add(
+                    egui::Slider::new(&mut self.height, min_height..=max_height)
+                        .text("Height [mm]"),
+                );
+                ui.checkbox(&mut self.use_transfer_function, "Apply Transfer Function");
+                ui.label(format!(
+                    "Path Length: {:.1} mm",
+                    self.curved_path.borrow().length()
+                ));
+                if !self.image.is_empty() {
+                    ui.label(format!(
+                        "{width} x {height} pixels of {:.2} mm",
+                        self.image.spacing.0
+                    ));
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    use crate::io::ImageFileFormat;
+
+                    ui.separator();
+                    ui.add_enabled_ui(!self.image.is_empty(), |ui| {
+                        egui::ComboBox::from_label("Format")
+                            .selected_text(self.export_format.label())
+                            .show_ui(ui, |ui| {
+                                for format in ImageFileFormat::ALL {
+                                    ui.selectable_value(
+                                        &mut self.export_format,
+                                        format,
+                                        format.label(),
+                                    );
+                                }
+                            });
+                        if ui.button("Export...").clicked() {
+                            self.export_image();
+                        }
+                    });
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::rc::Rc;
+
+use crate::{
+    data::{SliceOrientation, Volume, WindowLevel},
+    processing::ValueStatistics,
+};
+
+use super::{histogram_plot::histogram_plot, statistics_task::StatisticsTask, SharedSliceState};
+
+#[derive(Clone, Copy, PartialEq)]
+enum HistogramScale {
+    Linear,
+    Logarithmic,
+}
+
+/// Values the statistics are computed from.
+#[derive(Clone, Copy, PartialEq)]
+enum Source {
+    Volume,
+    /// The slice through the cursor.
+    Slice(SliceOrientation),
+}
+
+impl Source {
+    const ALL: [Self; 4] = [
+        Self::Volume,
+        Self::Slice(SliceOrientation::Axial),
+        Self::Slice(SliceOrientation::Coronal),
+        Self::Slice(SliceOrientation::Sagittal),
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Volume => "Whole volume",
+            Self::Slice(SliceOrientation::Axial) => "Axial slice",
+            Self::Slice(SliceOrientation::Coronal) => "Coronal slice",
+            Self::Slice(SliceOrientation::Sagittal) => "Sagittal slice",
+        }
+    }
+}
+
+/// Histogram and statistics of the volume values. Clicking the histogram
+/// centers the grayscale window of the slice views on a value, dragging
+/// selects the window.
+pub struct HistogramPanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+    scale: HistogramScale,
+    bin_count: usize,
+    source: Source,
+    /// Orientation and index of the slice counted last.
+    requested: Option<(SliceOrientation, u32)>,
+    statistics: Option<Rc<ValueStatistics>>,
+    /// Counts the values of single slices, the volume statistics are shared.
+    task: StatisticsTask,
+    /// Value the window drag started at.
+    drag_start: Option<f32>,
+}
+
+impl HistogramPanel {
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        let mut panel = Self {
+            volume,
+            shared,
+            scale: HistogramScale::Logarithmic,
+            bin_count: 256,
+            source: Source::Volume,
+            requested: None,
+            statistics: None,
+            task: StatisticsTask::default(),
+            drag_start: None,
+        };
+        panel.update_statistics();
+        panel
+    }
+
+    fn slice_values(&self, orientation: SliceOrientation, index: u32) -> Vec<u16> {
+        let (width, height, depth) = self.volume.dimensions;
+        let mut ranges = [0..width, 0..height, 0..depth];
+        ranges[orientation.normal_axis()] = index..index + 1;
+        let mut values = Vec::new();
+        for z in ranges[2].clone() {
+            for y in ranges[1].clone() {
+                for x in ranges[0].clone() {
+                    values.push(self.volume.value(x, y, z));
+                }
+            }
+        }
+        values
+    }
+
+    /// Takes the shared volume statistics, or starts counting the slice
+    /// values when the shown slice changed.
+    fn update_statistics(&mut self) {
+        let Source::Slice(orientation) = self.source else {
+            self.requested = None;
+            self.statistics = self.shared.volume_statistics.borrow_mut().get();
+            return;
+        };
+        let slice = self.shared.cursor.borrow().voxel(orientation.normal_axis());
+        if self.requested != Some((orientation, slice)) {
+            self.requested = Some((orientation, slice));
+            self.task
+                .start(self.slice_values(orientation, slice).into());
+        }
+        if let Some(statistics) = self.task.finished() {
+            self.statistics = Some(Rc::new(statistics));
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.update_statistics();
+        let computing = match self.source {
+            Source::Volume => self.shared.volume_statistics.borrow().is_running(),
+            Source::Slice(_) => self.task.is_running(),
+        };
+        if computing {
+            ui.ctx().request_repaint();
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("histogram_source")
+                .selected_text(self.source.label())
+                .show_ui(ui, |ui| {
+                    for source in Source::ALL {
+                        ui.selectable_value(&mut self.source, source, source.label());
+                    }
+                });
+            ui.selectable_value(&mut self.scale, HistogramScale::Linear, "Linear");
+            ui.selectable_value(&mut self.scale, HistogramScale::Logarithmic, "Log");
+            ui.label("Bins:");
+            ui.add(egui::DragValue::new(&mut self.bin_count).clamp_range(2..=4096));
+            if computing {
+                ui.spinner();
+            }
+        });
+        self.window_settings(ui);
+        ui.separator();
+
+        let Some(statistics) = &self.statistics else {
+            ui.label("Computing statistics...");
+            return;
+        };
+        egui::Grid::new("value_statistics")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                let mut cells = vec![
+                    ("Count".to_owned(), statistics.count.to_string()),
+                    ("Zeros".to_owned(), statistics.zeros.to_string()),
+                    ("Min".to_owned(), statistics.min.to_string()),
+                    ("Max".to_owned(), statistics.max.to_string()),
+                    ("Mean".to_owned(), format!("{:.2}", statistics.mean)),
+                    ("Std".to_owned(), format!("{:.2}", statistics.std)),
+                ];
+                for (percent, value) in ValueStatistics::PERCENTILES
+                    .iter()
+                    .zip(statistics.percentiles)
+                {
+                    let label = if *percent == 50.0 {
+                        "Median".to_owned()
+                    } else {
+                        format!("P{percent}")
+                    };
+                    cells.push((label, format!("{value:.1}")));
+                }
+                // the values are 16 bit integers
+                cells.push(("NaNs".to_owned(), "0".to_owned()));
+                for row in cells.chunks(2) {
+                    for (label, value) in row {
+                        ui.label(label);
+                        ui.label(value);
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+
+        let histogram = statistics.histogram(self.bin_count);
+        let heights = match self.scale {
+            HistogramScale::Linear => histogram.linear_heights(),
+            HistogramScale::Logarithmic => histogram.log_heights(),
+        };
+        let mut window = self.shared.window.get();
+        let input = histogram_plot(
+            ui,
+            &histogram,
+            heights,
+            window.range(),
+            &mut self.drag_start,
+            "Click to center the window, drag to select it",
+        );
+        if let Some((lower, upper)) = input.dragged {
+            window = WindowLevel::from_range(lower, upper);
+        }
+        if let Some(value) = input.clicked {
+            window.level = value;
+        }
+        self.shared.window.set(window);
+    }
+
+    fn window_settings(&mut self, ui: &mut egui::Ui) {
+        let mut window = self.shared.window.get();
+        ui.horizontal(|ui| {
+            ui.label("Level:");
+            ui.add(egui::DragValue::new(&mut window.level));
+            ui.label("Width:");
+            ui.add(egui::DragValue::new(&mut window.width).clamp_range(1.0..=f32::MAX));
+            if ui
+                .button("Full range")
+                .on_hover_text("Show all 16 bit values")
+                .clicked()
+            {
+                window = WindowLevel::default();
+            }
+            if let Some(statistics) = &self.statistics {
+                if ui
+                    .button("Min to max")
+                    .on_hover_text("Fit the window to the values of the selection")
+                    .clicked()
+                {
+                    window = WindowLevel::from_range(statistics.min as f32, statistics.max as f32);
+                }
+            }
+        });
+        self.shared.window.set(window);
+    }
+}
@@ -0,0 +1,79 @@
+use egui::{Pos2, Rect, Sense};
+
+use crate::data::Histogram;
+
+/// What the pointer did on a histogram plot, in values.
+#[derive(Default)]
+pub(super) struct HistogramInput {
+    pub clicked: Option<f32>,
+    /// Lower and upper value of the range dragged over so far.
+    pub dragged: Option<(f32, f32)>,
+}
+
+/// Histogram bars over a shaded band of selected values, filling the space
+/// left in `ui`. `heights` are the bar heights from 0 to 1, `drag_start`
+/// keeps the value a drag started at between frames and `hint` explains
+/// the pointer actions below the hovered count.
+pub(super) fn histogram_plot(
+    ui: &mut egui::Ui,
+    histogram: &Histogram,
+    heights: Vec<f32>,
+    band: (f32, f32),
+    drag_start: &mut Option<f32>,
+    hint: &str,
+) -> HistogramInput {
+    let size = ui.available_size_before_wrap().max(egui::vec2(64.0, 64.0));
+    let (rect, response) = ui.allocate_exact_size(size, Sense::click_and_drag());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+
+    let value_range = histogram.min..=histogram.max.max(histogram.min + 1.0);
+    let to_value = |pos: Pos2| egui::remap_clamp(pos.x, rect.x_range(), value_range.clone());
+    let to_x = |value: f32| egui::remap(value, value_range.clone(), rect.x_range());
+
+    // selection as shaded band behind the bars
+    let band = Rect::from_x_y_ranges(to_x(band.0)..=to_x(band.1), rect.y_range());
+    painter.rect_filled(
+        band.intersect(rect),
+        0.0,
+        visuals.selection.bg_fill.linear_multiply(0.4),
+    );
+
+    let bar_width = rect.width() / heights.len() as f32;
+    for (i, height) in heights.into_iter().enumerate() {
+        let left = rect.left() + i as f32 * bar_width;
+        painter.rect_filled(
+            Rect::from_min_max(
+                Pos2::new(left, rect.bottom() - height * rect.height()),
+                Pos2::new(left + bar_width, rect.bottom()),
+            ),
+            0.0,
+            visuals.widgets.inactive.fg_stroke.color,
+        );
+    }
+
+    let mut input = HistogramInput::default();
+    if response.drag_started() {
+        *drag_start = response.interact_pointer_pos().map(to_value);
+    }
+    if let (Some(start), Some(pos)) = (*drag_start, response.interact_pointer_pos()) {
+        if response.dragged() {
+            let end = to_value(pos);
+            input.dragged = Some((start.min(end), start.max(end)));
+        }
+    }
+    if response.drag_released() {
+        *drag_start = None;
+    }
+    if response.clicked() {
+        input.clicked = response.interact_pointer_pos().map(to_value);
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let value = to_value(pos);
+        let count = histogram.bin(value).map_or(0, |bin| histogram.bins[bin]);
+        response.on_hover_text_at_pointer(format!("Value {value:.0}: {count} voxels\n{hint}"));
+    }
+    input
+}
@@ -0,0 +1,324 @@
+use eframe::{
+    egui_wgpu::wgpu::util::DeviceExt,
+    egui_wgpu::{self, wgpu},
+};
+
+use super::labels::{Labels, COLOR_TABLE_WIDTH};
+use crate::data::{Label, LabelMap};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct LabelOverlayUniform {
+    opacity: f32,
+    outline: i32,
+    enabled: i32,
+    preview_label: u32,
+    preview_color: [f32; 4],
+}
+
+/// GPU copy of the label map shared by all slice views, bound as group 3 of
+/// the slice shader.
+///
+/// Labels are read without filtering from an `R8Uint` or `R16Uint` texture,
+/// depending on the largest label, and colored by a table with one texel per
+/// label value. While a threshold is previewed the texture holds the preview
+/// map instead, with its label drawn in the preview color.
+pub struct LabelOverlay {
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    texture: wgpu::Texture,
+    /// Bytes per label in `texture`.
+    label_size: u32,
+    color_table: wgpu::Texture,
+    uniform_buffer: wgpu::Buffer,
+    /// Generation of the uploaded label map.
+    generation: u64,
+    /// Labels the color table was written for.
+    items: Vec<Label>,
+    uniform: LabelOverlayUniform,
+}
+
+impl LabelOverlay {
+    /// Stores an empty overlay with the paint callback resources, where the
+    /// slice views find it.
+    pub fn register(wgpu_render_state: &egui_wgpu::RenderState) {
+        let overlay = Self::new(&wgpu_render_state.device, &wgpu_render_state.queue);
+        wgpu_render_state
+            .renderer
+            .write()
+            .paint_callback_resources
+            .insert(overlay);
+    }
+
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("label_overlay_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D3,
+                        sample_type: wgpu::TextureSampleType::Uint,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let color_table = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Label Colors"),
+            size: Self::color_table_size(),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let uniform = LabelOverlayUniform {
+            opacity: 0.0,
+            outline: 0,
+            enabled: 0,
+            preview_label: 0,
+            preview_color: [0.0; 4],
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Label Overlay"),
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+        });
+
+        let texture = Self::create_texture(device, None, 1);
+        let bind_group = Self::create_bind_group(
+            device,
+            &bind_group_layout,
+            &texture,
+            &color_table,
+            &uniform_buffer,
+        );
+        let overlay = Self {
+            bind_group_layout,
+            bind_group,
+            texture,
+            label_size: 1,
+            color_table,
+            uniform_buffer,
+            generation: 0,
+            items: Vec::new(),
+            uniform,
+        };
+        overlay.write_colors(queue, &Labels::default());
+        overlay
+    }
+
+    /// Uploads what changed in `labels` since the last call, edits only
+    /// rewrite the changed slices.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, labels: &mut Labels) {
+        let preview_label = labels.preview_label();
+        let label_size = Self::label_size(labels.max_label.max(preview_label.unwrap_or(0)));
+        if self.generation != labels.generation || self.label_size < label_size {
+            self.generation = labels.generation;
+            self.label_size = label_size;
+            self.texture = Self::create_texture(device, labels.shown_map(), label_size);
+            if let Some(map) = labels.shown_map() {
+                let (_, _, depth) = map.dimensions;
+                self.write_slices(queue, map, 0, depth.saturating_sub(1));
+            }
+            labels.changed = None;
+            self.bind_group = Self::create_bind_group(
+                device,
+                &self.bind_group_layout,
+                &self.texture,
+                &self.color_table,
+                &self.uniform_buffer,
+            );
+        }
+        let changed = labels.changed.take();
+        if let (Some(map), Some((first, last))) = (labels.shown_map(), changed) {
+            self.write_slices(queue, map, first, last);
+        }
+
+        if self.items != labels.items {
+            self.items = labels.items.clone();
+            self.write_colors(queue, labels);
+        }
+
+        let preview_color = preview_label.map_or([0; 3], |value| {
+            labels
+                .label(value)
+                .map_or_else(|| Label::new(value).color, |label| label.color)
+        });
+        let uniform = LabelOverlayUniform {
+            opacity: labels.opacity,
+            outline: labels.outline as i32,
+            enabled: (labels.shown && labels.map.is_some()) as i32,
+            preview_label: preview_label.unwrap_or(0) as u32,
+            // written like the color table, which is not sRGB
+            preview_color: [
+                preview_color[0] as f32 / 255.0,
+                preview_color[1] as f32 / 255.0,
+                preview_color[2] as f32 / 255.0,
+                1.0,
+            ],
+        };
+        if self.uniform != uniform {
+            self.uniform = uniform;
+            queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+        }
+    }
+
+    fn color_table_size() -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width: COLOR_TABLE_WIDTH,
+            height: (u16::MAX as u32 + 1) / COLOR_TABLE_WIDTH,
+            depth_or_array_layers: 1,
+        }
+    }
+
+    fn label_size(max_label: u16) -> u32 {
+        if max_label > u8::MAX as u16 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Texture sized for `map`, a single background voxel without one.
+    fn create_texture(
+        device: &wgpu::Device,
+        map: Option<&LabelMap>,
+        label_size: u32,
+    ) -> wgpu::Texture {
+        let dimensions = map.map_or((1, 1, 1), |map| map.dimensions);
+        let format = if label_size == 2 {
+            wgpu::TextureFormat::R16Uint
+        } else {
+            wgpu::TextureFormat::R8Uint
+        };
+        let (width, height, depth) = dimensions;
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: depth,
+        };
+        // wgpu zero-initializes textures, which is background
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Label Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        })
+    }
+
+    /// Uploads the slices `first` to `last` along z.
+    fn write_slices(&self, queue: &wgpu::Queue, map: &LabelMap, first: u32, last: u32) {
+        let (width, height, _) = map.dimensions;
+        let values = &map.values[map.index(0, 0, first)..map.index(0, 0, last + 1)];
+        let bytes: Vec<u8> = if self.label_size == 2 {
+            values
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect()
+        } else {
+            values.iter().map(|&value| value as u8).collect()
+        };
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: first,
+                },
+            },
+            &bytes,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(self.label_size * width),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: last - first + 1,
+            },
+        );
+    }
+
+    fn write_colors(&self, queue: &wgpu::Queue, labels: &Labels) {
+        let size = Self::color_table_size();
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture: &self.color_table,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            &labels.color_table(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * size.width),
+                rows_per_image: Some(size.height),
+            },
+            size,
+        );
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        texture: &wgpu::Texture,
+        color_table: &wgpu::Texture,
+        uniform_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let color_view = color_table.create_view(&wgpu::TextureViewDescriptor::default());
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("label_overlay_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&color_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+}
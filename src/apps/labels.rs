@@ -0,0 +1,584 @@
+use std::rc::Rc;
+
+use cgmath::{MetricSpace, Point3};
+
+use crate::{
+    data::{Label, LabelMap, SliceOrientation, Volume},
+    processing::{
+        apply_mask, apply_threshold, connected_components, fill_slice, grow_region,
+        interpolate_slices, paint_brush, relabel_components, ChangedSlices, Components,
+        Connectivity, RegionGrowing,
+    },
+};
+
+use super::{slice_renderer::PointTool, SharedSliceState};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LabelTool {
+    Brush,
+    Eraser,
+    /// Fills the connected region under the pointer in the shown slice.
+    Fill,
+    /// Grows a region in 3D from the voxel under the pointer.
+    Grow,
+}
+
+impl LabelTool {
+    pub const ALL: [Self; 4] = [Self::Brush, Self::Eraser, Self::Fill, Self::Grow];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Brush => "Brush",
+            Self::Eraser => "Eraser",
+            Self::Fill => "Fill",
+            Self::Grow => "Grow",
+        }
+    }
+}
+
+/// `map` with a threshold committed to a copy, computed on the CPU so the
+/// preview selects exactly the voxels a commit would.
+struct ThresholdPreview {
+    range: (u16, u16),
+    label: u16,
+    overwrite: bool,
+    map: LabelMap,
+}
+
+/// Label map shown on top of the slice views, its display settings and the
+/// tools editing it.
+pub struct Labels {
+    pub map: Option<LabelMap>,
+    /// Display settings of the labels in `map`, sorted by value.
+    pub items: Vec<Label>,
+    pub shown: bool,
+    pub opacity: f32,
+    /// Draw only the borders of the labeled regions.
+    pub outline: bool,
+    /// Bumped when `map` is replaced, so the overlay uploads it anew.
+    pub generation: u64,
+    /// Slices edited since the overlay uploaded `map`.
+    pub changed: ChangedSlices,
+    /// Largest label value in `map`, decides the texture format.
+    pub max_label: u16,
+    /// Editing tool used in the slice views, `None` when clicks move the
+    /// cursor instead.
+    pub tool: Option<LabelTool>,
+    /// Label the brush and fill write.
+    pub active: u16,
+    /// Brush radius in mm.
+    pub brush_radius: f32,
+    /// Paint spheres instead of discs in the shown slice.
+    pub brush_3d: bool,
+    /// Only edit voxels with values in `value_range`.
+    pub constrain: bool,
+    pub value_range: (u16, u16),
+    /// Settings of the grow tool.
+    pub growing: RegionGrowing,
+    /// Shown instead of `map` while a threshold is being chosen.
+    threshold_preview: Option<ThresholdPreview>,
+    /// Brush position of the stroke being painted.
+    stroke: Option<Point3<f32>>,
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self {
+            map: None,
+            items: Vec::new(),
+            shown: true,
+            opacity: 0.5,
+            outline: false,
+            generation: 0,
+            changed: None,
+            max_label: 0,
+            tool: None,
+            active: 1,
+            brush_radius: 3.0,
+            brush_3d: false,
+            constrain: false,
+            value_range: (0, u16::MAX),
+            growing: RegionGrowing::default(),
+            threshold_preview: None,
+            stroke: None,
+        }
+    }
+}
+
+impl Labels {
+    /// Shows `map` with a label entry for every value in it.
+    pub fn set_map(&mut self, map: LabelMap) {
+        self.items = map.present_labels().into_iter().map(Label::new).collect();
+        self.max_label = self.items.last().map_or(0, |label| label.value);
+        self.map = Some(map);
+        self.threshold_preview = None;
+        self.generation += 1;
+        self.changed = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.map = None;
+        self.threshold_preview = None;
+        self.items.clear();
+        self.max_label = 0;
+        self.generation += 1;
+        self.changed = None;
+    }
+
+    /// Starts an empty label map unless one with `dimensions` exists.
+    pub fn ensure_map(&mut self, dimensions: (u32, u32, u32)) {
+        if self.map.as_ref().map(|map| map.dimensions) != Some(dimensions) {
+            self.set_map(LabelMap::new(dimensions));
+        }
+    }
+
+    /// Map the overlay shows, the threshold preview while there is one.
+    pub fn shown_map(&self) -> Option<&LabelMap> {
+        match &self.threshold_preview {
+            Some(preview) => Some(&preview.map),
+            None => self.map.as_ref(),
+        }
+    }
+
+    /// Label the threshold preview writes, if one is shown.
+    pub fn preview_label(&self) -> Option<u16> {
+        self.threshold_preview.as_ref().map(|preview| preview.label)
+    }
+
+    /// Shows what committing `range` with the active label would write,
+    /// recomputed only when the settings changed.
+    pub fn preview_threshold(&mut self, volume: &Volume, range: (u16, u16), overwrite: bool) {
+        if let Some(preview) = &self.threshold_preview {
+            if (preview.range, preview.label, preview.overwrite) == (range, self.active, overwrite)
+            {
+                return;
+            }
+        }
+        let mut map = self
+            .map
+            .clone()
+            .filter(|map| map.dimensions == volume.dimensions)
+            .unwrap_or_else(|| LabelMap::new(volume.dimensions));
+        apply_threshold(&mut map, volume, range, self.active, overwrite);
+        self.threshold_preview = Some(ThresholdPreview {
+            range,
+            label: self.active,
+            overwrite,
+            map,
+        });
+        self.generation += 1;
+    }
+
+    pub fn clear_threshold_preview(&mut self) {
+        if self.threshold_preview.take().is_some() {
+            self.generation += 1;
+        }
+    }
+
+    pub fn label(&self, value: u16) -> Option<&Label> {
+        self.items
+            .binary_search_by_key(&value, |label| label.value)
+            .ok()
+            .map(|index| &self.items[index])
+    }
+
+    /// RGBA colors of all label values in rows of `COLOR_TABLE_WIDTH`, hidden
+    /// labels and background are transparent.
+    pub fn color_table(&self) -> Vec<u8> {
+        let mut table = vec![0; 4 * (u16::MAX as usize + 1)];
+        for label in self.items.iter().filter(|label| label.visible) {
+            let texel = 4 * label.value as usize;
+            table[texel..texel + 3].copy_from_slice(&label.color);
+            table[texel + 3] = 255;
+        }
+        table
+    }
+
+    fn value_range(&self) -> Option<(u16, u16)> {
+        self.constrain.then_some(self.value_range)
+    }
+
+    /// Records an edit writing `label`, so the overlay uploads the changed
+    /// slices and the label gets an entry.
+    fn edited(&mut self, changed: ChangedSlices, label: u16) {
+        let Some((first, last)) = changed else {
+            return;
+        };
+        // the preview is computed anew from the edited map
+        self.clear_threshold_preview();
+        self.changed = Some(match self.changed {
+            Some((previous_first, previous_last)) => {
+                (previous_first.min(first), previous_last.max(last))
+            }
+            None => (first, last),
+        });
+        if label != 0 {
+            if let Err(index) = self.items.binary_search_by_key(&label, |item| item.value) {
+                self.items.insert(index, Label::new(label));
+            }
+            self.max_label = self.max_label.max(label);
+        }
+    }
+
+    /// Paints with the brush or eraser from the previous position of the
+    /// stroke to `position`, in the slice normal to `slice_axis` unless the
+    /// brush is 3D.
+    pub fn paint(&mut self, volume: &Volume, position: Point3<f32>, slice_axis: usize) {
+        let label = match self.tool {
+            Some(LabelTool::Brush) => self.active,
+            Some(LabelTool::Eraser) => 0,
+            _ => return,
+        };
+        let (radius, range) = (self.brush_radius, self.value_range());
+        self.ensure_map(volume.dimensions);
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let slice_axis = (!self.brush_3d).then_some(slice_axis);
+
+        // dabs close enough to leave no gaps when the pointer moves fast
+        let start = self.stroke.unwrap_or(position);
+        let step =
+            (radius / 2.0).max(0.5 * volume.spacing.0.min(volume.spacing.1).min(volume.spacing.2));
+        let dabs = (start.distance(position) / step).ceil().max(1.0) as u32;
+        let mut changed = None;
+        for dab in 1..=dabs {
+            let center = start + (position - start) * (dab as f32 / dabs as f32);
+            let dab_changed = paint_brush(map, volume, center, radius, slice_axis, label, range);
+            changed = match (changed, dab_changed) {
+                (Some((first, last)), Some((dab_first, dab_last))) => {
+                    Some((u32::min(first, dab_first), u32::max(last, dab_last)))
+                }
+                (changed, dab_changed) => changed.or(dab_changed),
+            };
+        }
+        self.stroke = Some(position);
+        self.edited(changed, label);
+    }
+
+    pub fn end_stroke(&mut self) {
+        self.stroke = None;
+    }
+
+    /// Fills the region under `position` in the slice normal to `normal_axis`.
+    pub fn fill(&mut self, volume: &Volume, position: Point3<f32>, normal_axis: usize) {
+        let range = self.value_range();
+        self.ensure_map(volume.dimensions);
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let seed = Self::voxel(volume, position);
+        let changed = fill_slice(map, volume, seed, normal_axis, self.active, range);
+        self.edited(changed, self.active);
+    }
+
+    /// Voxel containing `position`, clamped to the volume.
+    fn voxel(volume: &Volume, position: Point3<f32>) -> [u32; 3] {
+        let (width, height, depth) = volume.dimensions;
+        let voxel = volume.voxel_coordinates(position);
+        std::array::from_fn(|axis| {
+            let last = [width, height, depth][axis] - 1;
+            ((voxel[axis] + 0.5).floor().max(0.0) as u32).min(last)
+        })
+    }
+
+    /// Grows a region from the voxel at `position` into the background with
+    /// the active label.
+    pub fn grow(&mut self, volume: &Volume, position: Point3<f32>) {
+        self.ensure_map(volume.dimensions);
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let region = grow_region(volume, Self::voxel(volume, position), &self.growing);
+        let changed = apply_mask(map, &region, self.active, false);
+        self.edited(changed, self.active);
+    }
+
+    /// Connected parts of the voxels with the active label.
+    pub fn components(&self, connectivity: Connectivity) -> Option<Components> {
+        let map = self.map.as_ref()?;
+        let mask: Vec<bool> = map
+            .values
+            .iter()
+            .map(|&value| value == self.active)
+            .collect();
+        Some(connected_components(&mask, map.dimensions, connectivity))
+    }
+
+    /// Erases the components of `label` after the first `count`.
+    pub fn keep_largest(&mut self, label: u16, components: &Components, count: usize) {
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let mut labels = vec![label; count.min(components.components.len())];
+        labels.resize(components.components.len(), 0);
+        let changed = relabel_components(map, components, label, &labels);
+        self.edited(changed, 0);
+    }
+
+    /// Gives every component of `label` but the largest a new label above the
+    /// existing ones, as long as label values are left.
+    pub fn split_components(&mut self, label: u16, components: &Components) {
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let free = u16::MAX - self.max_label;
+        let new_labels: Vec<u16> = (1..=free)
+            .map(|offset| self.max_label + offset)
+            .take(components.components.len().saturating_sub(1))
+            .collect();
+        let labels: Vec<u16> = std::iter::once(label)
+            .chain(new_labels.iter().copied())
+            .collect();
+        let changed = relabel_components(map, components, label, &labels);
+        for new_label in new_labels {
+            self.edited(changed, new_label);
+        }
+    }
+
+    /// Fills the slices normal to `axis` between slices painted with the
+    /// active label.
+    pub fn interpolate(&mut self, axis: usize) {
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let changed = interpolate_slices(map, self.active, axis);
+        self.edited(changed, self.active);
+    }
+
+    /// Sets the voxels with values in `range` to the active label, voxels of
+    /// other labels only with `overwrite`.
+    pub fn threshold(&mut self, volume: &Volume, range: (u16, u16), overwrite: bool) {
+        self.ensure_map(volume.dimensions);
+        let Some(map) = &mut self.map else {
+            return;
+        };
+        let changed = apply_threshold(map, volume, range, self.active, overwrite);
+        self.edited(changed, self.active);
+    }
+
+    /// Returns whether a tool was picked, so other tools can be turned off.
+    pub fn tool_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let previous = self.tool;
+            ui.selectable_value(&mut self.tool, None, "Off");
+            for tool in LabelTool::ALL {
+                ui.selectable_value(&mut self.tool, Some(tool), tool.label());
+            }
+            if matches!(
+                self.tool,
+                Some(LabelTool::Brush | LabelTool::Fill | LabelTool::Grow)
+            ) {
+                ui.label("Label:");
+                ui.add(egui::DragValue::new(&mut self.active).clamp_range(1..=u16::MAX));
+            }
+            if matches!(self.tool, Some(LabelTool::Brush | LabelTool::Eraser)) {
+                ui.label("Radius:");
+                ui.add(
+                    egui::DragValue::new(&mut self.brush_radius)
+                        .clamp_range(0.0..=100.0)
+                        .speed(0.1)
+                        .suffix(" mm"),
+                );
+                ui.checkbox(&mut self.brush_3d, "3D");
+            }
+            if self.tool != previous {
+                self.stroke = None;
+            }
+            self.tool != previous && self.tool.is_some()
+        })
+        .inner
+    }
+}
+
+/// Width of the 2D texture holding one color per label value.
+pub const COLOR_TABLE_WIDTH: u32 = 256;
+
+/// Loads, creates and saves a label map for the volume, edits how its
+/// labels are shown and picks the editing tools.
+pub struct LabelsPanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+    /// Slices the interpolation fills between are normal to this orientation.
+    interpolation_orientation: SliceOrientation,
+}
+
+impl LabelsPanel {
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            volume,
+            shared,
+            interpolation_orientation: SliceOrientation::Axial,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load(&self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("3D Raw", &["raw"])
+            .pick_file()
+        else {
+            return;
+        };
+        match crate::io::load_label_map(&path, self.volume.dimensions) {
+            Ok(map) => self.shared.labels.borrow_mut().set_map(map),
+            Err(err) => log::error!("Failed to load label map: {err}"),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(&self) {
+        let labels = self.shared.labels.borrow();
+        let Some(map) = &labels.map else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("3D Raw", &["raw"])
+            .set_file_name("labels.raw")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(err) = crate::io::save_label_map(&path, map, labels.max_label) {
+            log::error!("Failed to save label map: {err}");
+        }
+    }
+
+    fn file_buttons(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .button("New")
+                .on_hover_text("Start an empty label map")
+                .clicked()
+            {
+                let mut labels = self.shared.labels.borrow_mut();
+                labels.set_map(LabelMap::new(self.volume.dimensions));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if ui
+                    .button("Load RAW...")
+                    .on_hover_text("8 or 16 bit labels with the dimensions of the volume")
+                    .clicked()
+                {
+                    self.load();
+                }
+                let has_map = self.shared.labels.borrow().map.is_some();
+                if ui
+                    .add_enabled(has_map, egui::Button::new("Save RAW..."))
+                    .on_hover_text("8 bit labels if all fit, 16 bit otherwise")
+                    .clicked()
+                {
+                    self.save();
+                }
+            }
+            let mut labels = self.shared.labels.borrow_mut();
+            if ui
+                .add_enabled(labels.map.is_some(), egui::Button::new("Clear"))
+                .clicked()
+            {
+                labels.clear();
+            }
+        });
+    }
+
+    fn editing_ui(&mut self, ui: &mut egui::Ui) {
+        if self.shared.labels.borrow_mut().tool_ui(ui) {
+            self.shared.keep_only_tool(PointTool::Labels);
+        }
+        ui.label(
+            "Drag in a slice view to paint or erase, click to fill a region of the slice \
+            or to grow a region from a seed.",
+        );
+        let mut labels = self.shared.labels.borrow_mut();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut labels.constrain, "Only values from")
+                .on_hover_text("Leave voxels with other values unchanged");
+            let (lower, upper) = &mut labels.value_range;
+            ui.add(egui::DragValue::new(lower).clamp_range(0..=u16::MAX));
+            ui.label("to");
+            ui.add(egui::DragValue::new(upper).clamp_range(0..=u16::MAX));
+        });
+        ui.horizontal(|ui| {
+            let orientation = &mut self.interpolation_orientation;
+            for (option, name) in [
+                (SliceOrientation::Axial, "Axial"),
+                (SliceOrientation::Coronal, "Coronal"),
+                (SliceOrientation::Sagittal, "Sagittal"),
+            ] {
+                ui.selectable_value(orientation, option, name);
+            }
+            if ui
+                .add_enabled(labels.map.is_some(), egui::Button::new("Interpolate"))
+                .on_hover_text("Fill the slices between the slices painted with the active label")
+                .clicked()
+            {
+                labels.interpolate(orientation.normal_axis());
+            }
+        });
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.file_buttons(ui);
+        self.editing_ui(ui);
+        ui.separator();
+
+        let mut labels = self.shared.labels.borrow_mut();
+        if labels.map.is_none() {
+            ui.label("No label map");
+            return;
+        }
+        ui.label(format!(
+            "{} labels, {} bit",
+            labels.items.len(),
+            if labels.max_label > u8::MAX as u16 {
+                16
+            } else {
+                8
+            }
+        ));
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut labels.shown, "Show");
+            ui.add(egui::Slider::new(&mut labels.opacity, 0.0..=1.0).text("Opacity"));
+            ui.checkbox(&mut labels.outline, "Outline only");
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Show all").clicked() {
+                labels
+                    .items
+                    .iter_mut()
+                    .for_each(|label| label.visible = true);
+            }
+            if ui.button("Hide all").clicked() {
+                labels
+                    .items
+                    .iter_mut()
+                    .for_each(|label| label.visible = false);
+            }
+        });
+        ui.separator();
+
+        let mut active = labels.active;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("labels")
+                .striped(true)
+                .num_columns(4)
+                .show(ui, |ui| {
+                    for label in &mut labels.items {
+                        ui.checkbox(&mut label.visible, "");
+                        ui.color_edit_button_srgb(&mut label.color);
+                        if ui
+                            .selectable_label(active == label.value, label.value.to_string())
+                            .on_hover_text("Paint with this label")
+                            .clicked()
+                        {
+                            active = label.value;
+                        }
+                        ui.add(egui::TextEdit::singleline(&mut label.name).desired_width(160.0));
+                        ui.end_row();
+                    }
+                });
+        });
+        labels.active = active;
+    }
+}
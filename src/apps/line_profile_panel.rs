@@ -0,0 +1,103 @@
+use std::rc::Rc;
+
+use egui::plot::{Legend, Line, Plot, PlotPoints};
+
+use crate::{
+    data::{MeasurementKind, Volume},
+    processing::line_profile,
+};
+
+use super::{slice_renderer::PointTool, SharedSliceState};
+
+/// Plots the intensity along the profile lines drawn in the slice views.
+///
+/// Profiles are measurements of kind `MeasurementKind::Profile`, so they are
+/// drawn, edited and listed like the other measurements and keep their number.
+pub struct LineProfilePanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+}
+
+impl LineProfilePanel {
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        Self { volume, shared }
+    }
+
+    /// Samples of every profile with its measurement number.
+    fn profiles(&self) -> Vec<(usize, Vec<[f32; 2]>)> {
+        let measurements = self.shared.measurements.borrow();
+        measurements
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, measurement)| measurement.kind == MeasurementKind::Profile)
+            .map(|(index, measurement)| {
+                let samples =
+                    line_profile(&self.volume, measurement.points[0], measurement.points[1]);
+                (index + 1, samples)
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_csv(&self, profiles: &[(usize, Vec<[f32; 2]>)]) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("line_profiles.csv")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(err) = crate::io::export_line_profiles(&path, profiles) {
+            log::error!("Failed to export line profiles: {err}");
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let drawing = self.shared.measurements.borrow().tool == Some(MeasurementKind::Profile);
+            if ui.selectable_label(drawing, "Draw Profile").clicked() {
+                self.shared.keep_only_tool(PointTool::Measurement);
+                let mut measurements = self.shared.measurements.borrow_mut();
+                measurements.tool = (!drawing).then_some(MeasurementKind::Profile);
+                measurements.pending = None;
+            }
+            ui.label("Click start and end of a line in a slice view, drag its ends to move it.");
+        });
+        ui.separator();
+
+        let profiles = self.profiles();
+        if profiles.is_empty() {
+            ui.label("No profiles");
+        }
+        let selected = self.shared.measurements.borrow().selected;
+        ui.label("Value over distance along the line in mm");
+        Plot::new("line_profiles")
+            .legend(Legend::default())
+            .height(ui.available_height() - 32.0)
+            .show(ui, |plot_ui| {
+                for (number, samples) in &profiles {
+                    let points: PlotPoints = samples
+                        .iter()
+                        .map(|&[distance, value]| [distance as f64, value as f64])
+                        .collect();
+                    plot_ui.line(
+                        Line::new(points)
+                            .name(format!("Profile {number}"))
+                            .highlight(selected == Some(number - 1)),
+                    );
+                }
+            });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.separator();
+            if ui
+                .add_enabled(!profiles.is_empty(), egui::Button::new("Export CSV..."))
+                .clicked()
+            {
+                self.export_csv(&profiles);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use eframe::wgpu::{self, util::DeviceExt};
+
+use crate::data::TransferFunction;
+
+/// GPU copy of the transfer function shared by all renderers.
+///
+/// Every renderer binds the same texture, so uploading a changed transfer
+/// function once updates all views. The table only spans the value range of
+/// the volume, which the shaders read from `range_buffer` to remap samples.
+pub struct LookupTable {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    /// Range covered by the table, normalized like the volume texture.
+    pub range_buffer: wgpu::Buffer,
+    transfer_function: TransferFunction,
+    /// Smallest and largest value covered by the table in voxel units.
+    range: (f32, f32),
+}
+
+impl LookupTable {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        transfer_function: &TransferFunction,
+        range: (f32, f32),
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Lookup Table"),
+            size: Self::size(),
+            mip_level_count: 1,
+            sample_count: 1,
+            // 1D textures are not supported by the WebGL backend
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let range_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Lookup Table Range"),
+            contents: bytemuck::cast_slice(&[0.0f32, 1.0]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let lookup_table = Self {
+            texture,
+            view,
+            sampler,
+            range_buffer,
+            transfer_function: transfer_function.clone(),
+            range: Self::nonempty(range),
+        };
+        lookup_table.write(queue);
+        lookup_table
+    }
+
+    /// Uploads `transfer_function` if it differs from the one on the GPU.
+    pub fn update(&mut self, queue: &wgpu::Queue, transfer_function: &TransferFunction) {
+        if self.transfer_function != *transfer_function {
+            self.transfer_function = transfer_function.clone();
+            self.write(queue);
+        }
+    }
+
+    /// Spans the table over `range`, e.g. after loading another volume.
+    pub fn set_range(&mut self, queue: &wgpu::Queue, range: (f32, f32)) {
+        let range = Self::nonempty(range);
+        if self.range != range {
+            self.range = range;
+            self.write(queue);
+        }
+    }
+
+    /// Widens a single value to a range the shaders can divide by.
+    fn nonempty((min, max): (f32, f32)) -> (f32, f32) {
+        (min, max.max(min + 1.0))
+    }
+
+    fn size() -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width: TransferFunction::LOOKUP_TABLE_SIZE as u32,
+            height: 1,
+            depth_or_array_layers: 1,
+        }
+    }
+
+    fn write(&self, queue: &wgpu::Queue) {
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            &self.transfer_function.to_lookup_table(self.range),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * TransferFunction::LOOKUP_TABLE_SIZE as u32),
+                rows_per_image: Some(1),
+            },
+            Self::size(),
+        );
+        // the texture holds values normalized to the u16 range
+        let (min, max) = self.range;
+        queue.write_buffer(
+            &self.range_buffer,
+            0,
+            bytemuck::cast_slice(&[min / u16::MAX as f32, max / u16::MAX as f32]),
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::rc::Rc;
+
+use cgmath::Point3;
+
+use crate::data::{Measurement, MeasurementKind, Volume};
+
+use super::{slice_renderer::PointTool, SharedSliceState};
+
+/// Measurements shared by the slice views and the measurements panel.
+#[derive(Default)]
+pub struct Measurements {
+    pub items: Vec<Measurement>,
+    /// Kind of measurement that clicks in the slice views place, `None` when
+    /// clicks move the cursor instead.
+    pub tool: Option<MeasurementKind>,
+    /// Close new polylines.
+    pub closed: bool,
+    /// Measurement being placed.
+    pub pending: Option<Measurement>,
+    /// Highlighted in the slice views and the panel.
+    pub selected: Option<usize>,
+}
+
+impl Measurements {
+    /// Adds `point` to the pending measurement and completes it once it has
+    /// all its points.
+    pub fn add_point(&mut self, point: Point3<f32>) {
+        let Some(kind) = self.tool else {
+            return;
+        };
+        let closed = self.closed;
+        let pending = self.pending.get_or_insert_with(|| Measurement {
+            closed,
+            ..Measurement::new(kind)
+        });
+        pending.points.push(point);
+        if kind.point_count().is_some() && pending.is_complete() {
+            self.finish();
+        }
+    }
+
+    /// Removes the last point of the pending measurement.
+    pub fn remove_point(&mut self) {
+        if let Some(pending) = &mut self.pending {
+            pending.points.pop();
+            if pending.points.is_empty() {
+                self.pending = None;
+            }
+        }
+    }
+
+    /// Stores the pending measurement, or drops it if it lacks points.
+    pub fn finish(&mut self) {
+        if let Some(measurement) = self.pending.take().filter(Measurement::is_complete) {
+            self.items.push(measurement);
+            self.selected = Some(self.items.len() - 1);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.items.remove(index);
+        self.selected = match self.selected {
+            Some(selected) if selected > index => Some(selected - 1),
+            Some(selected) if selected == index => None,
+            selected => selected,
+        };
+    }
+
+    /// Returns whether a kind was picked, so other tools can be turned off.
+    pub fn tool_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let previous = self.tool;
+            ui.selectable_value(&mut self.tool, None, "Off");
+            for kind in MeasurementKind::ALL {
+                ui.selectable_value(&mut self.tool, Some(kind), kind.label());
+            }
+            if self.tool == Some(MeasurementKind::Polyline) {
+                ui.checkbox(&mut self.closed, "Closed");
+            }
+            if self.tool != previous {
+                self.pending = None;
+            }
+            self.tool != previous && self.tool.is_some()
+        })
+        .inner
+    }
+}
+
+/// Lists the measurements of the slice views.
+pub struct MeasurementsPanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+}
+
+impl MeasurementsPanel {
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        Self { volume, shared }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_csv(&self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("measurements.csv")
+            .save_file()
+        else {
+            return;
+        };
+        let measurements = self.shared.measurements.borrow();
+        if let Err(err) = crate::io::export_measurements(
+            &path,
+            &measurements.items,
+            &self.volume,
+            self.shared.coordinate_system.get(),
+        ) {
+            log::error!("Failed to export measurements: {err}");
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.shared.measurements.borrow_mut().tool_ui(ui) {
+            self.shared.keep_only_tool(PointTool::Measurement);
+        }
+        ui.label("Click in a slice view to place points, double-click to finish a polyline and drag points to edit them.");
+        ui.separator();
+
+        let mut removed = None;
+        {
+            let mut measurements = self.shared.measurements.borrow_mut();
+            if measurements.items.is_empty() {
+                ui.label("No measurements");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(ui.available_height() - 32.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("measurements")
+                        .striped(true)
+                        .num_columns(5)
+                        .show(ui, |ui| {
+                            for index in 0..measurements.items.len() {
+                                let measurement = &measurements.items[index];
+                                let selected = measurements.selected == Some(index);
+                                let first_point = measurement.points[0];
+                                let kind = measurement.kind.label();
+                                let value = measurement.format_value();
+                                let system = self.shared.coordinate_system.get();
+                                let position = system.format(self.volume.position_in(
+                                    self.volume.voxel_coordinates(first_point),
+                                    system,
+                                ));
+                                if ui
+                                    .selectable_label(selected, format!("{}", index + 1))
+                                    .on_hover_text("Select and show in the slice views")
+                                    .clicked()
+                                {
+                                    measurements.selected = Some(index);
+                                    self.shared.cursor.borrow_mut().set_position(first_point);
+                                }
+                                ui.label(kind);
+                                ui.label(value);
+                                ui.label(position).on_hover_text("First point");
+                                if ui.small_button("Delete").clicked() {
+                                    removed = Some(index);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some(index) = removed {
+                measurements.remove(index);
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.separator();
+            let empty = self.shared.measurements.borrow().items.is_empty();
+            if ui
+                .add_enabled(!empty, egui::Button::new("Export CSV..."))
+                .clicked()
+            {
+                self.export_csv();
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Vertex shader
+
+struct Uniforms {
+    view_projection: mat4x4<f32>,
+    // voxel index coordinates to world space
+    model: mat4x4<f32>,
+    // inverse transpose of `model`
+    normal_matrix: mat4x4<f32>,
+    eye: vec4<f32>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    let world_position = uniforms.model * vec4<f32>(in.position, 1.0);
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_projection * world_position;
+    out.world_position = world_position.xyz;
+    out.normal = (uniforms.normal_matrix * vec4<f32>(in.normal, 0.0)).xyz;
+    return out;
+}
+
+// Fragment shader
+
+// Phong shading with a headlight at the camera.
+@fragment
+fn fs_main(in: VertexOutput, @builtin(front_facing) front_facing: bool) -> @location(0) vec4<f32> {
+    var normal = normalize(in.normal);
+    // surfaces cut open at the volume border show their back faces
+    if (!front_facing) {
+        normal = -normal;
+    }
+    let light = normalize(uniforms.eye.xyz - in.world_position);
+
+    let ambient = 0.15;
+    let diffuse = 0.75 * max(dot(normal, light), 0.0);
+    let specular = 0.3 * pow(max(dot(reflect(-light, normal), light), 0.0), 32.0);
+
+    return vec4<f32>(uniforms.color.rgb * (ambient + diffuse) + specular, 1.0);
+}
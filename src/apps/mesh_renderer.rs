@@ -0,0 +1,678 @@
+use std::{rc::Rc, sync::Arc};
+
+use bytemuck::Zeroable;
+use cgmath::{Matrix, Matrix4, SquareMatrix, Vector3};
+use eframe::{
+    egui_wgpu::wgpu::util::DeviceExt,
+    egui_wgpu::{self, wgpu},
+};
+
+use super::{camera::OrbitCamera, settings_overlay::settings_overlay};
+use crate::data::{Mesh, Volume};
+
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+// Needs to match the memory layout of `Uniforms` in mesh.wgsl
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct MeshUniforms {
+    view_projection: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    normal_matrix: [[f32; 4]; 4],
+    eye: [f32; 4],
+    color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct Vertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+impl Vertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+    fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Color and depth attachments the mesh is drawn into, egui's render pass has
+/// no depth buffer.
+struct RenderTarget {
+    size: [u32; 2],
+    color_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    blit_bind_group: wgpu::BindGroup,
+}
+
+struct MeshRenderResources {
+    mesh_pipeline: wgpu::RenderPipeline,
+    blit_pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    blit_bind_group_layout: wgpu::BindGroupLayout,
+    blit_sampler: wgpu::Sampler,
+    color_format: wgpu::TextureFormat,
+    vertex_buffer: Option<wgpu::Buffer>,
+    index_buffer: Option<wgpu::Buffer>,
+    index_count: u32,
+    render_target: Option<RenderTarget>,
+}
+
+impl MeshRenderResources {
+    fn set_mesh(&mut self, device: &wgpu::Device, mesh: &Mesh) {
+        if mesh.is_empty() {
+            self.vertex_buffer = None;
+            self.index_buffer = None;
+            self.index_count = 0;
+            return;
+        }
+
+        let vertices: Vec<Vertex> = mesh
+            .positions
+            .iter()
+            .zip(&mesh.normals)
+            .map(|(&position, &normal)| Vertex { position, normal })
+            .collect();
+
+        self.vertex_buffer = Some(
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Vertex Buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+        );
+        self.index_buffer = Some(
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Index Buffer"),
+                contents: bytemuck::cast_slice(&mesh.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            }),
+        );
+        self.index_count = mesh.indices.len() as u32;
+    }
+
+    fn resize(&mut self, device: &wgpu::Device, size: [u32; 2]) {
+        if self.render_target.as_ref().map(|target| target.size) == Some(size) {
+            return;
+        }
+
+        let extent = wgpu::Extent3d {
+            width: size[0],
+            height: size[1],
+            depth_or_array_layers: 1,
+        };
+        let create_view = |format, usage, label| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: extent,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let color_view = create_view(
+            self.color_format,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            "Mesh Color Target",
+        );
+        let depth_view = create_view(
+            DEPTH_FORMAT,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+            "Mesh Depth Target",
+        );
+
+        let blit_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.blit_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&color_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.blit_sampler),
+                },
+            ],
+            label: Some("mesh_blit_bind_group"),
+        });
+
+        self.render_target = Some(RenderTarget {
+            size,
+            color_view,
+            depth_view,
+            blit_bind_group,
+        });
+    }
+
+    fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        uniforms: MeshUniforms,
+        size: [u32; 2],
+    ) {
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        self.resize(device, size);
+        let render_target = self.render_target.as_ref().unwrap();
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Mesh Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &render_target.color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &render_target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: false,
+                }),
+                stencil_ops: None,
+            }),
+        });
+
+        if let (Some(vertex_buffer), Some(index_buffer)) = (&self.vertex_buffer, &self.index_buffer)
+        {
+            render_pass.set_pipeline(&self.mesh_pipeline);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..self.index_count, 0, 0..1);
+        }
+    }
+
+    fn paint<'rp>(&'rp self, render_pass: &mut wgpu::RenderPass<'rp>) {
+        if let Some(render_target) = &self.render_target {
+            render_pass.set_pipeline(&self.blit_pipeline);
+            render_pass.set_bind_group(0, &render_target.blit_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+/// Post-processing and file format used when saving the surface.
+#[cfg(not(target_arch = "wasm32"))]
+struct MeshExport {
+    format: crate::io::MeshFileFormat,
+    decimate: bool,
+    /// Edge length of the clustering grid in mm.
+    cell_size: f32,
+    smooth: bool,
+    smoothing_iterations: u32,
+    /// Store the interpolated voxel value per vertex, PLY only.
+    include_values: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for MeshExport {
+    fn default() -> Self {
+        Self {
+            format: crate::io::MeshFileFormat::StlBinary,
+            decimate: false,
+            cell_size: 2.0,
+            smooth: false,
+            smoothing_iterations: 10,
+            include_values: false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl MeshExport {
+    /// Returns true if the export was requested.
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        use crate::io::MeshFileFormat;
+
+        egui::ComboBox::from_label("Format")
+            .selected_text(self.format.label())
+            .show_ui(ui, |ui| {
+                for format in MeshFileFormat::ALL {
+                    ui.selectable_value(&mut self.format, format, format.label());
+                }
+            });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.decimate, "Decimate");
+            ui.add_enabled(
+                self.decimate,
+                egui::Slider::new(&mut self.cell_size, 0.5..=10.0).text("Cell Size [mm]"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.smooth, "Smooth");
+            ui.add_enabled(
+                self.smooth,
+                egui::Slider::new(&mut self.smoothing_iterations, 1..=50).text("Iterations"),
+            );
+        });
+        ui.add_enabled(
+            self.format == MeshFileFormat::Ply,
+            egui::Checkbox::new(&mut self.include_values, "Include Voxel Values"),
+        );
+        ui.button("Export...").clicked()
+    }
+}
+
+/// Shaded display of an isosurface mesh extracted on the CPU.
+pub struct MeshRenderer {
+    id: egui::Id,
+    wgpu_render_state: egui_wgpu::RenderState,
+    volume: Rc<Volume>,
+    camera: OrbitCamera,
+    /// Maps voxel index coordinates into the unit cube used by the 3D views.
+    model: Matrix4<f32>,
+    value_range: (u16, u16),
+    iso_value: f32,
+    color: [f32; 3],
+    mesh: Mesh,
+    #[cfg(not(target_arch = "wasm32"))]
+    export: MeshExport,
+    pub show_settings_oberlay: bool,
+}
+
+impl MeshRenderer {
+    pub fn new(wgpu_render_state: &egui_wgpu::RenderState, volume: Rc<Volume>) -> Option<Self> {
+        let device = &wgpu_render_state.device;
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Uniforms"),
+            contents: bytemuck::cast_slice(&[MeshUniforms::zeroed()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("mesh_uniform_bind_group_layout"),
+            });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("mesh_uniform_bind_group"),
+        });
+
+        let blit_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+                label: Some("mesh_blit_bind_group_layout"),
+            });
+
+        let blit_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let mesh_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Mesh Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("mesh.wgsl").into()),
+        });
+
+        let blit_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Blit Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blit.wgsl").into()),
+        });
+
+        let mesh_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Mesh Pipeline Layout"),
+            bind_group_layouts: &[&uniform_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let mesh_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Pipeline"),
+            layout: Some(&mesh_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &mesh_shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &mesh_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu_render_state.target_format.into())],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let blit_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blit Pipeline Layout"),
+            bind_group_layouts: &[&blit_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let blit_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Blit Pipeline"),
+            layout: Some(&blit_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &blit_shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &blit_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu_render_state.target_format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let id = egui::Id::new(uuid::Uuid::new_v4());
+        let mesh_render_resources = MeshRenderResources {
+            mesh_pipeline,
+            blit_pipeline,
+            uniform_buffer,
+            uniform_bind_group,
+            blit_bind_group_layout,
+            blit_sampler,
+            color_format: wgpu_render_state.target_format,
+            vertex_buffer: None,
+            index_buffer: None,
+            index_count: 0,
+            render_target: None,
+        };
+
+        match wgpu_render_state
+            .renderer
+            .write()
+            .paint_callback_resources
+            .entry::<std::collections::HashMap<egui::Id, MeshRenderResources>>()
+        {
+            type_map::concurrent::Entry::Occupied(mut e) => {
+                e.get_mut().insert(id, mesh_render_resources);
+            }
+            type_map::concurrent::Entry::Vacant(e) => {
+                e.insert(std::collections::HashMap::new())
+                    .insert(id, mesh_render_resources);
+            }
+        }
+
+        let value_range = volume.range();
+
+        Some(Self {
+            id,
+            wgpu_render_state: wgpu_render_state.clone(),
+            model: Self::model(&volume),
+            volume,
+            camera: OrbitCamera::default(),
+            value_range,
+            iso_value: (value_range.0 as f32 + value_range.1 as f32) / 2.0,
+            color: [0.9, 0.85, 0.75],
+            mesh: Mesh::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            export: MeshExport::default(),
+            show_settings_oberlay: true,
+        })
+    }
+
+    /// Same placement as the ray casting box: centered at the origin with the
+    /// largest physical extent scaled to one, voxel centers at the cell centers.
+    fn model(volume: &Volume) -> Matrix4<f32> {
+        let dimensions = Vector3::new(
+            volume.dimensions.0 as f32,
+            volume.dimensions.1 as f32,
+            volume.dimensions.2 as f32,
+        );
+        let extent = Vector3::new(
+            dimensions.x * volume.spacing.0,
+            dimensions.y * volume.spacing.1,
+            dimensions.z * volume.spacing.2,
+        );
+        let box_size = extent / extent.x.max(extent.y).max(extent.z);
+        let scale = box_size.zip(dimensions, |size, dimension| size / dimension);
+        let offset = box_size.zip(dimensions, |size, dimension| (0.5 / dimension - 0.5) * size);
+
+        Matrix4::from_translation(offset)
+            * Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+    }
+
+    fn extract_surface(&mut self) {
+        self.mesh = crate::processing::marching_cubes(&self.volume, self.iso_value);
+
+        let device = &self.wgpu_render_state.device;
+        let mut renderer = self.wgpu_render_state.renderer.write();
+        let resources: &mut std::collections::HashMap<egui::Id, MeshRenderResources> =
+            renderer.paint_callback_resources.get_mut().unwrap();
+        resources
+            .get_mut(&self.id)
+            .unwrap()
+            .set_mesh(device, &self.mesh);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_mesh(&self) {
+        let format = self.export.format;
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(format.label(), &[format.extension()])
+            .set_file_name(&format!("isosurface.{}", format.extension()))
+            .save_file()
+        else {
+            return;
+        };
+
+        // decimated in world coordinates, so the cells are cubes in mm
+        let mut mesh = self.mesh.to_world(&self.volume);
+        if self.export.decimate {
+            mesh = crate::processing::decimate(&mesh, self.export.cell_size);
+        }
+        if self.export.smooth {
+            mesh = crate::processing::smooth(&mesh, self.export.smoothing_iterations, 0.5);
+        }
+        let scalars: Option<Vec<f32>> =
+            (format == crate::io::MeshFileFormat::Ply && self.export.include_values).then(|| {
+                let world_to_index = self
+                    .volume
+                    .index_to_world()
+                    .invert()
+                    .unwrap_or_else(Matrix4::identity);
+                mesh.positions
+                    .iter()
+                    .map(|&position| {
+                        let index = world_to_index * Vector3::from(position).extend(1.0);
+                        self.volume.interpolate(index.truncate().into())
+                    })
+                    .collect()
+            });
+
+        if let Err(err) = crate::io::export_mesh(&path, &mesh, format, scalars.as_deref()) {
+            log::error!("Failed to export mesh: {err}");
+        }
+    }
+
+    fn uniforms(&self, aspect_ratio: f32) -> MeshUniforms {
+        let normal_matrix = self
+            .model
+            .invert()
+            .unwrap_or_else(Matrix4::identity)
+            .transpose();
+        let eye = self.camera.eye();
+
+        MeshUniforms {
+            view_projection: self.camera.view_projection(aspect_ratio).into(),
+            model: self.model.into(),
+            normal_matrix: normal_matrix.into(),
+            eye: [eye.x, eye.y, eye.z, 1.0],
+            color: [self.color[0], self.color[1], self.color[2], 1.0],
+        }
+    }
+
+    fn handle_input(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let delta = response.drag_delta() / rect.height();
+
+        if response.dragged_by(egui::PointerButton::Primary) {
+            self.camera.orbit(
+                delta.x * std::f32::consts::PI,
+                delta.y * std::f32::consts::PI,
+            );
+        }
+        if response.dragged_by(egui::PointerButton::Secondary)
+            || response.dragged_by(egui::PointerButton::Middle)
+        {
+            self.camera.pan(delta.x, delta.y);
+        }
+        if response.hovered() {
+            let scroll = ui.input(|i| i.scroll_delta.y);
+            if scroll != 0.0 {
+                self.camera.zoom(scroll * 0.002);
+            }
+        }
+        if response.double_clicked() {
+            self.camera = OrbitCamera::default();
+        }
+    }
+
+    pub fn custom_painting(&mut self, ui: &mut egui::Ui) {
+        let availbale_size = ui.available_size_before_wrap();
+        let (rect, response) =
+            ui.allocate_exact_size(availbale_size, egui::Sense::click_and_drag());
+
+        self.handle_input(ui, rect, &response);
+
+        // Clone locals so we can move them into the paint callback:
+        let uniforms = self.uniforms(rect.aspect_ratio());
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        let size = [
+            ((rect.width() * pixels_per_point).round() as u32).max(1),
+            ((rect.height() * pixels_per_point).round() as u32).max(1),
+        ];
+        let id = self.id;
+
+        let cb = egui_wgpu::CallbackFn::new()
+            .prepare(move |device, queue, encoder, paint_callback_resources| {
+                let resources: &mut std::collections::HashMap<egui::Id, MeshRenderResources> =
+                    paint_callback_resources.get_mut().unwrap();
+                let mesh_render_resources = resources.get_mut(&id).unwrap();
+                mesh_render_resources.prepare(device, queue, encoder, uniforms, size);
+                Vec::new()
+            })
+            .paint(move |_info, render_pass, paint_callback_resources| {
+                let resources: &std::collections::HashMap<egui::Id, MeshRenderResources> =
+                    paint_callback_resources.get().unwrap();
+                let mesh_render_resources = resources.get(&id).unwrap();
+                mesh_render_resources.paint(render_pass);
+            });
+
+        let callback = egui::PaintCallback {
+            rect,
+            callback: Arc::new(cb),
+        };
+
+        ui.painter().add(callback);
+
+        if self.mesh.is_empty() {
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Extract a surface in the settings",
+                egui::FontId::proportional(14.0),
+                egui::Color32::GRAY,
+            );
+        }
+
+        // Paint overlay
+        if self.show_settings_oberlay {
+            let (min, max) = self.value_range;
+            settings_overlay(ui, rect, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.iso_value, min as f32..=max as f32)
+                        .text("Iso Value"),
+                );
+                if ui.button("Extract Surface").clicked() {
+                    self.extract_surface();
+                }
+                ui.label(format!("{} triangles", self.mesh.triangle_count()));
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(&mut self.color);
+                    ui.label("Surface Color");
+                });
+                if ui.button("Reset Camera").clicked() {
+                    self.camera = OrbitCamera::default();
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+                    ui.add_enabled_ui(!self.mesh.is_empty(), |ui| {
+                        if self.export.ui(ui) {
+                            self.export_mesh();
+                        }
+                    });
+                }
+            });
+        }
+    }
+}
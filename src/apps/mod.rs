@@ -1,5 +1,45 @@
+mod annotations;
+mod camera;
+mod cine;
+mod cursor;
+mod curved_path;
+mod curved_reformation_view;
+mod histogram_panel;
+mod histogram_plot;
+mod label_overlay;
+mod labels;
+mod line_profile_panel;
+mod lookup_table;
+mod measurements;
+mod mesh_renderer;
+mod oblique_plane;
+mod regions_panel;
+mod rois;
+mod settings_overlay;
+mod slice_annotations;
 mod slice_renderer;
+mod statistics_task;
 mod texture;
+mod threshold_panel;
+mod transfer_function_editor;
+mod view_transform;
+mod volume_renderer;
 
-pub use slice_renderer::SliceRenderer;
+pub use annotations::AnnotationsPanel;
+pub use curved_path::CurvedPath;
+pub use curved_reformation_view::CurvedReformationView;
+pub use histogram_panel::HistogramPanel;
+pub use label_overlay::LabelOverlay;
+pub use labels::LabelsPanel;
+pub use line_profile_panel::LineProfilePanel;
+pub use lookup_table::LookupTable;
+pub use measurements::MeasurementsPanel;
+pub use mesh_renderer::MeshRenderer;
+pub use regions_panel::RegionsPanel;
+pub use rois::RoiPanel;
+pub use slice_annotations::SliceAnnotations;
+pub use slice_renderer::{SharedSliceState, SliceRenderer};
 pub use texture::Texture;
+pub use threshold_panel::ThresholdPanel;
+pub use transfer_function_editor::TransferFunctionEditor;
+pub use volume_renderer::VolumeRenderer;
@@ -0,0 +1,169 @@
+use cgmath::{InnerSpace, Matrix4, Point3, Quaternion, Rad, Rotation, Rotation3, Vector3};
+
+/// Arbitrary slicing plane shared by the oblique slice views and the views
+/// that show and edit it.
+///
+/// Positions are given in mm relative to the corner of the volume, whose
+/// size in mm is passed in as `extent` where it matters.
+#[derive(Clone, Copy, Debug)]
+pub struct ObliquePlane {
+    pub origin: Point3<f32>,
+    /// Rotates the z axis onto the plane normal.
+    pub orientation: Quaternion<f32>,
+    /// Rotation around the normal in radians.
+    pub rotation: f32,
+    /// Frame in which an oblique view was last painted.
+    last_shown: Option<u64>,
+}
+
+impl ObliquePlane {
+    /// Axial plane through the center of a volume of `extent`.
+    pub fn new(extent: Vector3<f32>) -> Self {
+        Self {
+            origin: Point3::new(0.0, 0.0, 0.0) + extent / 2.0,
+            orientation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            rotation: 0.0,
+            last_shown: None,
+        }
+    }
+
+    /// Resets origin and orientation, the plane then matches the axial view.
+    pub fn reset(&mut self, extent: Vector3<f32>) {
+        self.origin = Point3::new(0.0, 0.0, 0.0) + extent / 2.0;
+        self.orientation = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        self.rotation = 0.0;
+    }
+
+    pub fn normal(&self) -> Vector3<f32> {
+        self.orientation.rotate_vector(Vector3::unit_z())
+    }
+
+    /// Horizontal and vertical direction of the plane as shown in the oblique view.
+    pub fn axes(&self) -> (Vector3<f32>, Vector3<f32>) {
+        let rotation = self.orientation * Quaternion::from_angle_z(Rad(self.rotation));
+        (
+            rotation.rotate_vector(Vector3::unit_x()),
+            rotation.rotate_vector(Vector3::unit_y()),
+        )
+    }
+
+    /// Edge length of the square shown in the oblique view, large enough to
+    /// cover a volume of `extent` at any orientation.
+    pub fn size(extent: Vector3<f32>) -> f32 {
+        extent.magnitude()
+    }
+
+    /// Maps view coordinates `(x, y, depth, 1)` in [0, 1] to volume texture
+    /// coordinates of a volume of `extent`. The plane itself lies at depth 0.5.
+    pub fn transform(&self, extent: Vector3<f32>) -> Matrix4<f32> {
+        let (u, v) = self.axes();
+        let n = self.normal();
+        let size = Self::size(extent);
+        let to_texture = |vector: Vector3<f32>| {
+            Vector3::new(
+                vector.x / extent.x,
+                vector.y / extent.y,
+                vector.z / extent.z,
+            )
+        };
+        let corner = self.origin - (u + v + n) * (0.5 * size);
+
+        Matrix4::from_cols(
+            to_texture(u * size).extend(0.0),
+            to_texture(v * size).extend(0.0),
+            to_texture(n * size).extend(0.0),
+            to_texture(corner - Point3::new(0.0, 0.0, 0.0)).extend(1.0),
+        )
+    }
+
+    /// Rotates the plane around `axis` through its origin.
+    pub fn rotate(&mut self, axis: Vector3<f32>, angle: f32) {
+        self.orientation = (Quaternion::from_axis_angle(axis.normalize(), Rad(angle))
+            * self.orientation)
+            .normalize();
+    }
+
+    /// Moves the origin, keeping it inside a volume of `extent`.
+    pub fn translate(&mut self, delta: Vector3<f32>, extent: Vector3<f32>) {
+        let origin = self.origin + delta;
+        self.origin = Point3::new(
+            origin.x.clamp(0.0, extent.x),
+            origin.y.clamp(0.0, extent.y),
+            origin.z.clamp(0.0, extent.z),
+        );
+    }
+
+    pub fn mark_shown(&mut self, frame: u64) {
+        self.last_shown = Some(frame);
+    }
+
+    /// True while an oblique view is visible. Views painted earlier in the
+    /// frame than the oblique view see the previous frame.
+    pub fn is_shown(&self, frame: u64) -> bool {
+        self.last_shown
+            .is_some_and(|last_shown| last_shown + 1 >= frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{assert_abs_diff_eq, Vector4};
+
+    use super::*;
+
+    fn map(plane: &ObliquePlane, extent: Vector3<f32>, view: [f32; 3]) -> Vector3<f32> {
+        let view = Vector4::new(view[0], view[1], view[2], 1.0);
+        (plane.transform(extent) * view).truncate()
+    }
+
+    #[test]
+    fn default_plane_matches_the_axial_view() {
+        let extent = Vector3::new(10.0, 20.0, 10.0);
+        let mut plane = ObliquePlane::new(extent);
+        assert_abs_diff_eq!(
+            map(&plane, extent, [0.5, 0.5, 0.5]),
+            Vector3::new(0.5, 0.5, 0.5)
+        );
+
+        // the axial mapping, zoomed out so the square covers the volume diagonal
+        let size = ObliquePlane::size(extent);
+        for view in [
+            [0.0, 0.0, 0.5],
+            [0.25, 0.9, 0.5],
+            [1.0, 0.3, 0.0],
+            [0.6, 1.0, 1.0],
+        ] {
+            let expected = Vector3::new(
+                0.5 + (view[0] - 0.5) * size / extent.x,
+                0.5 + (view[1] - 0.5) * size / extent.y,
+                0.5 + (view[2] - 0.5) * size / extent.z,
+            );
+            assert_abs_diff_eq!(map(&plane, extent, view), expected, epsilon = 1e-6);
+        }
+
+        // the plane follows its origin
+        plane.translate(Vector3::new(0.0, 0.0, -2.5), extent);
+        assert_abs_diff_eq!(
+            map(&plane, extent, [0.5, 0.5, 0.5]),
+            Vector3::new(0.5, 0.5, 0.25)
+        );
+        plane.reset(extent);
+        assert_abs_diff_eq!(
+            map(&plane, extent, [0.5, 0.5, 0.5]),
+            Vector3::new(0.5, 0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn rotation_turns_the_normal_around_the_origin() {
+        let extent = Vector3::new(10.0, 10.0, 10.0);
+        let mut plane = ObliquePlane::new(extent);
+        plane.rotate(Vector3::unit_x(), std::f32::consts::FRAC_PI_2);
+        assert_abs_diff_eq!(plane.normal(), -Vector3::unit_y(), epsilon = 1e-6);
+        assert_abs_diff_eq!(
+            map(&plane, extent, [0.5, 0.5, 0.5]),
+            Vector3::new(0.5, 0.5, 0.5),
+            epsilon = 1e-6
+        );
+    }
+}
@@ -0,0 +1,216 @@
+// Vertex shader
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+}
+
+// One triangle covering the whole viewport, no vertex buffer needed.
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index & 2u) * 2 - 1);
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.ndc = vec2<f32>(x, y);
+    return out;
+}
+
+// Fragment shader
+
+@group(0) @binding(0)
+var t_volume: texture_3d<f32>;
+@group(0) @binding(1)
+var s_volume: sampler;
+@group(0) @binding(2)
+var t_lookup_table: texture_2d<f32>;
+@group(0) @binding(3)
+var s_lookup_table: sampler;
+@group(0) @binding(4)
+var<uniform> lookup_table_range: vec2<f32>; // values at the first and last entry, normalized like the volume
+
+struct Uniforms {
+    inverse_view_projection: mat4x4<f32>,
+    // extent of the volume in world space, centered at the origin
+    box_size: vec3<f32>,
+    step_size: f32,
+    // step size the opacities of the transfer function are defined for
+    reference_step_size: f32,
+    // accumulated opacity at which marching stops, > 1.0 disables it
+    early_ray_termination: f32,
+    jitter: u32,
+    // 0 = direct volume rendering, 1 = maximum, 2 = minimum, 3 = average intensity projection,
+    // 4 = isosurface
+    render_mode: u32,
+    // normalized like the volume texture
+    iso_value: f32,
+}
+
+@group(1) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+fn unproject(ndc: vec3<f32>) -> vec3<f32> {
+    let position = uniforms.inverse_view_projection * vec4<f32>(ndc, 1.0);
+    return position.xyz / position.w;
+}
+
+// Returns the entry and exit distance of the ray, no hit if x > y.
+fn intersect_box(origin: vec3<f32>, direction: vec3<f32>) -> vec2<f32> {
+    let box_min = -0.5 * uniforms.box_size;
+    let box_max = 0.5 * uniforms.box_size;
+    let inverse_direction = 1.0 / direction;
+    let t0 = (box_min - origin) * inverse_direction;
+    let t1 = (box_max - origin) * inverse_direction;
+    let t_min = min(t0, t1);
+    let t_max = max(t0, t1);
+    let t_near = max(max(t_min.x, t_min.y), t_min.z);
+    let t_far = min(min(t_max.x, t_max.y), t_max.z);
+    return vec2<f32>(max(t_near, 0.0), t_far);
+}
+
+fn apply_transfer_function(value: f32) -> vec4<f32> {
+    // hit the texel centers of the lookup table
+    let size = f32(textureDimensions(t_lookup_table).x);
+    let t = (value - lookup_table_range.x) / (lookup_table_range.y - lookup_table_range.x);
+    let coordinate = (clamp(t, 0.0, 1.0) * (size - 1.0) + 0.5) / size;
+    return textureSampleLevel(t_lookup_table, s_lookup_table, vec2<f32>(coordinate, 0.5), 0.0);
+}
+
+fn sample_volume(position: vec3<f32>) -> f32 {
+    let tex_coords = position / uniforms.box_size + 0.5;
+    return textureSampleLevel(t_volume, s_volume, tex_coords, 0.0)[0];
+}
+
+// Cheap per pixel noise in [0, 1) to hide wood grain artifacts.
+fn hash(position: vec2<f32>) -> f32 {
+    return fract(sin(dot(position, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+// Combines all samples between `t_start` and `t_end` according to `render_mode`.
+fn project(origin: vec3<f32>, direction: vec3<f32>, t_start: f32, t_end: f32) -> vec4<f32> {
+    var maximum = 0.0;
+    var minimum = 1.0;
+    var sum = 0.0;
+    var count = 0.0;
+
+    var t = t_start;
+    loop {
+        if (t > t_end) {
+            break;
+        }
+        let value = sample_volume(origin + t * direction);
+        maximum = max(maximum, value);
+        minimum = min(minimum, value);
+        sum += value;
+        count += 1.0;
+        t += uniforms.step_size;
+    }
+
+    var value = sum / max(count, 1.0);
+    if (uniforms.render_mode == 1u) {
+        value = maximum;
+    } else if (uniforms.render_mode == 2u) {
+        value = minimum;
+    }
+    return vec4<f32>(value, value, value, 1.0);
+}
+
+// Gradient by central differences one texel apart, in world space.
+fn gradient(position: vec3<f32>) -> vec3<f32> {
+    let texel = uniforms.box_size / vec3<f32>(textureDimensions(t_volume));
+    let dx = vec3<f32>(texel.x, 0.0, 0.0);
+    let dy = vec3<f32>(0.0, texel.y, 0.0);
+    let dz = vec3<f32>(0.0, 0.0, texel.z);
+    return vec3<f32>(
+        sample_volume(position + dx) - sample_volume(position - dx),
+        sample_volume(position + dy) - sample_volume(position - dy),
+        sample_volume(position + dz) - sample_volume(position - dz),
+    ) / (2.0 * texel);
+}
+
+// Finds the first crossing of `iso_value` and shades it with a headlight.
+fn isosurface(origin: vec3<f32>, direction: vec3<f32>, t_start: f32, t_end: f32) -> vec4<f32> {
+    var t = t_start;
+    loop {
+        if (t > t_end) {
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        }
+        if (sample_volume(origin + t * direction) >= uniforms.iso_value) {
+            break;
+        }
+        t += uniforms.step_size;
+    }
+
+    // refine the hit between the last two samples by bisection
+    var t_outside = max(t - uniforms.step_size, t_start);
+    var t_inside = t;
+    for (var i = 0; i < 6; i++) {
+        let t_middle = 0.5 * (t_outside + t_inside);
+        if (sample_volume(origin + t_middle * direction) >= uniforms.iso_value) {
+            t_inside = t_middle;
+        } else {
+            t_outside = t_middle;
+        }
+    }
+    let position = origin + t_inside * direction;
+
+    // the surface points towards lower values, flat where the gradient vanishes
+    var normal = -direction;
+    let g = gradient(position);
+    if (dot(g, g) > 0.0) {
+        normal = -normalize(g);
+    }
+    let light = -direction;
+
+    let ambient = 0.15;
+    let diffuse = 0.75 * abs(dot(normal, light));
+    let specular = 0.3 * pow(max(dot(reflect(-light, normal), light), 0.0), 32.0);
+    let color = vec3<f32>(0.9, 0.85, 0.75);
+
+    return vec4<f32>(color * (ambient + diffuse) + specular, 1.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let near = unproject(vec3<f32>(in.ndc, -1.0));
+    let far = unproject(vec3<f32>(in.ndc, 1.0));
+    let direction = normalize(far - near);
+
+    let hit = intersect_box(near, direction);
+    if (hit.x > hit.y) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+
+    var t = hit.x;
+    if (uniforms.jitter != 0u) {
+        t += hash(in.clip_position.xy) * uniforms.step_size;
+    }
+
+    if (uniforms.render_mode == 4u) {
+        return isosurface(near, direction, t, hit.y);
+    }
+    if (uniforms.render_mode != 0u) {
+        return project(near, direction, t, hit.y);
+    }
+
+    let opacity_correction = uniforms.step_size / uniforms.reference_step_size;
+    var color = vec3<f32>(0.0);
+    var alpha = 0.0;
+
+    loop {
+        if (t > hit.y || alpha >= uniforms.early_ray_termination) {
+            break;
+        }
+
+        let sample = apply_transfer_function(sample_volume(near + t * direction));
+        let sample_alpha = 1.0 - pow(1.0 - clamp(sample.a, 0.0, 0.9999), opacity_correction);
+
+        // front to back compositing
+        color += (1.0 - alpha) * sample_alpha * sample.rgb;
+        alpha += (1.0 - alpha) * sample_alpha;
+
+        t += uniforms.step_size;
+    }
+
+    return vec4<f32>(color, 1.0);
+}
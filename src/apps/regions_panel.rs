@@ -0,0 +1,215 @@
+use std::rc::Rc;
+
+use crate::{
+    data::Volume,
+    processing::{Components, Connectivity, GrowingCriterion},
+};
+
+use super::{labels::LabelTool, slice_renderer::PointTool, SharedSliceState};
+
+/// Settings of the grow tool and the connected components of the active
+/// label, which can be reduced to the largest or split into labels of their
+/// own.
+pub struct RegionsPanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+    connectivity: Connectivity,
+    /// Label the components were found for and the components.
+    components: Option<(u16, Components)>,
+    keep_count: usize,
+}
+
+impl RegionsPanel {
+    /// Rows shown in the component table.
+    const MAX_ROWS: usize = 1000;
+
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            volume,
+            shared,
+            connectivity: Connectivity::Faces,
+            components: None,
+            keep_count: 1,
+        }
+    }
+
+    fn growing_ui(&mut self, ui: &mut egui::Ui) {
+        let mut labels = self.shared.labels.borrow_mut();
+        ui.horizontal(|ui| {
+            let mut growing = labels.tool == Some(LabelTool::Grow);
+            if ui
+                .toggle_value(&mut growing, "Grow")
+                .on_hover_text(
+                    "Click a seed in a slice view to grow a region with the active label",
+                )
+                .changed()
+            {
+                labels.tool = growing.then_some(LabelTool::Grow);
+                labels.end_stroke();
+                if growing {
+                    self.shared.keep_only_tool(PointTool::Labels);
+                }
+            }
+            ui.label("Label:");
+            ui.add(egui::DragValue::new(&mut labels.active).clamp_range(1..=u16::MAX));
+        });
+
+        let settings = &mut labels.growing;
+        ui.horizontal(|ui| {
+            for criterion in GrowingCriterion::ALL {
+                ui.selectable_value(&mut settings.criterion, criterion, criterion.label());
+            }
+            egui::ComboBox::from_id_source("growing_connectivity")
+                .selected_text(settings.connectivity.label())
+                .show_ui(ui, |ui| {
+                    for connectivity in Connectivity::ALL {
+                        ui.selectable_value(
+                            &mut settings.connectivity,
+                            connectivity,
+                            connectivity.label(),
+                        );
+                    }
+                });
+        });
+        ui.horizontal(|ui| match settings.criterion {
+            GrowingCriterion::Tolerance => {
+                ui.label("Tolerance:");
+                ui.add(egui::DragValue::new(&mut settings.tolerance))
+                    .on_hover_text("Largest difference to the seed value");
+            }
+            GrowingCriterion::Confidence => {
+                ui.label("Std multiplier:");
+                ui.add(
+                    egui::DragValue::new(&mut settings.multiplier)
+                        .clamp_range(0.0..=10.0)
+                        .speed(0.05),
+                );
+                ui.label("Iterations:");
+                ui.add(egui::DragValue::new(&mut settings.iterations).clamp_range(0..=20));
+                ui.label("Radius:");
+                ui.add(egui::DragValue::new(&mut settings.radius).clamp_range(0..=10))
+                    .on_hover_text("Voxels around the seed of the first estimate");
+            }
+        });
+    }
+
+    fn components_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("components_connectivity")
+                .selected_text(self.connectivity.label())
+                .show_ui(ui, |ui| {
+                    for connectivity in Connectivity::ALL {
+                        ui.selectable_value(
+                            &mut self.connectivity,
+                            connectivity,
+                            connectivity.label(),
+                        );
+                    }
+                });
+            let has_map = self.shared.labels.borrow().map.is_some();
+            if ui
+                .add_enabled(has_map, egui::Button::new("Find components"))
+                .on_hover_text("Split the voxels of the active label into connected parts")
+                .clicked()
+            {
+                self.find_components();
+            }
+        });
+
+        let Some((label, components)) = &self.components else {
+            return;
+        };
+        let label = *label;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Keep largest");
+            ui.add(
+                egui::DragValue::new(&mut self.keep_count)
+                    .clamp_range(1..=components.components.len().max(1)),
+            );
+            let mut labels = self.shared.labels.borrow_mut();
+            if ui
+                .button("Keep")
+                .on_hover_text("Erase the smaller components")
+                .clicked()
+            {
+                labels.keep_largest(label, components, self.keep_count);
+                changed = true;
+            }
+            if ui
+                .button("Split into labels")
+                .on_hover_text("Give every component but the largest a new label")
+                .clicked()
+            {
+                labels.split_components(label, components);
+                changed = true;
+            }
+        });
+        if changed {
+            self.find_components();
+            return;
+        }
+        self.components_table(ui, label, components);
+    }
+
+    fn find_components(&mut self) {
+        let labels = self.shared.labels.borrow();
+        self.components = labels
+            .components(self.connectivity)
+            .map(|components| (labels.active, components));
+    }
+
+    fn components_table(&self, ui: &mut egui::Ui, label: u16, components: &Components) {
+        let count = components.components.len();
+        ui.label(format!("{count} components of label {label}"));
+        let (x, y, z) = self.volume.spacing;
+        let system = self.shared.coordinate_system.get();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("components")
+                .striped(true)
+                .num_columns(4)
+                .show(ui, |ui| {
+                    ui.strong("#");
+                    ui.strong("Voxels");
+                    ui.strong("Volume");
+                    ui.strong("Centroid");
+                    ui.end_row();
+                    for (index, component) in components
+                        .components
+                        .iter()
+                        .take(Self::MAX_ROWS)
+                        .enumerate()
+                    {
+                        if ui
+                            .selectable_label(false, format!("{}", index + 1))
+                            .on_hover_text("Show the centroid in the slice views")
+                            .clicked()
+                        {
+                            let position = self.volume.voxel_position(component.centroid);
+                            self.shared.cursor.borrow_mut().set_position(position);
+                        }
+                        ui.label(component.voxel_count.to_string());
+                        ui.label(format!(
+                            "{:.1} mm³",
+                            component.voxel_count as f32 * x * y * z
+                        ));
+                        ui.label(
+                            system.format(self.volume.position_in(component.centroid, system)),
+                        );
+                        ui.end_row();
+                    }
+                });
+            if count > Self::MAX_ROWS {
+                ui.label(format!("and {} more", count - Self::MAX_ROWS));
+            }
+        });
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.strong("Region growing");
+        self.growing_ui(ui);
+        ui.separator();
+        ui.strong("Connected components");
+        self.components_ui(ui);
+    }
+}
@@ -0,0 +1,208 @@
+use cgmath::{EuclideanSpace, Point3};
+
+use crate::{
+    data::{Roi, RoiShape, RoiStatistics, Volume},
+    processing::roi_statistics,
+};
+
+use super::{slice_renderer::PointTool, SharedSliceState};
+
+/// Regions of interest shared by the slice views and the ROI panel.
+#[derive(Default)]
+pub struct Rois {
+    pub items: Vec<Roi>,
+    /// Statistics of `items`, computed once a region is complete.
+    pub statistics: Vec<Option<RoiStatistics>>,
+    /// Shape the slice views draw, `None` when clicks move the cursor instead.
+    pub tool: Option<RoiShape>,
+    /// Region being drawn.
+    pub pending: Option<Roi>,
+    /// Highlighted in the slice views and the panel.
+    pub selected: Option<usize>,
+}
+
+impl Rois {
+    /// Starts a region drawn in a slice normal to `normal_axis`.
+    pub fn start(&mut self, normal_axis: usize, point: Point3<f32>) {
+        if let Some(shape) = self.tool {
+            let mut roi = Roi::new(shape, normal_axis);
+            roi.points.push(point);
+            self.pending = Some(roi);
+        }
+    }
+
+    /// Places a corner of a rectangle, ellipse or box, the second corner
+    /// completes the region.
+    pub fn add_corner(&mut self, volume: &Volume, normal_axis: usize, point: Point3<f32>) {
+        match &mut self.pending {
+            Some(pending) => {
+                pending.points.push(point);
+                self.finish(volume);
+            }
+            None => self.start(normal_axis, point),
+        }
+    }
+
+    /// Stores the pending region with its statistics, or drops it if it
+    /// lacks points.
+    pub fn finish(&mut self, volume: &Volume) {
+        if let Some(roi) = self.pending.take().filter(Roi::is_complete) {
+            self.statistics.push(roi_statistics(volume, &roi));
+            self.items.push(roi);
+            self.selected = Some(self.items.len() - 1);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.items.remove(index);
+        self.statistics.remove(index);
+        self.selected = match self.selected {
+            Some(selected) if selected > index => Some(selected - 1),
+            Some(selected) if selected == index => None,
+            selected => selected,
+        };
+    }
+
+    /// Returns whether a shape was picked, so other tools can be turned off.
+    pub fn tool_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let previous = self.tool;
+            ui.selectable_value(&mut self.tool, None, "Off");
+            for shape in RoiShape::ALL {
+                ui.selectable_value(&mut self.tool, Some(shape), shape.label());
+            }
+            if self.tool != previous {
+                self.pending = None;
+            }
+            self.tool != previous && self.tool.is_some()
+        })
+        .inner
+    }
+}
+
+/// Lists the regions of interest of the slice views with their statistics.
+pub struct RoiPanel {
+    shared: SharedSliceState,
+}
+
+impl RoiPanel {
+    pub fn new(shared: SharedSliceState) -> Self {
+        Self { shared }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_csv(&self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("roi_statistics.csv")
+            .save_file()
+        else {
+            return;
+        };
+        let rois = self.shared.rois.borrow();
+        if let Err(err) = crate::io::export_roi_statistics(&path, &rois.items, &rois.statistics) {
+            log::error!("Failed to export ROI statistics: {err}");
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.shared.rois.borrow_mut().tool_ui(ui) {
+            self.shared.keep_only_tool(PointTool::Roi);
+        }
+        ui.label("Click two corners in a slice view, or drag to draw a freehand region. The corners of a box may lie in different slices.");
+        ui.separator();
+
+        let mut removed = None;
+        {
+            let mut rois = self.shared.rois.borrow_mut();
+            if rois.items.is_empty() {
+                ui.label("No regions");
+            }
+            let mut headers = vec![
+                "#", "Shape", "Voxels", "Size", "Mean", "Std", "Min", "Max", "Median",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+            headers.extend(RoiStatistics::PERCENTILES.map(|percent| format!("P{percent}")));
+            egui::ScrollArea::both()
+                .max_height(ui.available_height() - 32.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("rois")
+                        .striped(true)
+                        .num_columns(headers.len() + 1)
+                        .show(ui, |ui| {
+                            if rois.items.is_empty() {
+                                return;
+                            }
+                            for header in &headers {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for index in 0..rois.items.len() {
+                                let roi = &rois.items[index];
+                                let selected = rois.selected == Some(index);
+                                let center = Point3::centroid(&roi.points);
+                                let shape = roi.shape;
+                                if ui
+                                    .selectable_label(selected, format!("{}", index + 1))
+                                    .on_hover_text("Select and show in the slice views")
+                                    .clicked()
+                                {
+                                    rois.selected = Some(index);
+                                    self.shared.cursor.borrow_mut().set_position(center);
+                                }
+                                ui.label(shape.label());
+                                match &rois.statistics[index] {
+                                    Some(statistics) => {
+                                        ui.label(statistics.count.to_string());
+                                        ui.label(format!(
+                                            "{:.1} {}",
+                                            statistics.size,
+                                            shape.size_unit()
+                                        ));
+                                        for value in [
+                                            statistics.mean,
+                                            statistics.std,
+                                            statistics.min,
+                                            statistics.max,
+                                            statistics.median,
+                                        ]
+                                        .into_iter()
+                                        .chain(statistics.percentiles)
+                                        {
+                                            ui.label(format!("{value:.1}"));
+                                        }
+                                    }
+                                    None => {
+                                        ui.label("0");
+                                        for _ in 3..headers.len() {
+                                            ui.label("-");
+                                        }
+                                    }
+                                }
+                                if ui.small_button("Delete").clicked() {
+                                    removed = Some(index);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some(index) = removed {
+                rois.remove(index);
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.separator();
+            let empty = self.shared.rois.borrow().items.is_empty();
+            if ui
+                .add_enabled(!empty, egui::Button::new("Export CSV..."))
+                .clicked()
+            {
+                self.export_csv();
+            }
+        }
+    }
+}
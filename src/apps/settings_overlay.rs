@@ -0,0 +1,28 @@
+use egui::{epaint::Shadow, Pos2};
+
+/// Collapsible "Settings:" window pinned to the top left corner of a view.
+pub fn settings_overlay(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    let original_visuals = ui.visuals().clone();
+    let mut visuals = ui.visuals().clone();
+    visuals.window_shadow = Shadow::NONE;
+    // TODO: Implement overlay transparency
+    // visuals.window_fill = visuals.window_fill().gamma_multiply(0.5);
+    ui.ctx().set_visuals(visuals);
+
+    let overlay_position = Pos2 {
+        x: rect.left_top().x + 2.0,
+        y: rect.left_top().y + 2.0,
+    };
+    egui::Window::new("Settings:")
+        .id(ui.next_auto_id())
+        .fixed_pos(overlay_position)
+        .default_open(false)
+        .resizable(false)
+        .show(ui.painter().ctx(), add_contents);
+
+    ui.ctx().set_visuals(original_visuals);
+}
@@ -1,7 +1,13 @@
 // Vertex shader
 
+// maps the view quad onto the zoomed and panned slice
+struct ViewTransform {
+    scale: vec2<f32>,
+    translation: vec2<f32>,
+}
+
 @group(1) @binding(0)
-var<uniform> scale_factor: vec3<f32>;
+var<uniform> view_transform: ViewTransform;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -19,7 +25,8 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
     out.tex_coords = 0.5 * vec2(model.position.x, model.position.y) + 0.5;
-    out.clip_position = vec4<f32>(scale_factor * model.position, 1.0);
+    let position = model.position.xy * view_transform.scale + view_transform.translation;
+    out.clip_position = vec4<f32>(position, model.position.z, 1.0);
     return out;
 }
 
@@ -29,28 +36,219 @@ fn vs_main(
 var t_diffuse: texture_3d<f32>;
 @group(0) @binding(1)
 var s_diffuse: sampler;
+@group(0) @binding(2)
+var t_lookup_table: texture_2d<f32>;
+@group(0) @binding(3)
+var s_lookup_table: sampler;
+@group(0) @binding(4)
+var<uniform> lookup_table_range: vec2<f32>; // values at the first and last entry, normalized like the volume
 @group(2) @binding(0)
 var<uniform> slice_position: f32;
 @group(2) @binding(1)
-var<uniform> axis: i32; // 0 = x, 1 = y, 2 = z
+var<uniform> axis: i32; // 0 = x, 1 = y, 2 = z, 3 = oblique
+@group(2) @binding(2)
+var<uniform> use_transfer_function: i32;
+@group(2) @binding(3)
+var<uniform> render_mode: i32; // 0 = slice, 1 = maximum, 2 = minimum, 3 = average intensity projection
+@group(2) @binding(4)
+var<uniform> slice_count: i32; // voxels along the view axis
+@group(2) @binding(5)
+var<uniform> slab_thickness: i32; // voxels combined by the projection modes
+@group(2) @binding(6)
+var<uniform> plane_transform: mat4x4<f32>; // view coordinates to texture coordinates for oblique slices
+@group(2) @binding(7)
+var<uniform> interpolation: i32; // 0 = nearest, 1 = linear, 2 = cubic B-spline
+@group(2) @binding(8)
+var<uniform> window: vec2<f32>; // normalized values shown black and white in grayscale
 
-fn get_value(position: vec2<f32>) -> vec3<f32> {
+struct LabelOverlay {
+    opacity: f32,
+    outline: i32,
+    enabled: i32,
+    preview_label: u32, // drawn in `preview_color` while a threshold is previewed, 0 = no preview
+    preview_color: vec4<f32>,
+}
+
+@group(3) @binding(0)
+var t_labels: texture_3d<u32>;
+@group(3) @binding(1)
+var t_label_colors: texture_2d<f32>; // one texel per label value, transparent if hidden
+@group(3) @binding(2)
+var<uniform> label_overlay: LabelOverlay;
+
+fn get_value_at(position: vec2<f32>, depth: f32) -> vec3<f32> {
     var value: vec3<f32>;
     if (axis == 0) {
-        value = vec3<f32>(position.x, position.y, slice_position);
+        value = vec3<f32>(position.x, position.y, depth);
     }
     else if (axis == 1) {
-        value = vec3<f32>(position.x, slice_position, position.y);
+        value = vec3<f32>(position.x, depth, position.y);
     }
     else if (axis == 2) {
-        value = vec3<f32>(slice_position, position.x, position.y);
+        value = vec3<f32>(depth, position.x, position.y);
+    }
+    else if (axis == 3) {
+        value = (plane_transform * vec4<f32>(position, depth, 1.0)).xyz;
     }
     return value;
 }
 
+fn sample_linear(coordinates: vec3<f32>) -> f32 {
+    return textureSampleLevel(t_diffuse, s_diffuse, coordinates, 0.0)[0];
+}
+
+// Value of the voxel containing `coordinates`, without any filtering.
+fn sample_nearest(coordinates: vec3<f32>) -> f32 {
+    let size = vec3<i32>(textureDimensions(t_diffuse));
+    let voxel = clamp(vec3<i32>(floor(coordinates * vec3<f32>(size))), vec3<i32>(0), size - 1);
+    return textureLoad(t_diffuse, voxel, 0)[0];
+}
+
+// Cubic B-spline built from eight trilinear samples, following
+// "Fast Third-Order Texture Filtering" (Sigg and Hadwiger, GPU Gems 2).
+fn sample_cubic(coordinates: vec3<f32>) -> f32 {
+    let size = vec3<f32>(textureDimensions(t_diffuse));
+    let position = coordinates * size - 0.5;
+    let index = floor(position);
+    let f = position - index;
+
+    let f2 = f * f;
+    let f3 = f2 * f;
+    let w0 = (1.0 - 3.0 * f + 3.0 * f2 - f3) / 6.0;
+    let w1 = (4.0 - 6.0 * f2 + 3.0 * f3) / 6.0;
+    let w3 = f3 / 6.0;
+    let g0 = w0 + w1;
+    let g1 = 1.0 - g0;
+    // each linear sample blends two neighboring voxels with the weights of the spline
+    let h0 = (index - 0.5 + w1 / g0) / size;
+    let h1 = (index + 1.5 + w3 / g1) / size;
+
+    let x0y0 = mix(sample_linear(vec3<f32>(h0.x, h0.y, h0.z)), sample_linear(vec3<f32>(h0.x, h0.y, h1.z)), g1.z);
+    let x1y0 = mix(sample_linear(vec3<f32>(h1.x, h0.y, h0.z)), sample_linear(vec3<f32>(h1.x, h0.y, h1.z)), g1.z);
+    let x0y1 = mix(sample_linear(vec3<f32>(h0.x, h1.y, h0.z)), sample_linear(vec3<f32>(h0.x, h1.y, h1.z)), g1.z);
+    let x1y1 = mix(sample_linear(vec3<f32>(h1.x, h1.y, h0.z)), sample_linear(vec3<f32>(h1.x, h1.y, h1.z)), g1.z);
+    return mix(mix(x0y0, x1y0, g1.x), mix(x0y1, x1y1, g1.x), g1.y);
+}
+
+// Oblique planes reach beyond the volume, which is black instead of clamped to the border.
+fn sample_volume(coordinates: vec3<f32>) -> f32 {
+    if (any(coordinates < vec3<f32>(0.0)) || any(coordinates > vec3<f32>(1.0))) {
+        return 0.0;
+    }
+    if (interpolation == 0) {
+        return sample_nearest(coordinates);
+    } else if (interpolation == 2) {
+        return sample_cubic(coordinates);
+    }
+    return sample_linear(coordinates);
+}
+
+fn get_value(position: vec2<f32>) -> vec3<f32> {
+    return get_value_at(position, slice_position);
+}
+
+// Combines the voxels of the slab around the current slice according to `render_mode`.
+fn project(position: vec2<f32>) -> f32 {
+    let thickness = clamp(slab_thickness, 1, max(slice_count, 1));
+    let center = slice_position * f32(slice_count);
+    // shift the slab instead of cutting it off at the volume borders
+    let first = clamp(i32(floor(center - 0.5 * f32(thickness) + 0.5)), 0, slice_count - thickness);
+    let last = first + thickness;
+
+    var maximum = 0.0;
+    var minimum = 1.0;
+    var sum = 0.0;
+    for (var i = first; i < last; i++) {
+        let depth = (f32(i) + 0.5) / f32(slice_count);
+        let value = sample_volume(get_value_at(position, depth));
+        maximum = max(maximum, value);
+        minimum = min(minimum, value);
+        sum += value;
+    }
+
+    if (render_mode == 1) {
+        return maximum;
+    } else if (render_mode == 2) {
+        return minimum;
+    }
+    return sum / f32(thickness);
+}
+
+fn apply_transfer_function(value: f32) -> vec4<f32> {
+    // hit the texel centers of the lookup table
+    let size = f32(textureDimensions(t_lookup_table).x);
+    let t = (value - lookup_table_range.x) / (lookup_table_range.y - lookup_table_range.x);
+    let coordinate = (clamp(t, 0.0, 1.0) * (size - 1.0) + 0.5) / size;
+    return textureSample(t_lookup_table, s_lookup_table, vec2<f32>(coordinate, 0.5));
+}
+
+// Label of the voxel containing `coordinates`, background outside the volume.
+fn label_at(coordinates: vec3<f32>) -> u32 {
+    if (any(coordinates < vec3<f32>(0.0)) || any(coordinates > vec3<f32>(1.0))) {
+        return 0u;
+    }
+    let size = vec3<i32>(textureDimensions(t_labels));
+    let voxel = clamp(vec3<i32>(floor(coordinates * vec3<f32>(size))), vec3<i32>(0), size - 1);
+    return textureLoad(t_labels, voxel, 0).r;
+}
+
+// Distance of neighboring label voxels in view coordinates.
+fn label_voxel_step() -> vec2<f32> {
+    let size = vec3<f32>(textureDimensions(t_labels));
+    if (axis == 0) {
+        return 1.0 / size.xy;
+    } else if (axis == 1) {
+        return 1.0 / size.xz;
+    } else if (axis == 2) {
+        return 1.0 / size.yz;
+    }
+    return vec2<f32>(1.0 / max(size.x, max(size.y, size.z)));
+}
+
+// Blends the color of the label in the current slice over `color`, the
+// projection modes show the labels of the slice through the cursor. The
+// label of a threshold preview is shown even with the labels hidden.
+fn apply_labels(position: vec2<f32>, color: vec3<f32>) -> vec3<f32> {
+    if (label_overlay.enabled == 0 && label_overlay.preview_label == 0u) {
+        return color;
+    }
+    let label = label_at(get_value(position));
+    var label_color = textureLoad(t_label_colors, vec2<i32>(i32(label % 256u), i32(label / 256u)), 0);
+    if (label != 0u && label == label_overlay.preview_label) {
+        label_color = label_overlay.preview_color;
+    } else if (label_overlay.enabled == 0) {
+        return color;
+    }
+    if (label == 0u || label_color.a == 0.0) {
+        return color;
+    }
+    if (label_overlay.outline == 1) {
+        let step = label_voxel_step();
+        let inside = label_at(get_value(position + vec2<f32>(step.x, 0.0))) == label
+            && label_at(get_value(position - vec2<f32>(step.x, 0.0))) == label
+            && label_at(get_value(position + vec2<f32>(0.0, step.y))) == label
+            && label_at(get_value(position - vec2<f32>(0.0, step.y))) == label;
+        if (inside) {
+            return color;
+        }
+    }
+    return mix(color, label_color.rgb, label_overlay.opacity);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let position = in.tex_coords;
-    let value = textureSample(t_diffuse, s_diffuse, get_value(position))[0];
-    return vec4<f32>(value, value, value, 1.0);
+    var value = sample_volume(get_value(position));
+    if (render_mode != 0) {
+        value = project(position);
+    }
+    var color: vec3<f32>;
+    if (use_transfer_function == 1) {
+        let transfer_function_color = apply_transfer_function(value);
+        color = transfer_function_color.rgb * transfer_function_color.a;
+    } else {
+        let gray = clamp((value - window.x) / max(window.y - window.x, 1e-6), 0.0, 1.0);
+        color = vec3<f32>(gray);
+    }
+    return vec4<f32>(apply_labels(position, color), 1.0);
 }
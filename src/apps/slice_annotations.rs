@@ -0,0 +1,121 @@
+use cgmath::{InnerSpace, Vector3};
+
+use crate::data::CoordinateSystem;
+
+/// Text and markers painted on top of a slice view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SliceAnnotations {
+    /// Directions at the edges of the view in the selected coordinate system.
+    pub orientation_labels: bool,
+    pub scale_bar: bool,
+    /// Slice index and position in the top right corner.
+    pub slice_info: bool,
+}
+
+impl Default for SliceAnnotations {
+    fn default() -> Self {
+        Self {
+            orientation_labels: true,
+            scale_bar: true,
+            slice_info: true,
+        }
+    }
+}
+
+impl SliceAnnotations {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.orientation_labels, "Orientation");
+            ui.checkbox(&mut self.scale_bar, "Scale Bar");
+            ui.checkbox(&mut self.slice_info, "Slice Info");
+        });
+    }
+}
+
+/// Names the axes of `system` that `direction` mostly points along, e.g. "R",
+/// or "RS" for directions in between.
+pub fn orientation_label(direction: Vector3<f32>, system: CoordinateSystem) -> String {
+    let direction = direction.normalize();
+    let mut axes = [0, 1, 2];
+    axes.sort_by(|a, b| direction[*b].abs().total_cmp(&direction[*a].abs()));
+    axes.iter()
+        // the dominant axis always passes, its component is at least 1/sqrt(3)
+        .filter(|&&axis| direction[axis].abs() > 0.3)
+        .map(|&axis| {
+            let (negative, positive) = system.axis_names()[axis];
+            if direction[axis] < 0.0 {
+                negative
+            } else {
+                positive
+            }
+        })
+        .collect()
+}
+
+/// Longest length of 1, 2 or 5 times a power of ten not exceeding `maximum`
+/// mm, together with the decimals needed to print it. None if there is no
+/// such length, e.g. for an empty view.
+pub fn scale_bar_length(maximum: f32) -> Option<(f32, usize)> {
+    // zero, subnormal and non-finite values have no usable power of ten
+    if !maximum.is_normal() || maximum < 0.0 {
+        return None;
+    }
+    let exponent = maximum.log10().floor() as i32;
+    let power = 10_f32.powi(exponent);
+    let factor = [5.0, 2.0, 1.0]
+        .into_iter()
+        .find(|factor| factor * power <= maximum)
+        .unwrap_or(1.0);
+    Some((factor * power, (-exponent).max(0) as usize))
+}
+
+/// Paints `text` on a dark background, placed relative to `position` by `anchor`.
+pub fn paint_text(
+    painter: &egui::Painter,
+    position: egui::Pos2,
+    anchor: egui::Align2,
+    text: String,
+) {
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.0), egui::Color32::WHITE);
+    let text_rect = anchor.anchor_rect(egui::Rect::from_min_size(position, galley.size()));
+    painter.rect_filled(
+        text_rect.expand(2.0),
+        2.0,
+        egui::Color32::from_black_alpha(160),
+    );
+    painter.galley(text_rect.min, galley);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_bar_steps_through_1_2_5() {
+        assert_eq!(scale_bar_length(1.0), Some((1.0, 0)));
+        assert_eq!(scale_bar_length(4.9), Some((2.0, 0)));
+        assert_eq!(scale_bar_length(5.0), Some((5.0, 0)));
+        assert_eq!(scale_bar_length(19.0), Some((10.0, 0)));
+        assert_eq!(scale_bar_length(73.0), Some((50.0, 0)));
+        assert_eq!(scale_bar_length(250.0), Some((200.0, 0)));
+    }
+
+    #[test]
+    fn scale_bar_prints_the_decimals_of_small_lengths() {
+        let (length, decimals) = scale_bar_length(0.3).unwrap();
+        assert_eq!(decimals, 1);
+        assert_eq!(format!("{length:.decimals$}"), "0.2");
+        let (length, decimals) = scale_bar_length(0.06).unwrap();
+        assert_eq!(decimals, 2);
+        assert_eq!(format!("{length:.decimals$}"), "0.05");
+    }
+
+    #[test]
+    fn scale_bar_needs_a_positive_length() {
+        for maximum in [0.0, -1.0, f32::MIN_POSITIVE / 2.0, f32::INFINITY, f32::NAN] {
+            assert_eq!(scale_bar_length(maximum), None, "{maximum}");
+        }
+    }
+}
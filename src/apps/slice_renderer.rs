@@ -1,23 +1,57 @@
-use std::sync::Arc;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    sync::Arc,
+};
 
+use cgmath::{InnerSpace, SquareMatrix};
 use eframe::{
     egui_wgpu::wgpu::util::DeviceExt,
     egui_wgpu::{self, wgpu},
 };
-use egui::{epaint::Shadow, Pos2};
+
+use super::{
+    annotations::Annotations,
+    cine::Cine,
+    cursor::Cursor,
+    curved_path::CurvedPath,
+    label_overlay::LabelOverlay,
+    labels::{LabelTool, Labels},
+    measurements::Measurements,
+    oblique_plane::ObliquePlane,
+    rois::Rois,
+    settings_overlay::settings_overlay,
+    slice_annotations::{orientation_label, paint_text, scale_bar_length, SliceAnnotations},
+    statistics_task::VolumeStatistics,
+    view_transform::ViewTransform,
+};
+use crate::data::{
+    Annotation, AnnotationKind, CoordinateSystem, Roi, RoiShape, SliceOrientation, Volume,
+    WindowLevel,
+};
 
 // We need this for Rust to store our data correctly for the shaders
 #[repr(C)]
 // This is so we can store this in a buffer
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-struct Vector3 {
-    data: [f32; 3],
+struct ViewTransformUniform {
+    scale: [f32; 2],
+    translation: [f32; 2],
 }
 
-impl Vector3 {
-    fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { data: [x, y, z] }
-    }
+/// Per frame values written to the uniform buffers.
+#[derive(Clone, Copy)]
+struct SliceParameters {
+    slice_position: f32,
+    axis: i32,
+    use_transfer_function: i32,
+    render_mode: i32,
+    slice_count: i32,
+    slab_thickness: i32,
+    plane_transform: [[f32; 4]; 4],
+    interpolation: i32,
+    window: [f32; 2],
+    view_transform: ViewTransformUniform,
 }
 
 struct SliceRenderResources {
@@ -26,45 +60,83 @@ struct SliceRenderResources {
     index_buffer: wgpu::Buffer,
     uniform_buffer_slice_position: wgpu::Buffer,
     uniform_buffer_volume_axis: wgpu::Buffer,
-    uniform_buffer_fullscreen_factor: wgpu::Buffer,
+    uniform_buffer_use_transfer_function: wgpu::Buffer,
+    uniform_buffer_render_mode: wgpu::Buffer,
+    uniform_buffer_slice_count: wgpu::Buffer,
+    uniform_buffer_slab_thickness: wgpu::Buffer,
+    uniform_buffer_plane_transform: wgpu::Buffer,
+    uniform_buffer_interpolation: wgpu::Buffer,
+    uniform_buffer_window: wgpu::Buffer,
+    uniform_buffer_view_transform: wgpu::Buffer,
     texture_bind_group: wgpu::BindGroup,
-    fullscreen_factor_bind_group: wgpu::BindGroup,
+    view_transform_bind_group: wgpu::BindGroup,
     bind_group_slice_position: wgpu::BindGroup,
 }
 
 impl SliceRenderResources {
-    fn prepare(
-        &self,
-        _device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        slice_position: f32,
-        axis: i32,
-        fullscreen_factor: Vector3,
-    ) {
+    fn prepare(&self, _device: &wgpu::Device, queue: &wgpu::Queue, parameters: SliceParameters) {
         queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(INDICES));
         queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(VERTICES));
         queue.write_buffer(
-            &self.uniform_buffer_fullscreen_factor,
+            &self.uniform_buffer_view_transform,
             0,
-            bytemuck::cast_slice(&[fullscreen_factor]),
+            bytemuck::cast_slice(&[parameters.view_transform]),
         );
         queue.write_buffer(
             &self.uniform_buffer_slice_position,
             0,
-            bytemuck::cast_slice(&[slice_position]),
+            bytemuck::cast_slice(&[parameters.slice_position]),
         );
         queue.write_buffer(
             &self.uniform_buffer_volume_axis,
             0,
-            bytemuck::cast_slice(&[axis]),
+            bytemuck::cast_slice(&[parameters.axis]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_use_transfer_function,
+            0,
+            bytemuck::cast_slice(&[parameters.use_transfer_function]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_render_mode,
+            0,
+            bytemuck::cast_slice(&[parameters.render_mode]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_slice_count,
+            0,
+            bytemuck::cast_slice(&[parameters.slice_count]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_slab_thickness,
+            0,
+            bytemuck::cast_slice(&[parameters.slab_thickness]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_plane_transform,
+            0,
+            bytemuck::cast_slice(&[parameters.plane_transform]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_interpolation,
+            0,
+            bytemuck::cast_slice(&[parameters.interpolation]),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer_window,
+            0,
+            bytemuck::cast_slice(&[parameters.window]),
         );
     }
 
-    fn paint<'rp>(&'rp self, render_pass: &mut wgpu::RenderPass<'rp>) {
+    fn paint<'rp>(&'rp self, render_pass: &mut wgpu::RenderPass<'rp>, labels: &'rp LabelOverlay) {
         render_pass.set_pipeline(&self.render_pipeline);
 
-        // fullscreen_factor
-        render_pass.set_bind_group(1, &self.fullscreen_factor_bind_group, &[]);
+        // label map
+        render_pass.set_bind_group(3, &labels.bind_group, &[]);
+
+        // view transform
+        render_pass.set_bind_group(1, &self.view_transform_bind_group, &[]);
         // slice position
         render_pass.set_bind_group(2, &self.bind_group_slice_position, &[]);
         // volume data
@@ -81,31 +153,234 @@ pub enum VolumeAxis {
     Axial,
     Coronal,
     Sagittal,
+    /// Arbitrary plane given by the shared `ObliquePlane`.
+    Oblique,
+}
+
+impl VolumeAxis {
+    /// `None` for the oblique plane.
+    fn orientation(self) -> Option<SliceOrientation> {
+        match self {
+            Self::Axial => Some(SliceOrientation::Axial),
+            Self::Coronal => Some(SliceOrientation::Coronal),
+            Self::Sagittal => Some(SliceOrientation::Sagittal),
+            Self::Oblique => None,
+        }
+    }
 }
 
 impl From<VolumeAxis> for i32 {
     fn from(v: VolumeAxis) -> i32 {
-        match v {
-            x if x == VolumeAxis::Axial => 0,
-            x if x == VolumeAxis::Axial => 1,
-            x if x == VolumeAxis::Axial => 2,
-            _ => -1,
+        v.orientation().map_or(3, SliceOrientation::shader_axis)
+    }
+}
+
+/// What a slice view shows along its view axis.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SliceRenderMode {
+    Slice,
+    MaximumIntensityProjection,
+    MinimumIntensityProjection,
+    AverageIntensityProjection,
+}
+
+impl SliceRenderMode {
+    const ALL: [Self; 4] = [
+        Self::Slice,
+        Self::MaximumIntensityProjection,
+        Self::MinimumIntensityProjection,
+        Self::AverageIntensityProjection,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Slice => "Slice",
+            Self::MaximumIntensityProjection => "MIP",
+            Self::MinimumIntensityProjection => "MinIP",
+            Self::AverageIntensityProjection => "Mean",
+        }
+    }
+}
+
+impl From<SliceRenderMode> for i32 {
+    fn from(mode: SliceRenderMode) -> i32 {
+        match mode {
+            SliceRenderMode::Slice => 0,
+            SliceRenderMode::MaximumIntensityProjection => 1,
+            SliceRenderMode::MinimumIntensityProjection => 2,
+            SliceRenderMode::AverageIntensityProjection => 3,
+        }
+    }
+}
+
+/// How the volume is sampled between voxel centers.
+#[derive(Clone, Copy, PartialEq)]
+enum Interpolation {
+    /// Shows crisp voxels.
+    Nearest,
+    Linear,
+    /// Smooth, but blurs slightly since the B-spline does not pass through the voxel values.
+    CubicBSpline,
+}
+
+impl Interpolation {
+    const ALL: [Self; 3] = [Self::Nearest, Self::Linear, Self::CubicBSpline];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Nearest => "Nearest",
+            Self::Linear => "Linear",
+            Self::CubicBSpline => "Cubic B-Spline",
+        }
+    }
+}
+
+impl From<Interpolation> for i32 {
+    fn from(interpolation: Interpolation) -> i32 {
+        match interpolation {
+            Interpolation::Nearest => 0,
+            Interpolation::Linear => 1,
+            Interpolation::CubicBSpline => 2,
+        }
+    }
+}
+
+/// Unit the slab thickness is edited in.
+#[derive(Clone, Copy, PartialEq)]
+enum SlabUnit {
+    Voxels,
+    Millimeters,
+}
+
+impl SlabUnit {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Voxels => "voxels",
+            Self::Millimeters => "mm",
+        }
+    }
+}
+
+/// Part of the oblique plane overlay grabbed with the pointer.
+#[derive(Clone, Copy, PartialEq)]
+enum PlaneHandle {
+    Center,
+    Rotation,
+}
+
+/// State linking the slice views, clones share it.
+#[derive(Clone)]
+pub struct SharedSliceState {
+    /// The slices shown pass through the cursor.
+    pub cursor: Rc<RefCell<Cursor>>,
+    /// Zoom of the views with synchronized zoom.
+    pub zoom: Rc<Cell<f32>>,
+    pub oblique_plane: Rc<RefCell<ObliquePlane>>,
+    pub curved_path: Rc<RefCell<CurvedPath>>,
+    /// Coordinate system positions are shown in.
+    pub coordinate_system: Rc<Cell<CoordinateSystem>>,
+    pub measurements: Rc<RefCell<Measurements>>,
+    pub rois: Rc<RefCell<Rois>>,
+    pub annotations: Rc<RefCell<Annotations>>,
+    /// Grayscale window of the views without transfer function.
+    pub window: Rc<Cell<WindowLevel>>,
+    /// Label map blended over the slices.
+    pub labels: Rc<RefCell<Labels>>,
+    /// Size of the volume in mm.
+    pub extent: cgmath::Vector3<f32>,
+    pub volume_statistics: Rc<RefCell<VolumeStatistics>>,
+}
+
+impl SharedSliceState {
+    pub fn new(dimensions: (u32, u32, u32), spacing: (f32, f32, f32)) -> Self {
+        let extent = cgmath::Vector3::new(
+            dimensions.0 as f32 * spacing.0,
+            dimensions.1 as f32 * spacing.1,
+            dimensions.2 as f32 * spacing.2,
+        );
+        Self {
+            cursor: Rc::new(RefCell::new(Cursor::new(dimensions, spacing))),
+            zoom: Rc::new(Cell::new(1.0)),
+            oblique_plane: Rc::new(RefCell::new(ObliquePlane::new(extent))),
+            curved_path: Rc::new(RefCell::new(CurvedPath::default())),
+            coordinate_system: Rc::new(Cell::new(CoordinateSystem::default())),
+            measurements: Rc::new(RefCell::new(Measurements::default())),
+            rois: Rc::new(RefCell::new(Rois::default())),
+            annotations: Rc::new(RefCell::new(Annotations::default())),
+            window: Rc::new(Cell::new(WindowLevel::default())),
+            labels: Rc::new(RefCell::new(Labels::default())),
+            extent,
+            volume_statistics: Rc::new(RefCell::new(VolumeStatistics::default())),
+        }
+    }
+}
+
+/// Tools placing points with clicks in the slice views.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PointTool {
+    Measurement,
+    Roi,
+    Annotation,
+    Labels,
+}
+
+impl SharedSliceState {
+    /// Turns off the tools other than `active`, so clicks place one thing only.
+    pub fn keep_only_tool(&self, active: PointTool) {
+        if active != PointTool::Measurement {
+            let mut measurements = self.measurements.borrow_mut();
+            measurements.tool = None;
+            measurements.pending = None;
+        }
+        if active != PointTool::Roi {
+            let mut rois = self.rois.borrow_mut();
+            rois.tool = None;
+            rois.pending = None;
+        }
+        if active != PointTool::Annotation {
+            let mut annotations = self.annotations.borrow_mut();
+            annotations.tool = None;
+            annotations.pending = None;
+        }
+        if active != PointTool::Labels {
+            let mut labels = self.labels.borrow_mut();
+            labels.tool = None;
+            labels.end_stroke();
         }
     }
 }
 
 pub struct SliceRenderer {
     id: egui::Id,
-    slice_position: u32,
-    scale: egui::Rect,
+    /// CPU-side data for the voxel probe.
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+    show_crosshair: bool,
+    view: ViewTransform,
+    /// Use the zoom shared with the other slice views.
+    sync_zoom: bool,
     axis: VolumeAxis,
     dimensions: (u32, u32, u32),
+    spacing: (f32, f32, f32),
+    use_transfer_function: bool,
+    render_mode: SliceRenderMode,
+    interpolation: Interpolation,
+    /// Number of slices combined by the projection modes.
+    slab_thickness: u32,
+    slab_unit: SlabUnit,
+    dragged_handle: Option<PlaneHandle>,
+    /// Measurement and point index of the grabbed measurement point.
+    dragged_point: Option<(usize, usize)>,
+    /// Wheel scrolling not yet turned into slice steps.
+    scroll_remainder: f32,
+    cine: Cine,
+    pub annotations: SliceAnnotations,
     pub show_settings_oberlay: bool,
 }
 
 impl SliceRenderer {
-    fn fullscreen_factor(viewport: egui::Rect, volume: egui::Rect) -> Vector3 {
-        let volume_aspect_ratio = volume.width() / volume.height();
+    fn fullscreen_factor(viewport: egui::Rect, extent: egui::Vec2) -> egui::Vec2 {
+        let volume_aspect_ratio = extent.x / extent.y;
         let viewport_aspect_ratio = viewport.width() / viewport.height();
 
         let mut scale_x: f32 = 1.0;
@@ -117,30 +392,79 @@ impl SliceRenderer {
             scale_x = volume_aspect_ratio / viewport_aspect_ratio;
         }
 
-        Vector3::new(scale_x, scale_y, 1.0)
+        egui::vec2(scale_x, scale_y)
     }
     pub fn axial(
         wgpu_render_state: &egui_wgpu::RenderState,
         texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<Volume>,
+        shared: SharedSliceState,
     ) -> Option<Self> {
-        Self::new(wgpu_render_state, texture, VolumeAxis::Axial)
+        Self::new(
+            wgpu_render_state,
+            texture,
+            lookup_table,
+            volume,
+            shared,
+            VolumeAxis::Axial,
+        )
     }
     pub fn saggital(
         wgpu_render_state: &egui_wgpu::RenderState,
         texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<Volume>,
+        shared: SharedSliceState,
     ) -> Option<Self> {
-        Self::new(wgpu_render_state, texture, VolumeAxis::Coronal)
+        Self::new(
+            wgpu_render_state,
+            texture,
+            lookup_table,
+            volume,
+            shared,
+            VolumeAxis::Sagittal,
+        )
     }
     pub fn coronal(
         wgpu_render_state: &egui_wgpu::RenderState,
         texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<Volume>,
+        shared: SharedSliceState,
+    ) -> Option<Self> {
+        Self::new(
+            wgpu_render_state,
+            texture,
+            lookup_table,
+            volume,
+            shared,
+            VolumeAxis::Coronal,
+        )
+    }
+    pub fn oblique(
+        wgpu_render_state: &egui_wgpu::RenderState,
+        texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<Volume>,
+        shared: SharedSliceState,
     ) -> Option<Self> {
-        Self::new(wgpu_render_state, texture, VolumeAxis::Sagittal)
+        Self::new(
+            wgpu_render_state,
+            texture,
+            lookup_table,
+            volume,
+            shared,
+            VolumeAxis::Oblique,
+        )
     }
 
     fn new(
         wgpu_render_state: &egui_wgpu::RenderState,
         texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<Volume>,
+        shared: SharedSliceState,
         axis: VolumeAxis,
     ) -> Option<Self> {
         // Get the WGPU render state from the eframe creation context. This can also be retrieved
@@ -170,10 +494,47 @@ impl SliceRenderer {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: (true) },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
                 label: Some("texture_bind_group_layout"),
             });
 
+        // the shader filters per view, nearest neighbor reads the texels directly
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
         let texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &texture_bind_group_layout,
             entries: &[
@@ -183,22 +544,37 @@ impl SliceRenderer {
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&lookup_table.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&lookup_table.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: lookup_table.range_buffer.as_entire_binding(),
                 },
             ],
             label: Some("diffuse_bind_group"),
         });
 
-        let fullscreen_factor = Vector3::new(1.0, 1.0, 1.0);
+        let view_transform = ViewTransformUniform {
+            scale: [1.0, 1.0],
+            translation: [0.0, 0.0],
+        };
 
-        let uniform_buffer_fullscreen_factor =
+        let uniform_buffer_view_transform =
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("fullscreen_factor Buffer"),
-                contents: bytemuck::cast_slice(&[fullscreen_factor]),
+                label: Some("View Transform Buffer"),
+                contents: bytemuck::cast_slice(&[view_transform]),
                 usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             });
 
-        let fullscreen_factor_bind_group_layout =
+        let view_transform_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[wgpu::BindGroupLayoutEntry {
                     binding: 0,
@@ -210,28 +586,22 @@ impl SliceRenderer {
                     },
                     count: None,
                 }],
-                label: Some("fullscreen_factor_bind_group_layout"),
+                label: Some("view_transform_bind_group_layout"),
             });
 
-        let fullscreen_factor_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &fullscreen_factor_bind_group_layout,
+        let view_transform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &view_transform_bind_group_layout,
             entries: &[wgpu::BindGroupEntry {
                 binding: 0,
-                resource: uniform_buffer_fullscreen_factor.as_entire_binding(),
+                resource: uniform_buffer_view_transform.as_entire_binding(),
             }],
-            label: Some("fullscreen_factor_bind_group"),
+            label: Some("view_transform_bind_group"),
         });
 
-        let slice_position: u32 = match axis {
-            VolumeAxis::Axial => texture.dimensions.0 / 2,
-            VolumeAxis::Coronal => texture.dimensions.1 / 2,
-            VolumeAxis::Sagittal => texture.dimensions.2 / 2,
-        };
-
         let uniform_buffer_slice_position =
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Slice position"),
-                contents: bytemuck::cast_slice(&[slice_position]),
+                contents: bytemuck::cast_slice(&[0.5_f32]),
                 // Mapping at creation (as done by the create_buffer_init utility) doesn't require us to to add the MAP_WRITE usage
                 // (this *happens* to workaround this bug )
                 usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
@@ -248,6 +618,54 @@ impl SliceRenderer {
                 usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
             });
 
+        let uniform_buffer_use_transfer_function =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Use Transfer Function"),
+                contents: bytemuck::cast_slice(&[0_i32]),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        let uniform_buffer_render_mode =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Render Mode"),
+                contents: bytemuck::cast_slice(&[i32::from(SliceRenderMode::Slice)]),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        let uniform_buffer_slice_count =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Slice Count"),
+                contents: bytemuck::cast_slice(&[1_i32]),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        let uniform_buffer_slab_thickness =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Slab Thickness"),
+                contents: bytemuck::cast_slice(&[1_i32]),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        let uniform_buffer_plane_transform =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Plane Transform"),
+                contents: bytemuck::cast_slice(&[[[0.0_f32; 4]; 4]]),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        let uniform_buffer_interpolation =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Interpolation"),
+                contents: bytemuck::cast_slice(&[i32::from(Interpolation::Linear)]),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            });
+
+        let uniform_buffer_window = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Window"),
+            contents: bytemuck::cast_slice(&[[0.0_f32, 1.0]]),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+        });
+
         let bind_group_layout_slice_position =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Slice position"),
@@ -272,6 +690,76 @@ impl SliceRenderer {
                         },
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 5,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 6,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 7,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 8,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
@@ -287,6 +775,34 @@ impl SliceRenderer {
                     binding: 1,
                     resource: uniform_buffer_volume_axis.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer_use_transfer_function.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: uniform_buffer_render_mode.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: uniform_buffer_slice_count.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: uniform_buffer_slab_thickness.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: uniform_buffer_plane_transform.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: uniform_buffer_interpolation.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: uniform_buffer_window.as_entire_binding(),
+                },
             ],
         });
 
@@ -295,16 +811,20 @@ impl SliceRenderer {
             source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
         });
 
-        let render_pipeline_layout =
+        let render_pipeline_layout = {
+            let renderer = wgpu_render_state.renderer.read();
+            let label_overlay: &LabelOverlay = renderer.paint_callback_resources.get().unwrap();
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
                 bind_group_layouts: &[
                     &texture_bind_group_layout,
-                    &fullscreen_factor_bind_group_layout,
+                    &view_transform_bind_group_layout,
                     &bind_group_layout_slice_position,
+                    &label_overlay.bind_group_layout,
                 ],
                 push_constant_ranges: &[],
-            });
+            })
+        };
 
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
@@ -359,9 +879,16 @@ impl SliceRenderer {
             index_buffer,
             uniform_buffer_slice_position,
             uniform_buffer_volume_axis,
-            uniform_buffer_fullscreen_factor,
+            uniform_buffer_use_transfer_function,
+            uniform_buffer_render_mode,
+            uniform_buffer_slice_count,
+            uniform_buffer_slab_thickness,
+            uniform_buffer_plane_transform,
+            uniform_buffer_interpolation,
+            uniform_buffer_window,
+            uniform_buffer_view_transform,
             texture_bind_group,
-            fullscreen_factor_bind_group,
+            view_transform_bind_group,
             bind_group_slice_position,
         };
 
@@ -382,57 +909,1275 @@ impl SliceRenderer {
             }
         }
 
-        let height = match axis {
-            VolumeAxis::Axial => texture.dimensions.0 as f32 * texture.spacing.0,
-            VolumeAxis::Coronal => texture.dimensions.2 as f32 * texture.spacing.2,
-            VolumeAxis::Sagittal => texture.dimensions.2 as f32 * texture.spacing.2,
-        };
-        let width = match axis {
-            VolumeAxis::Axial => texture.dimensions.1 as f32 * texture.spacing.1,
-            VolumeAxis::Coronal => texture.dimensions.1 as f32 * texture.spacing.1,
-            VolumeAxis::Sagittal => texture.dimensions.0 as f32 * texture.spacing.0,
-        };
-        let scale = egui::Rect::from_two_pos(
-            egui::Pos2::new(-width, -height),
-            egui::Pos2::new(width, height),
-        );
-
-        Some(Self {
+        let mut slice_renderer = Self {
             id,
-            slice_position,
-            scale,
+            volume,
+            shared,
+            show_crosshair: true,
+            view: ViewTransform::default(),
+            sync_zoom: false,
             axis,
             dimensions: texture.dimensions,
+            spacing: texture.spacing,
+            use_transfer_function: false,
+            render_mode: SliceRenderMode::Slice,
+            interpolation: Interpolation::Linear,
+            slab_thickness: 1,
+            slab_unit: SlabUnit::Voxels,
+            dragged_handle: None,
+            dragged_point: None,
+            scroll_remainder: 0.0,
+            cine: Cine::default(),
+            annotations: SliceAnnotations::default(),
             show_settings_oberlay: true,
-        })
+        };
+        // project through the whole volume by default
+        slice_renderer.slab_thickness = slice_renderer.slice_count();
+
+        Some(slice_renderer)
     }
-}
 
-impl SliceRenderer {
-    // pub fn custom_painting(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
-    pub fn custom_painting(&mut self, ui: &mut egui::Ui) {
-        let availbale_size = ui.available_size_before_wrap();
-        let (rect, _response) = ui.allocate_exact_size(availbale_size, egui::Sense::hover());
+    /// Number of voxels along the direction `get_value` in the shader slices through.
+    fn slice_count(&self) -> u32 {
+        match self.axis.orientation() {
+            Some(orientation) => orientation.slice_count(self.dimensions),
+            // steps of the finest voxel spacing through the depth of the plane
+            None => {
+                let spacing = self.spacing.0.min(self.spacing.1).min(self.spacing.2);
+                (ObliquePlane::size(self.shared.extent) / spacing).ceil() as u32
+            }
+        }
+    }
 
-        // Clone locals so we can move them into the paint callback:
-        let axis = match self.axis {
-            VolumeAxis::Axial => 0,
-            VolumeAxis::Coronal => 1,
-            VolumeAxis::Sagittal => 2,
-        };
-        let slice_position: f32 = match self.axis {
-            VolumeAxis::Axial => self.slice_position as f32 / self.dimensions.0 as f32,
-            VolumeAxis::Coronal => self.slice_position as f32 / self.dimensions.1 as f32,
-            VolumeAxis::Sagittal => self.slice_position as f32 / self.dimensions.2 as f32,
-        };
+    /// Voxel spacing in mm along the same direction as `slice_count`.
+    fn slice_spacing(&self) -> f32 {
+        match self.axis.orientation() {
+            Some(orientation) => orientation.slice_spacing(self.spacing),
+            None => ObliquePlane::size(self.shared.extent) / self.slice_count() as f32,
+        }
+    }
 
-        let fullscreen_factor = Self::fullscreen_factor(rect, self.scale);
+    /// Depth of the slice in [0, 1] as passed to the shader.
+    fn normalized_slice_position(&self) -> f32 {
+        match self.axis.orientation() {
+            Some(orientation) => {
+                orientation.depth(self.shared.cursor.borrow().texture_coordinates())
+            }
+            // the plane lies in the middle of its depth range
+            None => 0.5,
+        }
+    }
 
-        let id = self.id;
+    /// Texture coordinates shown at `position` in [0, 1] of the view, the
+    /// CPU counterpart of `get_value` in the shader.
+    fn texture_coordinates(&self, position: egui::Vec2) -> cgmath::Vector3<f32> {
+        let depth = self.normalized_slice_position();
+        let position = cgmath::Vector2::new(position.x, position.y);
+        match self.axis.orientation() {
+            Some(orientation) => orientation.texture_coordinates(position, depth),
+            None => {
+                let transform = self
+                    .shared
+                    .oblique_plane
+                    .borrow()
+                    .transform(self.shared.extent);
+                (transform * position.extend(depth).extend(1.0)).truncate()
+            }
+        }
+    }
 
-        // The callback function for WGPU is in two stages: prepare, and paint.
-        //
-        // The prepare callback is called every frame before paint and is given access to the wgpu
+    /// Position in [0, 1] of the view at `pointer`, the inverse of the vertex shader.
+    fn pointer_to_view(slice_rect: egui::Rect, pointer: egui::Pos2) -> egui::Vec2 {
+        egui::vec2(
+            (pointer.x - slice_rect.left()) / slice_rect.width(),
+            (slice_rect.bottom() - pointer.y) / slice_rect.height(),
+        )
+    }
+
+    /// Point of the volume in mm shown at `pointer` within `slice_rect`.
+    fn volume_position(&self, slice_rect: egui::Rect, pointer: egui::Pos2) -> cgmath::Point3<f32> {
+        let coordinates = self.texture_coordinates(Self::pointer_to_view(slice_rect, pointer));
+        let extent = self.shared.extent;
+        cgmath::Point3::new(
+            coordinates.x * extent.x,
+            coordinates.y * extent.y,
+            coordinates.z * extent.z,
+        )
+    }
+
+    /// Position in [0, 1] of the view showing `coordinates` in texture
+    /// coordinates, the inverse of `texture_coordinates` ignoring the depth.
+    fn view_position(&self, coordinates: cgmath::Vector3<f32>) -> egui::Vec2 {
+        let position = match self.axis.orientation() {
+            Some(orientation) => orientation.view_position(coordinates),
+            None => {
+                let transform = self
+                    .shared
+                    .oblique_plane
+                    .borrow()
+                    .transform(self.shared.extent);
+                let position = transform.invert().unwrap_or_else(cgmath::Matrix4::identity)
+                    * coordinates.extend(1.0);
+                position.truncate().truncate()
+            }
+        };
+        egui::vec2(position.x, position.y)
+    }
+
+    /// Axis of the volume the slice position of this view moves along.
+    fn slice_axis(&self) -> Option<usize> {
+        self.axis.orientation().map(SliceOrientation::normal_axis)
+    }
+
+    /// Area of `rect` covered by the slice.
+    fn slice_rect(&self, rect: egui::Rect) -> egui::Rect {
+        self.view.content_rect(rect, self.fitted_size(rect))
+    }
+
+    /// Width and height of the slice in mm.
+    fn slice_extent(&self) -> egui::Vec2 {
+        match self.axis.orientation() {
+            Some(orientation) => {
+                let extent = orientation.extent(self.dimensions, self.spacing);
+                egui::vec2(extent.x, extent.y)
+            }
+            None => egui::Vec2::splat(ObliquePlane::size(self.shared.extent)),
+        }
+    }
+
+    /// Size of the slice when fit into `rect`.
+    fn fitted_size(&self, rect: egui::Rect) -> egui::Vec2 {
+        rect.size() * Self::fullscreen_factor(rect, self.slice_extent())
+    }
+
+    /// Zoom at which one voxel along the horizontal view axis covers one pixel.
+    fn one_to_one_zoom(&self, rect: egui::Rect, pixels_per_point: f32) -> f32 {
+        let voxels = match self.axis.orientation() {
+            Some(orientation) => orientation.size(self.dimensions).x,
+            None => self.slice_count(),
+        };
+        voxels as f32 / (self.fitted_size(rect).x * pixels_per_point)
+    }
+
+    /// Ctrl+wheel and pinching zoom around the pointer, dragging pans and
+    /// double-clicking fits the slice into the view again.
+    fn handle_view_input(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let fitted_size = self.fitted_size(rect);
+
+        if let Some(pointer) = response.hover_pos() {
+            let factor = ui.input(|i| i.zoom_delta());
+            if factor != 1.0 {
+                self.view.zoom_around(pointer, factor, rect, fitted_size);
+            }
+        }
+        // the oblique plane handles and measurement points take the drag once
+        // grabbed, freehand regions and labels are drawn with the primary button
+        let drawing = self.shared.rois.borrow().tool == Some(RoiShape::Freehand)
+            || matches!(
+                self.shared.labels.borrow().tool,
+                Some(LabelTool::Brush | LabelTool::Eraser)
+            );
+        let dragged = (response.dragged_by(egui::PointerButton::Primary) && !drawing)
+            || response.dragged_by(egui::PointerButton::Secondary)
+            || response.dragged_by(egui::PointerButton::Middle);
+        if dragged
+            && !response.drag_started()
+            && self.dragged_handle.is_none()
+            && self.dragged_point.is_none()
+        {
+            self.view.pan_by(response.drag_delta(), fitted_size);
+        }
+        if response.double_clicked() && !self.is_placing_points() {
+            self.view = ViewTransform::default();
+        }
+    }
+
+    /// Moves the slice by `steps` slices, the oblique plane along its normal.
+    fn step_slice(&mut self, steps: i32) {
+        match self.slice_axis() {
+            Some(axis) => {
+                let mut cursor = self.shared.cursor.borrow_mut();
+                let index = (cursor.voxel(axis) as i32 + steps).max(0);
+                cursor.set_voxel(axis, index as u32);
+            }
+            None => {
+                let delta = self.shared.oblique_plane.borrow().normal()
+                    * (steps as f32 * self.slice_spacing());
+                self.shared
+                    .oblique_plane
+                    .borrow_mut()
+                    .translate(delta, self.shared.extent);
+            }
+        }
+    }
+
+    /// Wheel, arrow keys and PageUp/PageDown step through the slices of the
+    /// hovered view, Home/End jump to the first and last slice.
+    fn handle_slice_navigation(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        /// Wheel scrolling in points per slice.
+        const SCROLL_STEP: f32 = 40.0;
+
+        if !response.hovered() {
+            self.scroll_remainder = 0.0;
+            return;
+        }
+
+        self.scroll_remainder += ui.input(|i| i.scroll_delta.y);
+        let scrolled = (self.scroll_remainder / SCROLL_STEP).trunc();
+        self.scroll_remainder -= scrolled * SCROLL_STEP;
+        let mut steps = scrolled as i32;
+
+        let page = (self.slice_count() / 10).max(1) as i32;
+        // leave the keys to a focused widget such as the slice slider
+        let keys_free = ui.memory(|memory| memory.focus().is_none());
+        let (home, end) = ui.input(|i| {
+            if !keys_free {
+                return (false, false);
+            }
+            for (key, delta) in [
+                (egui::Key::ArrowUp, 1),
+                (egui::Key::ArrowRight, 1),
+                (egui::Key::ArrowDown, -1),
+                (egui::Key::ArrowLeft, -1),
+                (egui::Key::PageUp, page),
+                (egui::Key::PageDown, -page),
+            ] {
+                if i.key_pressed(key) {
+                    steps += delta;
+                }
+            }
+            (
+                i.key_pressed(egui::Key::Home),
+                i.key_pressed(egui::Key::End),
+            )
+        });
+        if steps != 0 {
+            self.step_slice(steps);
+        }
+        if let Some(axis) = self.slice_axis() {
+            let mut cursor = self.shared.cursor.borrow_mut();
+            if home {
+                cursor.set_voxel(axis, 0);
+            } else if end {
+                cursor.set_voxel(axis, u32::MAX);
+            }
+        }
+    }
+
+    /// Steps to the next slice while cine playback is running.
+    fn play_cine(&mut self, ui: &egui::Ui) {
+        let Some(axis) = self.slice_axis() else {
+            return;
+        };
+        let count = self.slice_count();
+        let index = self.shared.cursor.borrow().voxel(axis);
+        let time = ui.input(|i| i.time);
+        if let Some(index) = self.cine.advance(time, index, count) {
+            self.shared.cursor.borrow_mut().set_voxel(axis, index);
+        }
+        if self.cine.playing {
+            ui.ctx().request_repaint();
+        }
+    }
+
+    fn view_settings(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        ui.horizontal(|ui| {
+            if ui.button("Fit").clicked() {
+                self.view = ViewTransform::default();
+            }
+            if ui
+                .button("1:1")
+                .on_hover_text("One voxel per pixel")
+                .clicked()
+            {
+                let zoom = self.one_to_one_zoom(rect, ui.ctx().pixels_per_point());
+                let fitted_size = self.fitted_size(rect);
+                self.view
+                    .zoom_around(rect.center(), zoom / self.view.zoom, rect, fitted_size);
+            }
+            ui.checkbox(&mut self.sync_zoom, "Sync Zoom");
+            ui.label(format!("{:.0}%", self.view.zoom * 100.0))
+                .on_hover_text(
+                    "Ctrl+wheel or pinch to zoom, the wheel alone steps through the slices",
+                );
+        });
+    }
+
+    /// Path of the curved planar reformation, drawn in the axial view only.
+    fn curved_path(&self) -> Option<&Rc<RefCell<CurvedPath>>> {
+        (self.axis == VolumeAxis::Axial).then_some(&self.shared.curved_path)
+    }
+
+    fn is_drawing_path(&self) -> bool {
+        self.curved_path().is_some_and(|path| path.borrow().drawing)
+    }
+
+    fn slab_settings(&mut self, ui: &mut egui::Ui) {
+        let slice_count = self.slice_count();
+        let spacing = self.slice_spacing();
+
+        ui.horizontal(|ui| {
+            match self.slab_unit {
+                SlabUnit::Voxels => {
+                    ui.add(
+                        egui::Slider::new(&mut self.slab_thickness, 1..=slice_count)
+                            .text("Slab Thickness"),
+                    );
+                }
+                SlabUnit::Millimeters => {
+                    let mut thickness = self.slab_thickness as f32 * spacing;
+                    let response = ui.add(
+                        egui::Slider::new(&mut thickness, spacing..=slice_count as f32 * spacing)
+                            .text("Slab Thickness"),
+                    );
+                    if response.changed() {
+                        self.slab_thickness =
+                            ((thickness / spacing).round() as u32).clamp(1, slice_count);
+                    }
+                }
+            }
+            egui::ComboBox::from_id_source("slab_unit")
+                .width(60.0)
+                .selected_text(self.slab_unit.label())
+                .show_ui(ui, |ui| {
+                    for unit in [SlabUnit::Voxels, SlabUnit::Millimeters] {
+                        ui.selectable_value(&mut self.slab_unit, unit, unit.label());
+                    }
+                });
+        });
+    }
+}
+
+impl SliceRenderer {
+    /// Distance of the rotation handle from the center handle in points.
+    const HANDLE_DISTANCE: f32 = 60.0;
+    const HANDLE_GRAB_RADIUS: f32 = 8.0;
+
+    fn oblique_plane_settings(&mut self, ui: &mut egui::Ui) {
+        let mut plane = self.shared.oblique_plane.borrow_mut();
+        let normal = plane.normal();
+        let center = cgmath::Point3::new(0.0, 0.0, 0.0) + self.shared.extent / 2.0;
+
+        // distance of the plane from the center of the volume
+        let offset = normal.dot(plane.origin - center);
+        let mut new_offset = offset;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut new_offset)
+                    .speed(0.5)
+                    .suffix(" mm"),
+            );
+            ui.label("Offset");
+        });
+        if new_offset != offset {
+            plane.translate(normal * (new_offset - offset), self.shared.extent);
+        }
+        ui.horizontal(|ui| {
+            ui.drag_angle(&mut plane.rotation);
+            ui.label("In-Plane Rotation");
+        });
+        ui.label(format!(
+            "Normal: ({:.2}, {:.2}, {:.2})",
+            normal.x, normal.y, normal.z
+        ));
+        if ui
+            .button("Reset Plane")
+            .on_hover_text("Drag the handles in the other slice views to move and rotate the plane")
+            .clicked()
+        {
+            plane.reset(self.shared.extent);
+        }
+    }
+
+    /// Draws the line where the oblique plane cuts this view, with handles to
+    /// move the plane within and rotate it around the view direction.
+    fn oblique_plane_overlay(
+        &mut self,
+        ui: &egui::Ui,
+        rect: egui::Rect,
+        response: &egui::Response,
+    ) {
+        let slice_rect = self.slice_rect(rect);
+        let to_screen = |position: egui::Vec2| {
+            egui::pos2(
+                slice_rect.left() + position.x * slice_rect.width(),
+                slice_rect.bottom() - position.y * slice_rect.height(),
+            )
+        };
+
+        // view axes in mm
+        let plane = *self.shared.oblique_plane.borrow();
+        let extent = self.shared.extent;
+        let to_mm = |position: egui::Vec2| {
+            let coordinates = self.texture_coordinates(position);
+            cgmath::Vector3::new(
+                coordinates.x * extent.x,
+                coordinates.y * extent.y,
+                coordinates.z * extent.z,
+            )
+        };
+        let base = to_mm(egui::vec2(0.0, 0.0));
+        let horizontal = to_mm(egui::vec2(1.0, 0.0)) - base;
+        let vertical = to_mm(egui::vec2(0.0, 1.0)) - base;
+        let origin = plane.origin - cgmath::Point3::new(0.0, 0.0, 0.0) - base;
+
+        // the plane cuts the view along alpha * x + beta * y = gamma
+        let normal = plane.normal();
+        let alpha = normal.dot(horizontal);
+        let beta = normal.dot(vertical);
+        let gamma = normal.dot(origin);
+        if alpha.hypot(beta) < 1e-4 * horizontal.magnitude().max(vertical.magnitude()) {
+            // parallel to the view
+            return;
+        }
+
+        let mut crossings = Vec::new();
+        if beta.abs() > f32::EPSILON {
+            for x in [0.0, 1.0] {
+                crossings.push(egui::vec2(x, (gamma - alpha * x) / beta));
+            }
+        }
+        if alpha.abs() > f32::EPSILON {
+            for y in [0.0, 1.0] {
+                crossings.push(egui::vec2((gamma - beta * y) / alpha, y));
+            }
+        }
+        crossings.retain(|c| (0.0..=1.0).contains(&c.x) && (0.0..=1.0).contains(&c.y));
+        let Some((start, end)) = crossings
+            .iter()
+            .flat_map(|&a| crossings.iter().map(move |&b| (to_screen(a), to_screen(b))))
+            .max_by(|(a0, a1), (b0, b1)| a0.distance(*a1).total_cmp(&b0.distance(*b1)))
+        else {
+            return;
+        };
+        let length = start.distance(end);
+        if length < 1.0 {
+            return;
+        }
+
+        // center handle where the plane origin projects onto the line
+        let direction = (end - start) / length;
+        let origin_on_screen = to_screen(egui::vec2(
+            origin.dot(horizontal) / horizontal.magnitude2(),
+            origin.dot(vertical) / vertical.magnitude2(),
+        ));
+        let along = (origin_on_screen - start).dot(direction).clamp(0.0, length);
+        let center = start + direction * along;
+        let rotation_handle = if along + Self::HANDLE_DISTANCE <= length {
+            center + direction * Self::HANDLE_DISTANCE
+        } else {
+            center - direction * Self::HANDLE_DISTANCE
+        };
+
+        if response.drag_started() {
+            let press_origin = ui.input(|i| i.pointer.press_origin());
+            self.dragged_handle = press_origin.and_then(|pointer| {
+                if pointer.distance(rotation_handle) < Self::HANDLE_GRAB_RADIUS {
+                    Some(PlaneHandle::Rotation)
+                } else if pointer.distance(center) < Self::HANDLE_GRAB_RADIUS {
+                    Some(PlaneHandle::Center)
+                } else {
+                    None
+                }
+            });
+        }
+        if response.drag_released() {
+            self.dragged_handle = None;
+        }
+        if let (Some(handle), Some(pointer)) =
+            (self.dragged_handle, response.interact_pointer_pos())
+        {
+            let delta = response.drag_delta();
+            let mut plane = self.shared.oblique_plane.borrow_mut();
+            match handle {
+                PlaneHandle::Center => {
+                    let x = delta.x / slice_rect.width();
+                    let y = -delta.y / slice_rect.height();
+                    plane.translate(horizontal * x + vertical * y, self.shared.extent);
+                }
+                PlaneHandle::Rotation => {
+                    let previous = (pointer - delta - center).angle();
+                    let current = (pointer - center).angle();
+                    let angle = (current - previous + std::f32::consts::PI)
+                        .rem_euclid(std::f32::consts::TAU)
+                        - std::f32::consts::PI;
+                    // screen y points down, so positive angles turn clockwise
+                    plane.rotate(horizontal.cross(vertical), -angle);
+                }
+            }
+        }
+
+        let painter = ui.painter_at(rect);
+        let color = egui::Color32::from_rgb(255, 200, 0);
+        let stroke = egui::Stroke::new(1.5, color);
+        painter.line_segment([start, end], stroke);
+        painter.circle_stroke(center, 5.0, stroke);
+        painter.circle_filled(rotation_handle, 4.0, color);
+    }
+
+    fn curved_path_settings(&mut self, ui: &mut egui::Ui) {
+        let Some(curved_path) = self.curved_path() else {
+            return;
+        };
+        let mut path = curved_path.borrow_mut();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut path.drawing, "Draw Curved Path")
+                .on_hover_text(
+                "Click to add points, right-click removes the last point, double-click finishes",
+            );
+            if ui.button("Clear").clicked() {
+                path.points.clear();
+            }
+        });
+        ui.checkbox(&mut path.smooth, "Smooth Path");
+    }
+
+    /// Draws the path of the curved planar reformation projected onto this
+    /// view and adds points to it while drawing.
+    fn curved_path_overlay(&self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let Some(curved_path) = self.curved_path() else {
+            return;
+        };
+        let slice_rect = self.slice_rect(rect);
+        let extent = self.shared.extent;
+        let mut path = curved_path.borrow_mut();
+
+        if path.drawing {
+            if response.double_clicked() {
+                // the first click of the double click already added the last point
+                path.drawing = false;
+            } else if response.clicked() {
+                if let Some(pointer) = response
+                    .interact_pointer_pos()
+                    .filter(|pointer| slice_rect.contains(*pointer))
+                {
+                    path.points.push(self.volume_position(slice_rect, pointer));
+                }
+            } else if response.secondary_clicked() {
+                path.points.pop();
+            }
+        }
+
+        let to_screen = |point: cgmath::Point3<f32>| {
+            egui::pos2(
+                slice_rect.left() + point.x / extent.x * slice_rect.width(),
+                slice_rect.bottom() - point.y / extent.y * slice_rect.height(),
+            )
+        };
+        let painter = ui.painter_at(rect);
+        let color = egui::Color32::from_rgb(0, 200, 255);
+        painter.add(egui::Shape::line(
+            path.polyline().into_iter().map(to_screen).collect(),
+            egui::Stroke::new(1.5, color),
+        ));
+        for &point in &path.points {
+            painter.circle_filled(to_screen(point), 3.0, color);
+        }
+    }
+
+    /// Whether clicks place points of a curved path, a measurement, a region
+    /// of interest or an annotation instead of moving the cursor.
+    fn is_placing_points(&self) -> bool {
+        self.is_drawing_path()
+            || self.shared.measurements.borrow().tool.is_some()
+            || self.shared.rois.borrow().tool.is_some()
+            || self.shared.annotations.borrow().tool.is_some()
+            || self.shared.labels.borrow().tool.is_some()
+    }
+
+    /// Screen position of `position` given in mm, and whether it lies within
+    /// half a slice of the shown slice.
+    fn project_onto_slice(
+        &self,
+        slice_rect: egui::Rect,
+        position: cgmath::Point3<f32>,
+    ) -> (egui::Pos2, bool) {
+        let extent = self.shared.extent;
+        let coordinates = cgmath::Vector3::new(
+            position.x / extent.x,
+            position.y / extent.y,
+            position.z / extent.z,
+        );
+        let view = self.view_position(coordinates);
+        let depth = match self.axis.orientation() {
+            Some(orientation) => orientation.depth(coordinates),
+            None => {
+                let transform = self
+                    .shared
+                    .oblique_plane
+                    .borrow()
+                    .transform(self.shared.extent);
+                let position = transform.invert().unwrap_or_else(cgmath::Matrix4::identity)
+                    * coordinates.extend(1.0);
+                position.z
+            }
+        };
+        let distance = (depth - self.normalized_slice_position()) * self.slice_count() as f32;
+        (
+            egui::pos2(
+                slice_rect.left() + view.x * slice_rect.width(),
+                slice_rect.bottom() - view.y * slice_rect.height(),
+            ),
+            distance.abs() <= 0.5,
+        )
+    }
+
+    /// Places new measurement points with the selected tool, lets points be
+    /// dragged and draws the measurements lying in the shown slice.
+    fn measurement_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        const GRAB_RADIUS: f32 = 6.0;
+
+        let slice_rect = self.slice_rect(rect);
+        let measurements = self.shared.measurements.clone();
+        let mut measurements = measurements.borrow_mut();
+
+        if measurements.tool.is_some() {
+            if response.double_clicked() {
+                // the first click of the double click already added the last point
+                measurements.finish();
+            } else if response.clicked() {
+                if let Some(pointer) = response
+                    .interact_pointer_pos()
+                    .filter(|pointer| slice_rect.contains(*pointer))
+                {
+                    measurements.add_point(self.volume_position(slice_rect, pointer));
+                }
+            } else if response.secondary_clicked() {
+                measurements.remove_point();
+            }
+            if response.hovered() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                measurements.pending = None;
+            }
+        }
+
+        // project once, the slice stays the same while painting
+        let projected: Vec<Option<Vec<egui::Pos2>>> = measurements
+            .items
+            .iter()
+            .map(|measurement| {
+                let points: Vec<_> = measurement
+                    .points
+                    .iter()
+                    .map(|&point| self.project_onto_slice(slice_rect, point))
+                    .collect();
+                points
+                    .iter()
+                    .all(|(_, on_slice)| *on_slice)
+                    .then(|| points.into_iter().map(|(position, _)| position).collect())
+            })
+            .collect();
+
+        if response.drag_started_by(egui::PointerButton::Primary) {
+            let press_origin = ui.input(|i| i.pointer.press_origin());
+            self.dragged_point = press_origin.and_then(|pointer| {
+                projected.iter().enumerate().find_map(|(index, points)| {
+                    points
+                        .as_ref()?
+                        .iter()
+                        .position(|point| point.distance(pointer) < GRAB_RADIUS)
+                        .map(|point| (index, point))
+                })
+            });
+            if let Some((index, _)) = self.dragged_point {
+                measurements.selected = Some(index);
+            }
+        }
+        if response.drag_released() {
+            self.dragged_point = None;
+        }
+        if let (Some((index, point)), Some(pointer)) =
+            (self.dragged_point, response.interact_pointer_pos())
+        {
+            if let Some(measurement) = measurements.items.get_mut(index) {
+                measurement.points[point] = self.volume_position(slice_rect, pointer);
+            }
+        }
+
+        let painter = ui.painter_at(rect);
+        let paint = |measurement: &crate::data::Measurement, points: &[egui::Pos2], color| {
+            let stroke = egui::Stroke::new(1.5, color);
+            let mut line = points.to_vec();
+            if measurement.closed && points.len() > 2 {
+                line.push(points[0]);
+            }
+            painter.add(egui::Shape::line(line, stroke));
+            for &point in points {
+                painter.circle_stroke(point, 3.0, stroke);
+            }
+            if let Some(&last) = points.last() {
+                painter.text(
+                    last + egui::vec2(8.0, -8.0),
+                    egui::Align2::LEFT_BOTTOM,
+                    measurement.format_value(),
+                    egui::FontId::proportional(13.0),
+                    color,
+                );
+            }
+        };
+        for (index, (measurement, points)) in measurements.items.iter().zip(&projected).enumerate()
+        {
+            let Some(points) = points else {
+                continue;
+            };
+            let color = if measurements.selected == Some(index) {
+                egui::Color32::YELLOW
+            } else {
+                egui::Color32::from_rgb(255, 140, 0)
+            };
+            paint(measurement, points, color);
+        }
+
+        // rubber band from the last placed point to the pointer
+        if let Some(pending) = &measurements.pending {
+            let mut preview = pending.clone();
+            if let Some(pointer) = response.hover_pos().filter(|p| slice_rect.contains(*p)) {
+                preview
+                    .points
+                    .push(self.volume_position(slice_rect, pointer));
+            }
+            let points: Vec<_> = preview
+                .points
+                .iter()
+                .map(|&point| self.project_onto_slice(slice_rect, point).0)
+                .collect();
+            paint(&preview, &points, egui::Color32::YELLOW);
+        }
+    }
+
+    /// Screen outline of `roi` if it covers the shown slice.
+    fn roi_outline(&self, slice_rect: egui::Rect, roi: &Roi) -> Option<Vec<egui::Pos2>> {
+        const ELLIPSE_SEGMENTS: usize = 64;
+
+        let axis = self.slice_axis()?;
+        if !roi.shape.is_3d() && roi.normal_axis != axis {
+            return None;
+        }
+        let slice = self.shared.cursor.borrow().voxel(axis);
+        let (first, last) = roi.index_range(axis, self.dimensions, self.spacing)?;
+        if !(first..=last).contains(&slice) {
+            return None;
+        }
+
+        let points: Vec<_> = roi
+            .points
+            .iter()
+            .map(|&point| self.project_onto_slice(slice_rect, point).0)
+            .collect();
+        if roi.shape == RoiShape::Freehand {
+            return Some(points);
+        }
+        let &[corner, opposite, ..] = &points[..] else {
+            return None;
+        };
+        let bounds = egui::Rect::from_two_pos(corner, opposite);
+        Some(match roi.shape {
+            RoiShape::Ellipse => (0..ELLIPSE_SEGMENTS)
+                .map(|segment| {
+                    let angle = segment as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+                    bounds.center() + egui::vec2(angle.cos(), angle.sin()) * bounds.size() / 2.0
+                })
+                .collect(),
+            _ => vec![
+                bounds.left_top(),
+                bounds.right_top(),
+                bounds.right_bottom(),
+                bounds.left_bottom(),
+            ],
+        })
+    }
+
+    /// Draws regions of interest with the selected tool: two clicks place
+    /// the corners of rectangles, ellipses and boxes, dragging draws a
+    /// freehand region. Shows the regions covering the shown slice.
+    fn roi_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        /// Distance in points between two freehand outline points.
+        const FREEHAND_STEP: f32 = 3.0;
+
+        let Some(axis) = self.slice_axis() else {
+            return;
+        };
+        let slice_rect = self.slice_rect(rect);
+        let rois = self.shared.rois.clone();
+        let mut rois = rois.borrow_mut();
+
+        match rois.tool {
+            Some(RoiShape::Freehand) => {
+                if response.drag_started_by(egui::PointerButton::Primary) {
+                    let press_origin = ui.input(|i| i.pointer.press_origin());
+                    if let Some(origin) = press_origin.filter(|p| slice_rect.contains(*p)) {
+                        rois.start(axis, self.volume_position(slice_rect, origin));
+                    }
+                } else if response.drag_released() {
+                    rois.finish(&self.volume);
+                } else if let (Some(pending), Some(pointer)) =
+                    (&mut rois.pending, response.interact_pointer_pos())
+                {
+                    let pointer = slice_rect.clamp(pointer);
+                    let far_enough = match pending.points.last() {
+                        Some(&last) => {
+                            self.project_onto_slice(slice_rect, last)
+                                .0
+                                .distance(pointer)
+                                >= FREEHAND_STEP
+                        }
+                        None => true,
+                    };
+                    if far_enough {
+                        pending
+                            .points
+                            .push(self.volume_position(slice_rect, pointer));
+                    }
+                }
+            }
+            Some(_) => {
+                if response.clicked() {
+                    if let Some(pointer) = response
+                        .interact_pointer_pos()
+                        .filter(|pointer| slice_rect.contains(*pointer))
+                    {
+                        let position = self.volume_position(slice_rect, pointer);
+                        rois.add_corner(&self.volume, axis, position);
+                    }
+                } else if response.secondary_clicked() {
+                    rois.pending = None;
+                }
+            }
+            None => {}
+        }
+        if rois.tool.is_some()
+            && response.hovered()
+            && ui.input(|i| i.key_pressed(egui::Key::Escape))
+        {
+            rois.pending = None;
+        }
+
+        let painter = ui.painter_at(rect);
+        let paint = |outline: Vec<egui::Pos2>, color, label: Option<String>| {
+            let top_right = outline.iter().fold(
+                egui::pos2(f32::NEG_INFINITY, f32::INFINITY),
+                |corner, point| egui::pos2(corner.x.max(point.x), corner.y.min(point.y)),
+            );
+            painter.add(egui::Shape::closed_line(
+                outline,
+                egui::Stroke::new(1.5, color),
+            ));
+            if let Some(label) = label {
+                painter.text(
+                    top_right + egui::vec2(4.0, -4.0),
+                    egui::Align2::LEFT_BOTTOM,
+                    label,
+                    egui::FontId::proportional(13.0),
+                    color,
+                );
+            }
+        };
+        for (index, (roi, statistics)) in rois.items.iter().zip(&rois.statistics).enumerate() {
+            let Some(outline) = self.roi_outline(slice_rect, roi) else {
+                continue;
+            };
+            let color = if rois.selected == Some(index) {
+                egui::Color32::YELLOW
+            } else {
+                egui::Color32::from_rgb(0, 180, 255)
+            };
+            let label = match statistics {
+                Some(statistics) => format!(
+                    "{}: {:.1} ± {:.1}",
+                    index + 1,
+                    statistics.mean,
+                    statistics.std
+                ),
+                None => format!("{}: empty", index + 1),
+            };
+            paint(outline, color, Some(label));
+        }
+
+        // rubber band from the first corner to the pointer
+        if let Some(pending) = &rois.pending {
+            let mut preview = pending.clone();
+            if preview.shape != RoiShape::Freehand {
+                if let Some(pointer) = response.hover_pos().filter(|p| slice_rect.contains(*p)) {
+                    preview
+                        .points
+                        .push(self.volume_position(slice_rect, pointer));
+                }
+            }
+            if let Some(outline) = self.roi_outline(slice_rect, &preview) {
+                paint(outline, egui::Color32::YELLOW, None);
+            }
+        }
+    }
+
+    /// Places annotations with the selected tool and draws the annotations
+    /// anchored in the shown slice.
+    fn annotation_overlay(&self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let slice_rect = self.slice_rect(rect);
+        let annotations = self.shared.annotations.clone();
+        let mut annotations = annotations.borrow_mut();
+
+        if annotations.tool.is_some() {
+            if response.clicked() {
+                if let Some(pointer) = response
+                    .interact_pointer_pos()
+                    .filter(|pointer| slice_rect.contains(*pointer))
+                {
+                    annotations.add_point(self.volume_position(slice_rect, pointer));
+                }
+            } else if response.secondary_clicked()
+                || response.hovered() && ui.input(|i| i.key_pressed(egui::Key::Escape))
+            {
+                annotations.pending = None;
+            }
+        }
+
+        let painter = ui.painter_at(rect);
+        let paint = |annotation: &Annotation, color: egui::Color32| {
+            let (head, on_slice) = self.project_onto_slice(slice_rect, annotation.position);
+            if !on_slice {
+                return;
+            }
+            let label = match (annotation.kind, annotation.tail) {
+                (AnnotationKind::Arrow, Some(tail)) => {
+                    let tail = self.project_onto_slice(slice_rect, tail).0;
+                    painter.arrow(tail, head - tail, egui::Stroke::new(2.0, color));
+                    tail - egui::vec2(0.0, 4.0)
+                }
+                (AnnotationKind::Point, _) => {
+                    painter.circle_filled(head, 4.0, color);
+                    head + egui::vec2(6.0, -6.0)
+                }
+                _ => {
+                    painter.circle_filled(head, 2.0, color);
+                    head + egui::vec2(4.0, -4.0)
+                }
+            };
+            if !annotation.text.is_empty() {
+                let anchor = if annotation.kind == AnnotationKind::Arrow {
+                    egui::Align2::CENTER_BOTTOM
+                } else {
+                    egui::Align2::LEFT_BOTTOM
+                };
+                paint_text(&painter, label, anchor, annotation.text.clone());
+            }
+        };
+        for (index, annotation) in annotations.items.iter().enumerate() {
+            let color = if annotations.selected == Some(index) {
+                egui::Color32::YELLOW
+            } else {
+                egui::Color32::from_rgb(255, 90, 200)
+            };
+            paint(annotation, color);
+        }
+
+        // arrow from the pointer to the placed head
+        if let Some(pending) = &annotations.pending {
+            if let Some(pointer) = response.hover_pos().filter(|p| slice_rect.contains(*p)) {
+                let preview = Annotation {
+                    tail: Some(self.volume_position(slice_rect, pointer)),
+                    ..pending.clone()
+                };
+                paint(&preview, egui::Color32::YELLOW);
+            }
+        }
+    }
+
+    /// Paints labels while dragging the brush or eraser, fills or grows
+    /// regions on clicks and outlines the brush at the pointer.
+    fn label_editing_overlay(&self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let Some(axis) = self.slice_axis() else {
+            return;
+        };
+        let slice_rect = self.slice_rect(rect);
+        let labels = self.shared.labels.clone();
+        let mut labels = labels.borrow_mut();
+        let Some(tool) = labels.tool else {
+            return;
+        };
+
+        let pointer = response
+            .interact_pointer_pos()
+            .filter(|pointer| slice_rect.contains(*pointer));
+        match tool {
+            LabelTool::Brush | LabelTool::Eraser => {
+                if response.dragged_by(egui::PointerButton::Primary) || response.clicked() {
+                    if let Some(pointer) = pointer {
+                        labels.paint(
+                            &self.volume,
+                            self.volume_position(slice_rect, pointer),
+                            axis,
+                        );
+                    }
+                }
+                if response.drag_released() || response.clicked() {
+                    labels.end_stroke();
+                }
+            }
+            LabelTool::Fill => {
+                if let Some(pointer) = pointer.filter(|_| response.clicked()) {
+                    labels.fill(
+                        &self.volume,
+                        self.volume_position(slice_rect, pointer),
+                        axis,
+                    );
+                }
+            }
+            LabelTool::Grow => {
+                if let Some(pointer) = pointer.filter(|_| response.clicked()) {
+                    labels.grow(&self.volume, self.volume_position(slice_rect, pointer));
+                }
+            }
+        }
+
+        if matches!(tool, LabelTool::Fill | LabelTool::Grow) {
+            return;
+        }
+        if let Some(pointer) = response.hover_pos().filter(|p| slice_rect.contains(*p)) {
+            let color = match (tool, labels.label(labels.active)) {
+                (LabelTool::Brush, Some(label)) => {
+                    let [r, g, b] = label.color;
+                    egui::Color32::from_rgb(r, g, b)
+                }
+                _ => egui::Color32::WHITE,
+            };
+            let points_per_mm = slice_rect.width() / self.slice_extent().x;
+            ui.painter_at(rect).circle_stroke(
+                pointer,
+                labels.brush_radius * points_per_mm,
+                egui::Stroke::new(1.0, color),
+            );
+        }
+    }
+
+    /// Moves the shared cursor to clicked points and draws the crosshair through it.
+    fn cursor_overlay(&self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let slice_rect = self.slice_rect(rect);
+        if response.clicked() && !self.is_placing_points() {
+            if let Some(pointer) = response
+                .interact_pointer_pos()
+                .filter(|pointer| slice_rect.contains(*pointer))
+            {
+                let position = self.volume_position(slice_rect, pointer);
+                self.shared.cursor.borrow_mut().set_position(position);
+            }
+        }
+
+        if !self.show_crosshair {
+            return;
+        }
+        let position = self.view_position(self.shared.cursor.borrow().texture_coordinates());
+        let x = slice_rect.left() + position.x * slice_rect.width();
+        let y = slice_rect.bottom() - position.y * slice_rect.height();
+        let painter = ui.painter_at(rect.intersect(slice_rect));
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(0, 255, 100));
+        painter.vline(x, slice_rect.y_range(), stroke);
+        painter.hline(slice_rect.x_range(), y, stroke);
+    }
+
+    /// Shows index, position and original value of the voxel under the pointer
+    /// in a status line at the bottom of the view.
+    fn voxel_probe(&self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let Some(pointer) = response.hover_pos() else {
+            return;
+        };
+        let slice_rect = self.slice_rect(rect);
+        if !slice_rect.contains(pointer) {
+            return;
+        }
+        let coordinates = self.texture_coordinates(Self::pointer_to_view(slice_rect, pointer));
+        if (0..3).any(|i| !(0.0..=1.0).contains(&coordinates[i])) {
+            // oblique planes reach beyond the volume
+            return;
+        }
+
+        let dimensions = [self.dimensions.0, self.dimensions.1, self.dimensions.2];
+        let [i, j, k]: [u32; 3] = std::array::from_fn(|axis| {
+            ((coordinates[axis] * dimensions[axis] as f32) as u32).min(dimensions[axis] - 1)
+        });
+        let position = self.volume_position(slice_rect, pointer);
+        let value = self.volume.value(i, j, k);
+        let mut text = format!(
+            "Voxel: ({i}, {j}, {k})   Position: {}   Value: {value}",
+            self.format_position(position)
+        );
+        let labels = self.shared.labels.borrow();
+        if let Some(map) = &labels.map {
+            let label = map.label(i, j, k);
+            match labels.label(label) {
+                Some(entry) => text += &format!("   Label: {label} ({})", entry.name),
+                None => text += &format!("   Label: {label}"),
+            }
+        }
+
+        paint_text(
+            &ui.painter_at(rect),
+            rect.left_bottom() + egui::vec2(4.0, -4.0),
+            egui::Align2::LEFT_BOTTOM,
+            text,
+        );
+    }
+
+    /// `position` in mm from the corner of the volume, shown in the selected
+    /// coordinate system.
+    fn format_position(&self, position: cgmath::Point3<f32>) -> String {
+        let system = self.shared.coordinate_system.get();
+        let index = self.volume.voxel_coordinates(position);
+        system.format(self.volume.position_in(index, system))
+    }
+
+    /// Directions in mm from the center of the slice to the centers of its
+    /// right and top edges.
+    fn view_axes(&self) -> (cgmath::Vector3<f32>, cgmath::Vector3<f32>) {
+        let extent = self.shared.extent;
+        let center = self.texture_coordinates(egui::vec2(0.5, 0.5));
+        let direction = |position| {
+            let direction = self.texture_coordinates(position) - center;
+            cgmath::Vector3::new(
+                direction.x * extent.x,
+                direction.y * extent.y,
+                direction.z * extent.z,
+            )
+        };
+        (
+            direction(egui::vec2(1.0, 0.5)),
+            direction(egui::vec2(0.5, 1.0)),
+        )
+    }
+
+    /// Paints the orientation labels, the scale bar and the slice info as
+    /// enabled in `annotations`.
+    fn annotations_overlay(&self, ui: &egui::Ui, rect: egui::Rect) {
+        let painter = ui.painter_at(rect);
+        let (right, up) = self.view_axes();
+
+        if self.annotations.orientation_labels {
+            let system = self.shared.coordinate_system.get();
+            for (position, anchor, direction) in [
+                (
+                    rect.right_center() - egui::vec2(4.0, 0.0),
+                    egui::Align2::RIGHT_CENTER,
+                    right,
+                ),
+                (
+                    rect.left_center() + egui::vec2(4.0, 0.0),
+                    egui::Align2::LEFT_CENTER,
+                    -right,
+                ),
+                (
+                    rect.center_top() + egui::vec2(0.0, 4.0),
+                    egui::Align2::CENTER_TOP,
+                    up,
+                ),
+                (
+                    rect.center_bottom() - egui::vec2(0.0, 4.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    -up,
+                ),
+            ] {
+                let direction = self.volume.direction_in(direction, system);
+                paint_text(
+                    &painter,
+                    position,
+                    anchor,
+                    orientation_label(direction, system),
+                );
+            }
+        }
+
+        if self.annotations.scale_bar {
+            // `right` spans half of the slice
+            let mm_per_point = right.magnitude() / (0.5 * self.slice_rect(rect).width());
+            if let Some((length, decimals)) = scale_bar_length(0.2 * rect.width() * mm_per_point) {
+                let width = length / mm_per_point;
+                let end = rect.right_bottom() + egui::vec2(-12.0, -24.0);
+                let start = end - egui::vec2(width, 0.0);
+                let stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+                painter.line_segment([start, end], stroke);
+                for x in [start.x, end.x] {
+                    painter.vline(x, (end.y - 4.0)..=(end.y + 4.0), stroke);
+                }
+                paint_text(
+                    &painter,
+                    egui::pos2(start.x + 0.5 * width, end.y - 8.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    format!("{length:.decimals$} mm"),
+                );
+            }
+        }
+
+        if self.annotations.slice_info {
+            let text = match self.slice_axis() {
+                Some(axis) => {
+                    let cursor = *self.shared.cursor.borrow();
+                    format!(
+                        "Slice {}/{}   {}",
+                        cursor.voxel(axis) + 1,
+                        self.slice_count(),
+                        self.format_position(cursor.position())
+                    )
+                }
+                None => {
+                    let origin = self.shared.oblique_plane.borrow().origin;
+                    format!("Plane: {}", self.format_position(origin))
+                }
+            };
+            paint_text(
+                &painter,
+                rect.right_top() + egui::vec2(-4.0, 4.0),
+                egui::Align2::RIGHT_TOP,
+                text,
+            );
+        }
+    }
+
+    // pub fn custom_painting(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+    pub fn custom_painting(&mut self, ui: &mut egui::Ui) {
+        let availbale_size = ui.available_size_before_wrap();
+        let (rect, response) =
+            ui.allocate_exact_size(availbale_size, egui::Sense::click_and_drag());
+
+        if self.sync_zoom {
+            self.view.zoom = self.shared.zoom.get();
+        }
+        self.handle_view_input(ui, rect, &response);
+        self.handle_slice_navigation(ui, &response);
+        self.play_cine(ui);
+
+        let frame = ui.ctx().frame_nr();
+        if self.axis == VolumeAxis::Oblique {
+            self.shared.oblique_plane.borrow_mut().mark_shown(frame);
+        }
+
+        // Clone locals so we can move them into the paint callback:
+        let parameters = SliceParameters {
+            slice_position: self.normalized_slice_position(),
+            axis: self.axis.into(),
+            use_transfer_function: self.use_transfer_function as i32,
+            render_mode: self.render_mode.into(),
+            slice_count: self.slice_count() as i32,
+            slab_thickness: self.slab_thickness as i32,
+            plane_transform: self
+                .shared
+                .oblique_plane
+                .borrow()
+                .transform(self.shared.extent)
+                .into(),
+            interpolation: self.interpolation.into(),
+            window: {
+                let (lower, upper) = self.shared.window.get().range();
+                [lower / u16::MAX as f32, upper / u16::MAX as f32]
+            },
+            view_transform: {
+                let (scale, translation) =
+                    ViewTransform::clip_transform(rect, self.slice_rect(rect));
+                ViewTransformUniform { scale, translation }
+            },
+        };
+
+        let id = self.id;
+
+        // The callback function for WGPU is in two stages: prepare, and paint.
+        //
+        // The prepare callback is called every frame before paint and is given access to the wgpu
         // Device and Queue, which can be used, for instance, to update buffers and uniforms before
         // rendering.
         //
@@ -448,20 +2193,15 @@ impl SliceRenderer {
                 let resources: &std::collections::HashMap<egui::Id, SliceRenderResources> =
                     paint_callback_resources.get().unwrap();
                 let slice_render_resources = resources.get(&id).unwrap();
-                slice_render_resources.prepare(
-                    device,
-                    queue,
-                    slice_position,
-                    axis,
-                    fullscreen_factor,
-                );
+                slice_render_resources.prepare(device, queue, parameters);
                 Vec::new()
             })
             .paint(move |_info, render_pass, paint_callback_resources| {
                 let resources: &std::collections::HashMap<egui::Id, SliceRenderResources> =
                     paint_callback_resources.get().unwrap();
                 let slice_render_resources = resources.get(&id).unwrap();
-                slice_render_resources.paint(render_pass);
+                let label_overlay: &LabelOverlay = paint_callback_resources.get().unwrap();
+                slice_render_resources.paint(render_pass, label_overlay);
             });
 
         let callback = egui::PaintCallback {
@@ -471,42 +2211,86 @@ impl SliceRenderer {
 
         ui.painter().add(callback);
 
+        if self.axis != VolumeAxis::Oblique && self.shared.oblique_plane.borrow().is_shown(frame) {
+            self.oblique_plane_overlay(ui, rect, &response);
+        }
+        self.curved_path_overlay(ui, rect, &response);
+        self.measurement_overlay(ui, rect, &response);
+        self.roi_overlay(ui, rect, &response);
+        self.annotation_overlay(ui, rect, &response);
+        self.label_editing_overlay(ui, rect, &response);
+        self.cursor_overlay(ui, rect, &response);
+        self.annotations_overlay(ui, rect);
+        self.voxel_probe(ui, rect, &response);
+
         // Paint overlay
         if self.show_settings_oberlay {
-            let original_visuals = ui.visuals().clone();
-            let mut visuals = ui.visuals().clone();
-            visuals.window_shadow = Shadow::NONE;
-            // TODO: Implement overlay transparency
-            // visuals.window_fill = visuals.window_fill().gamma_multiply(0.5);
-            ui.ctx().set_visuals(visuals);
-
-            let overlay_position = Pos2 {
-                x: rect.left_top().x + 2.0,
-                y: rect.left_top().y + 2.0,
-            };
-            egui::Window::new("Settings:")
-                .id(ui.next_auto_id())
-                .fixed_pos(overlay_position)
-                .default_open(false)
-                .resizable(false)
-                .show(ui.painter().ctx(), |ui| {
-                    match self.axis {
-                        VolumeAxis::Axial => ui.add(
-                            egui::Slider::new(&mut self.slice_position, 1..=self.dimensions.0)
-                                .text("Slice Position"),
-                        ),
-                        VolumeAxis::Coronal => ui.add(
-                            egui::Slider::new(&mut self.slice_position, 1..=self.dimensions.1)
-                                .text("Slice Position"),
-                        ),
-                        VolumeAxis::Sagittal => ui.add(
-                            egui::Slider::new(&mut self.slice_position, 1..=self.dimensions.2)
-                                .text("Slice Position"),
-                        ),
-                    };
-                });
+            settings_overlay(ui, rect, |ui| {
+                if let Some(axis) = self.slice_axis() {
+                    let slice_count = self.slice_count();
+                    let mut slice = self.shared.cursor.borrow().voxel(axis) + 1;
+                    let response = ui
+                        .add(egui::Slider::new(&mut slice, 1..=slice_count).text("Slice Position"));
+                    if response.changed() {
+                        self.shared.cursor.borrow_mut().set_voxel(axis, slice - 1);
+                    }
+                    self.cine.ui(ui);
+                }
+                match self.axis {
+                    VolumeAxis::Axial => self.curved_path_settings(ui),
+                    VolumeAxis::Oblique => self.oblique_plane_settings(ui),
+                    _ => {}
+                }
+                ui.checkbox(&mut self.show_crosshair, "Show Crosshair");
+                ui.label("Measure:");
+                if self.shared.measurements.borrow_mut().tool_ui(ui) {
+                    self.shared.keep_only_tool(PointTool::Measurement);
+                }
+                if self.slice_axis().is_some() {
+                    ui.label("ROI:");
+                    if self.shared.rois.borrow_mut().tool_ui(ui) {
+                        self.shared.keep_only_tool(PointTool::Roi);
+                    }
+                }
+                ui.label("Annotate:");
+                if self.shared.annotations.borrow_mut().tool_ui(ui) {
+                    self.shared.keep_only_tool(PointTool::Annotation);
+                }
+                if self.slice_axis().is_some() {
+                    ui.label("Labels:");
+                    if self.shared.labels.borrow_mut().tool_ui(ui) {
+                        self.shared.keep_only_tool(PointTool::Labels);
+                    }
+                }
+                self.annotations.ui(ui);
+                self.view_settings(ui, rect);
+                egui::ComboBox::from_label("Render Mode")
+                    .selected_text(self.render_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in SliceRenderMode::ALL {
+                            ui.selectable_value(&mut self.render_mode, mode, mode.label());
+                        }
+                    });
+                if self.render_mode != SliceRenderMode::Slice {
+                    self.slab_settings(ui);
+                }
+                egui::ComboBox::from_label("Interpolation")
+                    .selected_text(self.interpolation.label())
+                    .show_ui(ui, |ui| {
+                        for interpolation in Interpolation::ALL {
+                            ui.selectable_value(
+                                &mut self.interpolation,
+                                interpolation,
+                                interpolation.label(),
+                            );
+                        }
+                    });
+                ui.checkbox(&mut self.use_transfer_function, "Apply Transfer Function");
+            });
+        }
 
-            ui.ctx().set_visuals(original_visuals);
+        if self.sync_zoom {
+            self.shared.zoom.set(self.view.zoom);
         }
     }
 }
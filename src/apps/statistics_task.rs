@@ -0,0 +1,80 @@
+use std::{
+    rc::Rc,
+    sync::{
+        mpsc::{self, TryRecvError},
+        Arc,
+    },
+};
+
+use crate::processing::ValueStatistics;
+
+/// Value statistics counted on a thread, so large volumes do not stall the
+/// UI. There are no threads on the web, where they are counted right away.
+#[derive(Default)]
+pub(super) struct StatisticsTask {
+    receiver: Option<mpsc::Receiver<ValueStatistics>>,
+}
+
+impl StatisticsTask {
+    /// Starts counting `values`, the statistics of an earlier start that did
+    /// not finish yet are dropped.
+    pub fn start(&mut self, values: Arc<[u16]>) {
+        let (sender, receiver) = mpsc::channel();
+        let count = move || {
+            // the receiver may have been replaced in the meantime
+            let _ = sender.send(ValueStatistics::new(values.iter().copied()));
+        };
+        #[cfg(target_arch = "wasm32")]
+        count();
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(count);
+        self.receiver = Some(receiver);
+    }
+
+    /// The statistics if they finished since the last call.
+    pub fn finished(&mut self) -> Option<ValueStatistics> {
+        let receiver = self.receiver.as_ref()?;
+        let statistics = match receiver.try_recv() {
+            Ok(statistics) => Some(statistics),
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                log::error!("Failed to compute the volume statistics");
+                None
+            }
+        };
+        self.receiver = None;
+        statistics
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+}
+
+/// Statistics of all volume values, counted once after import and shared by
+/// the panels showing them.
+#[derive(Default)]
+pub struct VolumeStatistics {
+    task: StatisticsTask,
+    statistics: Option<Rc<ValueStatistics>>,
+}
+
+impl VolumeStatistics {
+    /// Starts counting the values of a newly loaded volume.
+    pub fn start(&mut self, values: Arc<[u16]>) {
+        self.statistics = None;
+        self.task.start(values);
+    }
+
+    /// The statistics once they are counted.
+    pub fn get(&mut self) -> Option<Rc<ValueStatistics>> {
+        if let Some(statistics) = self.task.finished() {
+            self.statistics = Some(Rc::new(statistics));
+        }
+        self.statistics.clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.task.is_running()
+    }
+}
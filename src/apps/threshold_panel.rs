@@ -0,0 +1,202 @@
+use std::rc::Rc;
+
+use crate::{
+    data::Volume,
+    processing::{li_threshold, otsu_thresholds, triangle_threshold, ValueStatistics},
+};
+
+use super::{histogram_plot::histogram_plot, SharedSliceState};
+
+/// Ways to pick the threshold from the volume histogram.
+#[derive(Clone, Copy, PartialEq)]
+enum ThresholdMethod {
+    Otsu,
+    MultiOtsu,
+    Triangle,
+    Li,
+}
+
+impl ThresholdMethod {
+    const ALL: [Self; 4] = [Self::Otsu, Self::MultiOtsu, Self::Triangle, Self::Li];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Otsu => "Otsu",
+            Self::MultiOtsu => "Multi-Otsu",
+            Self::Triangle => "Triangle",
+            Self::Li => "Li",
+        }
+    }
+}
+
+/// Segments the voxels within a value range into the active label. The
+/// range is dragged on the histogram or computed from it, and shown in the
+/// slice views until it is committed.
+pub struct ThresholdPanel {
+    volume: Rc<Volume>,
+    shared: SharedSliceState,
+    /// Selected values, inclusive.
+    range: (u16, u16),
+    preview: bool,
+    method: ThresholdMethod,
+    /// Classes multi-Otsu splits the values into.
+    classes: usize,
+    /// Class selected after multi-Otsu, starting at 1 for the darkest.
+    class: usize,
+    /// Commit over voxels of other labels too.
+    overwrite: bool,
+    /// The shared volume statistics once they are counted.
+    statistics: Option<Rc<ValueStatistics>>,
+    /// Value the range drag started at.
+    drag_start: Option<f32>,
+}
+
+impl ThresholdPanel {
+    const BIN_COUNT: usize = 256;
+
+    pub fn new(volume: Rc<Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            volume,
+            shared,
+            range: (u16::MAX / 2, u16::MAX),
+            preview: true,
+            method: ThresholdMethod::Otsu,
+            classes: 3,
+            class: 3,
+            overwrite: false,
+            statistics: None,
+            drag_start: None,
+        }
+    }
+
+    /// Sets the range to the class the method separates, the brighter one
+    /// for the two class methods.
+    fn apply_method(&mut self) {
+        let Some(statistics) = &self.statistics else {
+            return;
+        };
+        let histogram = statistics.histogram(Self::BIN_COUNT);
+        let (min, max) = (statistics.min, statistics.max);
+        let first_value = |threshold: f32| (threshold.ceil().max(0.0) as u16).clamp(min, max);
+        self.range = match self.method {
+            ThresholdMethod::Otsu => (first_value(otsu_thresholds(&histogram, 2)[0]), max),
+            ThresholdMethod::MultiOtsu => {
+                let thresholds = otsu_thresholds(&histogram, self.classes);
+                let class = self.class.clamp(1, thresholds.len() + 1) - 1;
+                let lower = class
+                    .checked_sub(1)
+                    .map_or(min, |previous| first_value(thresholds[previous]));
+                let upper = thresholds
+                    .get(class)
+                    .map_or(max, |&next| first_value(next).saturating_sub(1));
+                (lower, upper.max(lower))
+            }
+            ThresholdMethod::Triangle => (first_value(triangle_threshold(&histogram)), max),
+            ThresholdMethod::Li => {
+                let threshold = li_threshold(&histogram);
+                (first_value(threshold.floor() + 1.0), max)
+            }
+        };
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        // start with the Otsu threshold once the values are counted
+        if self.statistics.is_none() {
+            self.statistics = self.shared.volume_statistics.borrow_mut().get();
+            self.apply_method();
+        }
+        let computing = self.statistics.is_none();
+        if computing {
+            ui.ctx().request_repaint();
+        }
+
+        ui.horizontal(|ui| {
+            let (lower, upper) = &mut self.range;
+            ui.label("Lower:");
+            ui.add(egui::DragValue::new(lower).clamp_range(0..=*upper));
+            ui.label("Upper:");
+            ui.add(egui::DragValue::new(upper).clamp_range(*lower..=u16::MAX));
+            ui.checkbox(&mut self.preview, "Preview")
+                .on_hover_text("Highlight the selected voxels in the slice views");
+            if computing {
+                ui.spinner();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("threshold_method")
+                .selected_text(self.method.label())
+                .show_ui(ui, |ui| {
+                    for method in ThresholdMethod::ALL {
+                        ui.selectable_value(&mut self.method, method, method.label());
+                    }
+                });
+            if self.method == ThresholdMethod::MultiOtsu {
+                ui.label("Classes:");
+                ui.add(egui::DragValue::new(&mut self.classes).clamp_range(3..=4));
+                ui.label("Class:");
+                ui.add(egui::DragValue::new(&mut self.class).clamp_range(1..=self.classes));
+            }
+            if ui
+                .add_enabled(self.statistics.is_some(), egui::Button::new("Apply"))
+                .on_hover_text("Set the range from the volume histogram")
+                .clicked()
+            {
+                self.apply_method();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut labels = self.shared.labels.borrow_mut();
+            ui.label("Label:");
+            ui.add(egui::DragValue::new(&mut labels.active).clamp_range(1..=u16::MAX));
+            ui.checkbox(&mut self.overwrite, "Overwrite other labels");
+            if ui
+                .button("Commit")
+                .on_hover_text("Write the selected voxels into the label map")
+                .clicked()
+            {
+                labels.threshold(&self.volume, self.range, self.overwrite);
+            }
+        });
+        {
+            let mut labels = self.shared.labels.borrow_mut();
+            if self.preview {
+                labels.preview_threshold(&self.volume, self.range, self.overwrite);
+            } else {
+                labels.clear_threshold_preview();
+            }
+        }
+        ui.separator();
+
+        let Some(statistics) = &self.statistics else {
+            ui.label("Computing statistics...");
+            return;
+        };
+        let (lower, upper) = self.range;
+        let selected = statistics.count_between(lower, upper);
+        ui.label(format!(
+            "{selected} voxels selected ({:.1} %)",
+            100.0 * selected as f64 / statistics.count.max(1) as f64
+        ));
+        let histogram = statistics.histogram(Self::BIN_COUNT);
+        let input = histogram_plot(
+            ui,
+            &histogram,
+            histogram.log_heights(),
+            (lower as f32, upper as f32 + 1.0),
+            &mut self.drag_start,
+            "Drag to select the range",
+        );
+        if let Some((lower, upper)) = input.dragged {
+            self.range = (lower.round() as u16, upper.round() as u16);
+        }
+    }
+}
+
+impl Drop for ThresholdPanel {
+    /// Closing the panel ends the preview.
+    fn drop(&mut self) {
+        self.shared.labels.borrow_mut().clear_threshold_preview();
+    }
+}
@@ -0,0 +1,265 @@
+use std::{cell::RefCell, rc::Rc};
+
+use egui::{Color32, Pos2, Rect, Sense, Shape, Stroke, Vec2};
+
+use crate::data::{ControlPoint, Histogram, TransferFunction, Volume};
+
+const POINT_RADIUS: f32 = 5.0;
+const HISTOGRAM_BINS: usize = 256;
+
+pub struct TransferFunctionEditor {
+    transfer_function: Rc<RefCell<TransferFunction>>,
+    histogram: Histogram,
+    selected: Option<usize>,
+    dragged: Option<usize>,
+}
+
+impl TransferFunctionEditor {
+    pub fn new(transfer_function: Rc<RefCell<TransferFunction>>, volume: &Volume) -> Self {
+        Self {
+            transfer_function,
+            histogram: Histogram::new(volume, HISTOGRAM_BINS),
+            selected: None,
+            dragged: None,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            self.file_buttons(ui);
+            ui.separator();
+            self.selected_point_settings(ui);
+        });
+        ui.separator();
+        self.editor_canvas(ui);
+    }
+
+    fn file_buttons(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .button("Reset")
+            .on_hover_text("Linear ramp over the value range of the volume")
+            .clicked()
+        {
+            *self.transfer_function.borrow_mut() =
+                TransferFunction::ramp(self.histogram.min, self.histogram.max);
+            self.selected = None;
+        }
+
+        #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+        {
+            if ui.button("Save...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Transfer Function", &["json"])
+                    .save_file()
+                {
+                    if let Err(err) =
+                        crate::io::save_transfer_function(&path, &self.transfer_function.borrow())
+                    {
+                        log::error!("Failed to save transfer function: {err}");
+                    }
+                }
+            }
+            if ui.button("Load...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Transfer Function", &["json"])
+                    .pick_file()
+                {
+                    match crate::io::load_transfer_function(&path) {
+                        Ok(transfer_function) => {
+                            *self.transfer_function.borrow_mut() = transfer_function;
+                            self.selected = None;
+                        }
+                        Err(err) => log::error!("Failed to load transfer function: {err}"),
+                    }
+                }
+            }
+        }
+    }
+
+    fn selected_point_settings(&mut self, ui: &mut egui::Ui) {
+        let mut transfer_function = self.transfer_function.borrow_mut();
+
+        let Some(index) = self
+            .selected
+            .filter(|&i| i < transfer_function.points().len())
+        else {
+            ui.label("Click to add a point, drag to move it and right click to delete it.");
+            return;
+        };
+
+        let mut point = transfer_function.points()[index];
+        ui.label("Value:");
+        ui.add(egui::DragValue::new(&mut point.value).clamp_range(self.value_range()));
+        ui.label("Opacity:");
+        ui.add(
+            egui::DragValue::new(&mut point.opacity)
+                .clamp_range(0.0..=1.0)
+                .speed(0.01),
+        );
+        ui.label("Color:");
+        ui.color_edit_button_srgb(&mut point.color);
+
+        if point != transfer_function.points()[index] {
+            self.selected = Some(transfer_function.update(index, point));
+        }
+
+        if ui.button("Delete").clicked() {
+            transfer_function.remove(index);
+            self.selected = None;
+        }
+    }
+
+    /// Value range shown on the x axis, never empty.
+    fn value_range(&self) -> std::ops::RangeInclusive<f32> {
+        self.histogram.min..=self.histogram.max.max(self.histogram.min + 1.0)
+    }
+
+    fn editor_canvas(&mut self, ui: &mut egui::Ui) {
+        let size = ui.available_size_before_wrap();
+        let (rect, response) = ui.allocate_exact_size(size, Sense::click_and_drag());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+
+        painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+
+        // leave a margin so points on the border can still be grabbed
+        let plot = rect.shrink(POINT_RADIUS * 2.0);
+        if plot.width() <= 0.0 || plot.height() <= 0.0 {
+            return;
+        }
+
+        let value_range = self.value_range();
+
+        // histogram on a log scale
+        let bar_width = plot.width() / self.histogram.bins.len() as f32;
+        for (i, height) in self.histogram.log_heights().into_iter().enumerate() {
+            let left = plot.left() + i as f32 * bar_width;
+            painter.rect_filled(
+                Rect::from_min_max(
+                    Pos2::new(left, plot.bottom() - height * plot.height()),
+                    Pos2::new(left + bar_width, plot.bottom()),
+                ),
+                0.0,
+                visuals.widgets.inactive.bg_fill,
+            );
+        }
+
+        let to_screen = |point: &ControlPoint| {
+            Pos2::new(
+                egui::remap(point.value, value_range.clone(), plot.x_range()),
+                egui::remap(point.opacity, 0.0..=1.0, plot.bottom_up_range()),
+            )
+        };
+        let from_screen = |pos: Pos2| {
+            (
+                egui::remap_clamp(pos.x, plot.x_range(), value_range.clone()),
+                egui::remap_clamp(pos.y, plot.bottom_up_range(), 0.0..=1.0),
+            )
+        };
+
+        let mut transfer_function = self.transfer_function.borrow_mut();
+
+        let hovered_point = response.hover_pos().and_then(|pos| {
+            transfer_function
+                .points()
+                .iter()
+                .position(|point| to_screen(point).distance(pos) <= POINT_RADIUS * 1.5)
+        });
+
+        // interaction
+        if response.drag_started() {
+            self.dragged = hovered_point;
+            if self.dragged.is_some() {
+                self.selected = self.dragged;
+            }
+        }
+        if let (Some(index), Some(pos)) = (self.dragged, response.interact_pointer_pos()) {
+            if response.dragged() {
+                let (value, opacity) = from_screen(pos);
+                let point = ControlPoint {
+                    value,
+                    opacity,
+                    ..transfer_function.points()[index]
+                };
+                let index = transfer_function.update(index, point);
+                self.dragged = Some(index);
+                self.selected = Some(index);
+            }
+        }
+        if response.drag_released() {
+            self.dragged = None;
+        }
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.selected = match hovered_point {
+                    Some(index) => Some(index),
+                    None => {
+                        let (value, opacity) = from_screen(pos);
+                        let (color, _) = transfer_function.sample(value);
+                        Some(transfer_function.insert(ControlPoint {
+                            value,
+                            color,
+                            opacity,
+                        }))
+                    }
+                };
+            }
+        }
+        if response.secondary_clicked() {
+            if let Some(index) = hovered_point {
+                transfer_function.remove(index);
+                self.selected = None;
+            }
+        }
+        if hovered_point.is_some() || self.dragged.is_some() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+        }
+
+        // color bar along the bottom edge
+        let color_bar_height = 8.0;
+        let steps = plot.width().max(1.0) as usize;
+        for step in 0..steps {
+            let x = plot.left() + step as f32;
+            let (value, _) = from_screen(Pos2::new(x, plot.bottom()));
+            let (color, _) = transfer_function.sample(value);
+            painter.line_segment(
+                [
+                    Pos2::new(x, rect.bottom() - color_bar_height),
+                    Pos2::new(x, rect.bottom()),
+                ],
+                Stroke::new(1.0, Color32::from_rgb(color[0], color[1], color[2])),
+            );
+        }
+
+        // opacity curve and control points
+        let line_color = visuals.widgets.active.fg_stroke.color;
+        let mut line: Vec<Pos2> = transfer_function.points().iter().map(to_screen).collect();
+        if let (Some(first), Some(last)) = (line.first().copied(), line.last().copied()) {
+            line.insert(0, Pos2::new(plot.left(), first.y));
+            line.push(Pos2::new(plot.right(), last.y));
+        }
+        painter.add(Shape::line(line, Stroke::new(1.5, line_color)));
+
+        for (i, point) in transfer_function.points().iter().enumerate() {
+            let center = to_screen(point);
+            let stroke_width = if Some(i) == self.selected { 3.0 } else { 1.0 };
+            painter.circle(
+                center,
+                POINT_RADIUS,
+                Color32::from_rgb(point.color[0], point.color[1], point.color[2]),
+                Stroke::new(stroke_width, line_color),
+            );
+        }
+
+        if let Some(pos) = response.hover_pos() {
+            let (value, opacity) = from_screen(pos);
+            painter.text(
+                rect.right_top() + Vec2::new(-4.0, 4.0),
+                egui::Align2::RIGHT_TOP,
+                format!("value: {value:.0}  opacity: {opacity:.2}"),
+                egui::TextStyle::Small.resolve(ui.style()),
+                visuals.text_color(),
+            );
+        }
+    }
+}
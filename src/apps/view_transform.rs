@@ -0,0 +1,107 @@
+/// Zoom and pan of a 2D view on top of fitting its content into the view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewTransform {
+    /// 1.0 fits the content into the view.
+    pub zoom: f32,
+    /// Offset of the content center from the view center, in units of the
+    /// fitted content size.
+    pub pan: egui::Vec2,
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: egui::Vec2::ZERO,
+        }
+    }
+}
+
+impl ViewTransform {
+    pub const MIN_ZOOM: f32 = 0.1;
+    pub const MAX_ZOOM: f32 = 64.0;
+
+    /// Area of `rect` covered by content of `fitted_size` once zoomed and panned.
+    pub fn content_rect(&self, rect: egui::Rect, fitted_size: egui::Vec2) -> egui::Rect {
+        egui::Rect::from_center_size(
+            rect.center() + self.pan * fitted_size,
+            fitted_size * self.zoom,
+        )
+    }
+
+    /// Zooms by `factor`, keeping the content under `pointer` in place.
+    pub fn zoom_around(
+        &mut self,
+        pointer: egui::Pos2,
+        factor: f32,
+        rect: egui::Rect,
+        fitted_size: egui::Vec2,
+    ) {
+        let zoom = (self.zoom * factor).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        let center = self.content_rect(rect, fitted_size).center();
+        let new_center = pointer - (pointer - center) * (zoom / self.zoom);
+        self.pan = (new_center - rect.center()) / fitted_size;
+        self.zoom = zoom;
+    }
+
+    /// Moves the content by `delta` points.
+    pub fn pan_by(&mut self, delta: egui::Vec2, fitted_size: egui::Vec2) {
+        self.pan += delta / fitted_size;
+    }
+
+    /// Scale and translation in clip space mapping the full view quad onto
+    /// `content_rect`, as passed to the vertex shader.
+    pub fn clip_transform(rect: egui::Rect, content_rect: egui::Rect) -> ([f32; 2], [f32; 2]) {
+        let scale = content_rect.size() / rect.size();
+        // clip space y points up
+        let translation = (content_rect.center() - rect.center()) / (0.5 * rect.size());
+        ([scale.x, scale.y], [translation.x, -translation.y])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect() -> egui::Rect {
+        egui::Rect::from_min_size(egui::pos2(20.0, 10.0), egui::vec2(400.0, 300.0))
+    }
+
+    /// Where `point` lies in the content, from 0 to 1 across it.
+    fn content_fraction(view: &ViewTransform, point: egui::Pos2) -> egui::Vec2 {
+        let content = view.content_rect(rect(), egui::vec2(300.0, 300.0));
+        (point - content.min) / content.size()
+    }
+
+    #[test]
+    fn zooming_keeps_the_content_under_the_pointer() {
+        let fitted_size = egui::vec2(300.0, 300.0);
+        let pointer = egui::pos2(120.0, 60.0);
+        let mut view = ViewTransform::default();
+        view.pan_by(egui::vec2(30.0, -15.0), fitted_size);
+
+        for factor in [2.0, 0.7, 1000.0] {
+            let before = content_fraction(&view, pointer);
+            view.zoom_around(pointer, factor, rect(), fitted_size);
+            let after = content_fraction(&view, pointer);
+            assert!((after - before).length() < 1e-5, "{before:?} {after:?}");
+        }
+        assert_eq!(view.zoom, ViewTransform::MAX_ZOOM);
+    }
+
+    #[test]
+    fn clip_transform_of_the_fitted_view() {
+        let rect = rect();
+        let content = ViewTransform::default().content_rect(rect, rect.size());
+        assert_eq!(content, rect);
+        let (scale, translation) = ViewTransform::clip_transform(rect, content);
+        assert_eq!(scale, [1.0, 1.0]);
+        assert_eq!(translation, [0.0, 0.0]);
+
+        // half the size, in the top right quarter
+        let content = egui::Rect::from_min_max(rect.center_top(), rect.right_center());
+        let (scale, translation) = ViewTransform::clip_transform(rect, content);
+        assert_eq!(scale, [0.5, 0.5]);
+        assert_eq!(translation, [0.5, 0.5]);
+    }
+}
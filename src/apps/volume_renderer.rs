@@ -0,0 +1,440 @@
+use std::sync::Arc;
+
+use bytemuck::Zeroable;
+use eframe::{
+    egui_wgpu::wgpu::util::DeviceExt,
+    egui_wgpu::{self, wgpu},
+};
+
+use super::{camera::OrbitCamera, settings_overlay::settings_overlay};
+
+// Needs to match the memory layout of `Uniforms` in ray_casting.wgsl
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct RayCastingUniforms {
+    inverse_view_projection: [[f32; 4]; 4],
+    box_size: [f32; 3],
+    step_size: f32,
+    reference_step_size: f32,
+    early_ray_termination: f32,
+    jitter: u32,
+    render_mode: u32,
+    iso_value: f32,
+    _padding: [u32; 3],
+}
+
+struct VolumeRenderResources {
+    render_pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    texture_bind_group: wgpu::BindGroup,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+impl VolumeRenderResources {
+    fn prepare(&self, _device: &wgpu::Device, queue: &wgpu::Queue, uniforms: RayCastingUniforms) {
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    fn paint<'rp>(&'rp self, render_pass: &mut wgpu::RenderPass<'rp>) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        // volume data and transfer function
+        render_pass.set_bind_group(0, &self.texture_bind_group, &[]);
+        // camera and ray marching settings
+        render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// How samples along a ray are combined.
+#[derive(Clone, Copy, PartialEq)]
+pub enum VolumeRenderMode {
+    DirectVolumeRendering,
+    MaximumIntensityProjection,
+    MinimumIntensityProjection,
+    AverageIntensityProjection,
+    Isosurface,
+}
+
+impl VolumeRenderMode {
+    const ALL: [Self; 5] = [
+        Self::DirectVolumeRendering,
+        Self::MaximumIntensityProjection,
+        Self::MinimumIntensityProjection,
+        Self::AverageIntensityProjection,
+        Self::Isosurface,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::DirectVolumeRendering => "DVR",
+            Self::MaximumIntensityProjection => "MIP",
+            Self::MinimumIntensityProjection => "MinIP",
+            Self::AverageIntensityProjection => "Mean",
+            Self::Isosurface => "Isosurface",
+        }
+    }
+}
+
+impl From<VolumeRenderMode> for u32 {
+    fn from(mode: VolumeRenderMode) -> u32 {
+        match mode {
+            VolumeRenderMode::DirectVolumeRendering => 0,
+            VolumeRenderMode::MaximumIntensityProjection => 1,
+            VolumeRenderMode::MinimumIntensityProjection => 2,
+            VolumeRenderMode::AverageIntensityProjection => 3,
+            VolumeRenderMode::Isosurface => 4,
+        }
+    }
+}
+
+/// Direct volume rendering, intensity projections and isosurfaces of the
+/// whole volume by GPU ray casting.
+pub struct VolumeRenderer {
+    id: egui::Id,
+    camera: OrbitCamera,
+    render_mode: VolumeRenderMode,
+    box_size: [f32; 3],
+    reference_step_size: f32,
+    sample_count: u32,
+    early_ray_termination: bool,
+    early_ray_termination_threshold: f32,
+    jitter: bool,
+    value_range: (u16, u16),
+    /// In voxel units, like the transfer function.
+    iso_value: f32,
+    pub show_settings_oberlay: bool,
+}
+
+impl VolumeRenderer {
+    pub fn new(
+        wgpu_render_state: &egui_wgpu::RenderState,
+        texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: &crate::data::Volume,
+    ) -> Option<Self> {
+        let device = &wgpu_render_state.device;
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D3,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: (true) },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: (true) },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("volume_texture_bind_group_layout"),
+            });
+
+        let texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&lookup_table.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&lookup_table.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: lookup_table.range_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("volume_texture_bind_group"),
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Ray Casting Uniforms"),
+            contents: bytemuck::cast_slice(&[RayCastingUniforms::zeroed()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("ray_casting_uniform_bind_group_layout"),
+            });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("ray_casting_uniform_bind_group"),
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Ray Casting Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("ray_casting.wgsl").into()),
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Ray Casting Pipeline Layout"),
+                bind_group_layouts: &[&texture_bind_group_layout, &uniform_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Ray Casting Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu_render_state.target_format.into())],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let id = egui::Id::new(uuid::Uuid::new_v4());
+        let volume_render_resources = VolumeRenderResources {
+            render_pipeline,
+            uniform_buffer,
+            texture_bind_group,
+            uniform_bind_group,
+        };
+
+        match wgpu_render_state
+            .renderer
+            .write()
+            .paint_callback_resources
+            .entry::<std::collections::HashMap<egui::Id, VolumeRenderResources>>()
+        {
+            type_map::concurrent::Entry::Occupied(mut e) => {
+                e.get_mut().insert(id, volume_render_resources);
+            }
+            type_map::concurrent::Entry::Vacant(e) => {
+                e.insert(std::collections::HashMap::new())
+                    .insert(id, volume_render_resources);
+            }
+        }
+
+        // fit the largest physical extent of the volume into the unit cube
+        let extent = [
+            texture.dimensions.0 as f32 * texture.spacing.0,
+            texture.dimensions.1 as f32 * texture.spacing.1,
+            texture.dimensions.2 as f32 * texture.spacing.2,
+        ];
+        let max_extent = extent[0].max(extent[1]).max(extent[2]);
+        let box_size = extent.map(|e| e / max_extent);
+
+        // one voxel along the axis with the most voxels
+        let max_dimension = texture
+            .dimensions
+            .0
+            .max(texture.dimensions.1)
+            .max(texture.dimensions.2);
+        let reference_step_size = 1.0 / max_dimension as f32;
+
+        let value_range = volume.range();
+
+        Some(Self {
+            id,
+            camera: OrbitCamera::default(),
+            render_mode: VolumeRenderMode::DirectVolumeRendering,
+            box_size,
+            reference_step_size,
+            sample_count: 512,
+            early_ray_termination: true,
+            early_ray_termination_threshold: 0.99,
+            jitter: true,
+            value_range,
+            iso_value: (value_range.0 as f32 + value_range.1 as f32) / 2.0,
+            show_settings_oberlay: true,
+        })
+    }
+
+    fn uniforms(&self, aspect_ratio: f32) -> RayCastingUniforms {
+        let diagonal = self.box_size.iter().map(|e| e * e).sum::<f32>().sqrt();
+
+        RayCastingUniforms {
+            inverse_view_projection: self.camera.inverse_view_projection(aspect_ratio).into(),
+            box_size: self.box_size,
+            step_size: diagonal / self.sample_count as f32,
+            reference_step_size: self.reference_step_size,
+            early_ray_termination: if self.early_ray_termination {
+                self.early_ray_termination_threshold
+            } else {
+                // accumulated opacity never exceeds 1.0
+                2.0
+            },
+            jitter: self.jitter as u32,
+            render_mode: self.render_mode.into(),
+            // the texture holds values normalized to the u16 range
+            iso_value: self.iso_value / u16::MAX as f32,
+            _padding: [0; 3],
+        }
+    }
+
+    fn handle_input(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response) {
+        let delta = response.drag_delta() / rect.height();
+
+        if response.dragged_by(egui::PointerButton::Primary) {
+            self.camera.orbit(
+                delta.x * std::f32::consts::PI,
+                delta.y * std::f32::consts::PI,
+            );
+        }
+        if response.dragged_by(egui::PointerButton::Secondary)
+            || response.dragged_by(egui::PointerButton::Middle)
+        {
+            self.camera.pan(delta.x, delta.y);
+        }
+        if response.hovered() {
+            let scroll = ui.input(|i| i.scroll_delta.y);
+            if scroll != 0.0 {
+                self.camera.zoom(scroll * 0.002);
+            }
+        }
+        if response.double_clicked() {
+            self.camera = OrbitCamera::default();
+        }
+    }
+
+    pub fn custom_painting(&mut self, ui: &mut egui::Ui) {
+        let availbale_size = ui.available_size_before_wrap();
+        let (rect, response) =
+            ui.allocate_exact_size(availbale_size, egui::Sense::click_and_drag());
+
+        self.handle_input(ui, rect, &response);
+
+        // Clone locals so we can move them into the paint callback:
+        let uniforms = self.uniforms(rect.aspect_ratio());
+        let id = self.id;
+
+        let cb = egui_wgpu::CallbackFn::new()
+            .prepare(move |device, queue, _encoder, paint_callback_resources| {
+                let resources: &std::collections::HashMap<egui::Id, VolumeRenderResources> =
+                    paint_callback_resources.get().unwrap();
+                let volume_render_resources = resources.get(&id).unwrap();
+                volume_render_resources.prepare(device, queue, uniforms);
+                Vec::new()
+            })
+            .paint(move |_info, render_pass, paint_callback_resources| {
+                let resources: &std::collections::HashMap<egui::Id, VolumeRenderResources> =
+                    paint_callback_resources.get().unwrap();
+                let volume_render_resources = resources.get(&id).unwrap();
+                volume_render_resources.paint(render_pass);
+            });
+
+        let callback = egui::PaintCallback {
+            rect,
+            callback: Arc::new(cb),
+        };
+
+        ui.painter().add(callback);
+
+        // Paint overlay
+        if self.show_settings_oberlay {
+            settings_overlay(ui, rect, |ui| {
+                egui::ComboBox::from_label("Render Mode")
+                    .selected_text(self.render_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in VolumeRenderMode::ALL {
+                            ui.selectable_value(&mut self.render_mode, mode, mode.label());
+                        }
+                    });
+                if self.render_mode == VolumeRenderMode::Isosurface {
+                    let (min, max) = self.value_range;
+                    ui.add(
+                        egui::Slider::new(&mut self.iso_value, min as f32..=max as f32)
+                            .text("Iso Value"),
+                    );
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.sample_count, 16..=2048)
+                        .logarithmic(true)
+                        .text("Samples per Ray"),
+                );
+                ui.checkbox(&mut self.early_ray_termination, "Early Ray Termination");
+                ui.add_enabled(
+                    self.early_ray_termination,
+                    egui::Slider::new(&mut self.early_ray_termination_threshold, 0.5..=1.0)
+                        .text("Opacity Threshold"),
+                );
+                ui.checkbox(&mut self.jitter, "Jittering")
+                    .on_hover_text("Randomly offset the ray start to hide sampling artifacts");
+                if ui.button("Reset Camera").clicked() {
+                    self.camera = OrbitCamera::default();
+                }
+            });
+        }
+    }
+}
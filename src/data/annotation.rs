@@ -0,0 +1,58 @@
+use cgmath::Point3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum AnnotationKind {
+    /// Marker at a point.
+    Point,
+    /// Arrow pointing at a point.
+    Arrow,
+    /// Text anchored at a point.
+    Text,
+}
+
+impl AnnotationKind {
+    pub const ALL: [Self; 3] = [Self::Point, Self::Arrow, Self::Text];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Point => "Point",
+            Self::Arrow => "Arrow",
+            Self::Text => "Text",
+        }
+    }
+}
+
+/// Annotation anchored in the volume, shown by the slice views whose slice
+/// contains its position.
+///
+/// Positions are given in mm relative to the corner of the volume like the
+/// measurement points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    /// Marked point, arrow head or text anchor.
+    pub position: Point3<f32>,
+    /// Where an arrow starts, the text of an arrow is shown there.
+    pub tail: Option<Point3<f32>>,
+    pub text: String,
+}
+
+impl Annotation {
+    pub fn new(kind: AnnotationKind, position: Point3<f32>, text: String) -> Self {
+        Self {
+            kind,
+            position,
+            tail: None,
+            text,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self.kind {
+            AnnotationKind::Arrow => self.tail.is_some(),
+            AnnotationKind::Text => !self.text.is_empty(),
+            AnnotationKind::Point => true,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+/// Coordinate system positions are shown in.
+///
+/// Volumes store their index-to-world transform in LPS like DICOM and NRRD,
+/// RAS as used by NIfTI negates the first two axes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CoordinateSystem {
+    /// x to the patient's right, y anterior, z superior.
+    #[default]
+    Ras,
+    /// x to the patient's left, y posterior, z superior.
+    Lps,
+    /// Voxel index coordinates.
+    Voxel,
+}
+
+impl CoordinateSystem {
+    pub const ALL: [Self; 3] = [Self::Ras, Self::Lps, Self::Voxel];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ras => "RAS",
+            Self::Lps => "LPS",
+            Self::Voxel => "Voxel",
+        }
+    }
+
+    /// Converts from LPS, voxel coordinates are returned unchanged.
+    pub fn transform_lps(&self, position: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::Ras => [-position[0], -position[1], position[2]],
+            Self::Lps | Self::Voxel => position,
+        }
+    }
+
+    /// Names of the negative and positive direction of each axis.
+    pub fn axis_names(&self) -> [(&'static str, &'static str); 3] {
+        match self {
+            Self::Ras => [("L", "R"), ("P", "A"), ("I", "S")],
+            Self::Lps => [("R", "L"), ("A", "P"), ("I", "S")],
+            Self::Voxel => [("-X", "+X"), ("-Y", "+Y"), ("-Z", "+Z")],
+        }
+    }
+
+    /// Unit of the coordinates.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Ras | Self::Lps => "mm",
+            Self::Voxel => "voxels",
+        }
+    }
+
+    /// `position` in this system for display, e.g. "RAS (1.0, 2.0, 3.0) mm".
+    pub fn format(&self, position: [f32; 3]) -> String {
+        let [x, y, z] = position;
+        format!("{} ({x:.1}, {y:.1}, {z:.1}) {}", self.label(), self.unit())
+    }
+}
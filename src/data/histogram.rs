@@ -0,0 +1,106 @@
+use super::Volume;
+
+/// Value distribution of a volume, binned over `[min, max]`.
+#[derive(Clone)]
+pub struct Histogram {
+    pub bins: Vec<u64>,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Histogram {
+    pub fn new(volume: &Volume, bin_count: usize) -> Self {
+        let (min, max) = volume.range();
+        Self::from_values(
+            volume.values.iter().map(|&value| value as f32),
+            bin_count,
+            min as f32,
+            max as f32,
+        )
+    }
+
+    pub fn from_values(
+        values: impl Iterator<Item = f32>,
+        bin_count: usize,
+        min: f32,
+        max: f32,
+    ) -> Self {
+        let mut histogram = Self {
+            bins: vec![0; bin_count.max(1)],
+            min,
+            max,
+        };
+        for value in values {
+            if let Some(bin) = histogram.bin(value) {
+                histogram.bins[bin] += 1;
+            }
+        }
+        histogram
+    }
+
+    pub fn bin_width(&self) -> f32 {
+        ((self.max - self.min) / self.bins.len() as f32).max(f32::EPSILON)
+    }
+
+    /// Index of the bin containing `value`, `None` if it lies outside of `[min, max]`.
+    pub fn bin(&self, value: f32) -> Option<usize> {
+        if !(self.min..=self.max).contains(&value) {
+            return None;
+        }
+        let bin = ((value - self.min) / self.bin_width()) as usize;
+        Some(bin.min(self.bins.len() - 1))
+    }
+
+    pub fn max_count(&self) -> u64 {
+        self.bins.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Bin heights normalized to `[0, 1]`.
+    pub fn linear_heights(&self) -> Vec<f32> {
+        let max = self.max_count().max(1) as f32;
+        self.bins.iter().map(|&count| count as f32 / max).collect()
+    }
+
+    /// Bin heights normalized to `[0, 1]` on a logarithmic scale.
+    pub fn log_heights(&self) -> Vec<f32> {
+        let max = (self.max_count() as f32).ln_1p();
+        self.bins
+            .iter()
+            .map(|&count| {
+                if max > 0.0 {
+                    (count as f32).ln_1p() / max
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_falls_into_the_last_bin() {
+        let histogram = Histogram::from_values([0.0, 1.0, 2.5, 10.0].into_iter(), 4, 0.0, 10.0);
+        assert_eq!(histogram.bin_width(), 2.5);
+        assert_eq!(histogram.bin(2.5), Some(1));
+        assert_eq!(histogram.bin(10.0), Some(3));
+        assert_eq!(histogram.bin(10.5), None);
+        assert_eq!(histogram.bin(-0.5), None);
+        assert_eq!(histogram.bins, vec![2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn heights_are_normalized() {
+        let histogram = Histogram::from_values([0.0, 0.0, 0.0, 9.0].into_iter(), 2, 0.0, 10.0);
+        assert_eq!(histogram.linear_heights(), vec![1.0, 1.0 / 3.0]);
+        let heights = histogram.log_heights();
+        assert_eq!(heights[0], 1.0);
+        assert!((heights[1] - 2f32.ln() / 4f32.ln()).abs() < 1e-6);
+
+        let empty = Histogram::from_values(std::iter::empty(), 2, 0.0, 10.0);
+        assert_eq!(empty.log_heights(), vec![0.0, 0.0]);
+    }
+}
@@ -0,0 +1,29 @@
+/// 2D scalar image resampled from the volume, e.g. a curved planar reformation.
+///
+/// Values keep the units of the volume, the first row is the top of the image.
+#[derive(Clone, Default)]
+pub struct Image {
+    pub dimensions: (u32, u32),
+    /// Pixel size in mm.
+    pub spacing: (f32, f32),
+    pub values: Vec<f32>,
+}
+
+impl Image {
+    pub fn new(dimensions: (u32, u32), spacing: (f32, f32)) -> Self {
+        Self {
+            dimensions,
+            spacing,
+            values: vec![0.0; dimensions.0 as usize * dimensions.1 as usize],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Position of pixel `(x, y)` in `values`.
+    pub fn index(&self, x: u32, y: u32) -> usize {
+        x as usize + self.dimensions.0 as usize * y as usize
+    }
+}
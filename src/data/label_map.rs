@@ -0,0 +1,91 @@
+/// Integer labels of a segmentation with the geometry of the loaded volume,
+/// 0 is background.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelMap {
+    pub dimensions: (u32, u32, u32),
+    pub values: Vec<u16>,
+}
+
+impl LabelMap {
+    /// Background only.
+    pub fn new(dimensions: (u32, u32, u32)) -> Self {
+        let (width, height, depth) = dimensions;
+        Self {
+            dimensions,
+            values: vec![0; width as usize * height as usize * depth as usize],
+        }
+    }
+
+    /// Position of voxel `(x, y, z)` in `values`, the same as in the volume.
+    pub fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        let (width, height, _) = self.dimensions;
+        x as usize + width as usize * (y as usize + height as usize * z as usize)
+    }
+
+    pub fn label(&self, x: u32, y: u32, z: u32) -> u16 {
+        self.values[self.index(x, y, z)]
+    }
+
+    /// Sorted labels other than background that occur in the map.
+    pub fn present_labels(&self) -> Vec<u16> {
+        let mut present = vec![false; u16::MAX as usize + 1];
+        for &value in &self.values {
+            present[value as usize] = true;
+        }
+        (1..=u16::MAX)
+            .filter(|&value| present[value as usize])
+            .collect()
+    }
+}
+
+/// Display settings of one label value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub value: u16,
+    pub name: String,
+    /// sRGB
+    pub color: [u8; 3],
+    pub visible: bool,
+}
+
+impl Label {
+    /// Colors assigned to new labels in turn.
+    const PALETTE: [[u8; 3]; 12] = [
+        [230, 25, 75],
+        [60, 180, 75],
+        [255, 225, 25],
+        [0, 130, 200],
+        [245, 130, 48],
+        [145, 30, 180],
+        [70, 240, 240],
+        [240, 50, 230],
+        [210, 245, 60],
+        [250, 190, 212],
+        [0, 128, 128],
+        [170, 110, 40],
+    ];
+
+    pub fn new(value: u16) -> Self {
+        Self {
+            value,
+            name: format!("Label {value}"),
+            color: Self::PALETTE[(value as usize + Self::PALETTE.len() - 1) % Self::PALETTE.len()],
+            visible: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn present_labels_skip_background() {
+        let map = LabelMap {
+            dimensions: (2, 2, 2),
+            values: vec![0, 3, 0, 0, 0, 300, 3, 0],
+        };
+        assert_eq!(map.present_labels(), vec![3, 300]);
+        assert_eq!(map.label(1, 0, 1), 300);
+    }
+}
@@ -0,0 +1,163 @@
+use cgmath::{InnerSpace, Point3};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeasurementKind {
+    /// Distance between two points.
+    Distance,
+    /// Angle at the second of three points.
+    Angle,
+    /// Length of a line through any number of points.
+    Polyline,
+    /// Line whose intensity profile is plotted by the line profile panel.
+    Profile,
+}
+
+impl MeasurementKind {
+    pub const ALL: [Self; 4] = [Self::Distance, Self::Angle, Self::Polyline, Self::Profile];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Distance => "Distance",
+            Self::Angle => "Angle",
+            Self::Polyline => "Polyline",
+            Self::Profile => "Profile",
+        }
+    }
+
+    /// Number of points that completes the measurement, `None` if open ended.
+    pub fn point_count(&self) -> Option<usize> {
+        match self {
+            Self::Distance | Self::Profile => Some(2),
+            Self::Angle => Some(3),
+            Self::Polyline => None,
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Distance | Self::Polyline | Self::Profile => "mm",
+            Self::Angle => "°",
+        }
+    }
+}
+
+/// Measurement placed in the slice views.
+///
+/// Points are given in mm relative to the corner of the volume, so distances
+/// take the voxel spacing into account.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub kind: MeasurementKind,
+    pub points: Vec<Point3<f32>>,
+    /// Connects the last point of a polyline back to the first one.
+    pub closed: bool,
+}
+
+impl Measurement {
+    pub fn new(kind: MeasurementKind) -> Self {
+        Self {
+            kind,
+            points: Vec::new(),
+            closed: false,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self.kind.point_count() {
+            Some(count) => self.points.len() >= count,
+            None => self.points.len() >= 2,
+        }
+    }
+
+    /// Point pairs connected by lines.
+    pub fn segments(&self) -> Vec<[Point3<f32>; 2]> {
+        let mut segments: Vec<_> = self
+            .points
+            .windows(2)
+            .map(|points| [points[0], points[1]])
+            .collect();
+        if self.kind == MeasurementKind::Polyline && self.closed && self.points.len() > 2 {
+            segments.push([self.points[self.points.len() - 1], self.points[0]]);
+        }
+        segments
+    }
+
+    /// Length in mm or angle in degrees, see `MeasurementKind::unit`.
+    pub fn value(&self) -> f32 {
+        match self.kind {
+            MeasurementKind::Distance | MeasurementKind::Polyline | MeasurementKind::Profile => {
+                self.segments()
+                    .iter()
+                    .map(|[start, end]| (end - start).magnitude())
+                    .sum()
+            }
+            MeasurementKind::Angle => {
+                let [first, vertex, last] = match self.points[..] {
+                    [first, vertex, last, ..] => [first, vertex, last],
+                    _ => return 0.0,
+                };
+                let (a, b) = (first - vertex, last - vertex);
+                if a.magnitude2() == 0.0 || b.magnitude2() == 0.0 {
+                    return 0.0;
+                }
+                a.angle(b).0.to_degrees()
+            }
+        }
+    }
+
+    /// Value with unit for display.
+    pub fn format_value(&self) -> String {
+        match self.kind {
+            MeasurementKind::Angle => format!("{:.1}{}", self.value(), self.kind.unit()),
+            _ => format!("{:.1} {}", self.value(), self.kind.unit()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(kind: MeasurementKind, points: &[[f32; 3]], closed: bool) -> Measurement {
+        Measurement {
+            kind,
+            points: points.iter().map(|&point| Point3::from(point)).collect(),
+            closed,
+        }
+    }
+
+    #[test]
+    fn distance() {
+        let ruler = measurement(
+            MeasurementKind::Distance,
+            &[[1.0, 2.0, 3.0], [4.0, 6.0, 3.0]],
+            false,
+        );
+        assert!(ruler.is_complete());
+        assert_eq!(ruler.value(), 5.0);
+    }
+
+    #[test]
+    fn right_angle() {
+        let angle = measurement(
+            MeasurementKind::Angle,
+            &[[2.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 3.0]],
+            false,
+        );
+        assert!((angle.value() - 90.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn closed_polyline_adds_last_segment() {
+        let square = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let open = measurement(MeasurementKind::Polyline, &square, false);
+        let closed = measurement(MeasurementKind::Polyline, &square, true);
+        assert_eq!(open.value(), 3.0);
+        assert_eq!(closed.value(), 4.0);
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(not(target_arch = "wasm32"))]
+use cgmath::{Matrix, Matrix3, SquareMatrix, Vector3};
+
+/// Indexed triangle mesh, e.g. an isosurface extracted from the volume.
+///
+/// Positions are given in voxel index coordinates, so `[0.0, 0.0, 0.0]` is the
+/// center of the first voxel. Triangles are wound counter-clockwise when seen
+/// from the side the normals point to.
+#[derive(Clone, Default)]
+pub struct Mesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+// only needed to post-process the file export, which is native only
+#[cfg(not(target_arch = "wasm32"))]
+impl Mesh {
+    /// Copy in LPS world coordinates in mm, the frame of the `world_lps`
+    /// positions in the annotation sidecar.
+    pub fn to_world(&self, volume: &super::Volume) -> Self {
+        let transform = volume.index_to_world();
+        // normals transform with the inverse transpose of the linear part
+        let normal_matrix = Matrix3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
+        )
+        .invert()
+        .unwrap_or_else(Matrix3::identity)
+        .transpose();
+        Self {
+            positions: self
+                .positions
+                .iter()
+                .map(|&position| {
+                    (transform * Vector3::from(position).extend(1.0))
+                        .truncate()
+                        .into()
+                })
+                .collect(),
+            normals: self
+                .normals
+                .iter()
+                .map(|&normal| normalize((normal_matrix * Vector3::from(normal)).into()))
+                .collect(),
+            indices: self.indices.clone(),
+        }
+    }
+
+    /// Replaces the normals by the area weighted face normals around each vertex.
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![[0.0; 3]; self.positions.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let normal = self.face_normal([triangle[0], triangle[1], triangle[2]]);
+            for &vertex in triangle {
+                for i in 0..3 {
+                    normals[vertex as usize][i] += normal[i];
+                }
+            }
+        }
+        self.normals = normals.into_iter().map(normalize).collect();
+    }
+
+    /// Normal of a triangle with the length of twice its area.
+    pub fn face_normal(&self, triangle: [u32; 3]) -> [f32; 3] {
+        let [a, b, c] = triangle.map(|vertex| self.positions[vertex as usize]);
+        let u: [f32; 3] = std::array::from_fn(|i| b[i] - a[i]);
+        let v: [f32; 3] = std::array::from_fn(|i| c[i] - a[i]);
+        [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn normalize(vector: [f32; 3]) -> [f32; 3] {
+    let length = vector.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if length > 0.0 {
+        vector.map(|c| c / length)
+    } else {
+        vector
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use cgmath::{assert_abs_diff_eq, InnerSpace};
+
+    use super::*;
+    use crate::data::{CoordinateSystem, Volume};
+
+    #[test]
+    fn world_positions_match_the_annotation_sidecar() {
+        let volume = Volume {
+            dimensions: (4, 4, 4),
+            spacing: (0.5, 1.0, 2.0),
+            origin: (10.0, -5.0, 3.0),
+            direction: Matrix3::from_diagonal(Vector3::new(-1.0, -1.0, 1.0)),
+            values: vec![0; 64].into(),
+            ..Default::default()
+        };
+        let normal = Vector3::new(1.0, 0.0, 1.0).normalize();
+        let mesh = Mesh {
+            positions: vec![[1.0, 2.0, 3.0]],
+            normals: vec![normal.into()],
+            indices: Vec::new(),
+        };
+        let world = mesh.to_world(&volume);
+        assert_eq!(world.positions[0], [9.5, -7.0, 9.0]);
+        assert_eq!(
+            world.positions[0],
+            volume.position_in([1.0, 2.0, 3.0], CoordinateSystem::Lps)
+        );
+
+        // still perpendicular to the surface after the anisotropic scaling
+        let tangent = volume.index_to_world() * Vector3::new(1.0, 0.0, -1.0).extend(0.0);
+        let world_normal = Vector3::from(world.normals[0]);
+        assert_abs_diff_eq!(world_normal.magnitude(), 1.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(world_normal.dot(tangent.truncate()), 0.0, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,25 @@
+mod annotation;
+mod coordinates;
+mod histogram;
+mod image;
+mod label_map;
+mod measurement;
+mod mesh;
+mod roi;
+mod slice_orientation;
+mod transfer_function;
+mod volume;
+mod window_level;
+
+pub use annotation::{Annotation, AnnotationKind};
+pub use coordinates::CoordinateSystem;
+pub use histogram::Histogram;
+pub use image::Image;
+pub use label_map::{Label, LabelMap};
+pub use measurement::{Measurement, MeasurementKind};
+pub use mesh::Mesh;
+pub use roi::{Roi, RoiShape, RoiStatistics};
+pub use slice_orientation::SliceOrientation;
+pub use transfer_function::{ControlPoint, TransferFunction};
+pub use volume::Volume;
+pub use window_level::WindowLevel;
@@ -0,0 +1,237 @@
+use cgmath::Point3;
+
+use super::SliceOrientation;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoiShape {
+    /// Rectangle spanned by two corners in a slice.
+    Rectangle,
+    /// Ellipse inscribed into the rectangle spanned by two corners in a slice.
+    Ellipse,
+    /// Polygon drawn freehand in a slice.
+    Freehand,
+    /// Box spanned by two corners that may lie in different slices.
+    Box,
+}
+
+impl RoiShape {
+    pub const ALL: [Self; 4] = [Self::Rectangle, Self::Ellipse, Self::Freehand, Self::Box];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Rectangle => "Rectangle",
+            Self::Ellipse => "Ellipse",
+            Self::Freehand => "Freehand",
+            Self::Box => "Box",
+        }
+    }
+
+    /// Whether the region covers several slices, its size is a volume then.
+    pub fn is_3d(&self) -> bool {
+        *self == Self::Box
+    }
+
+    /// Unit of the region's size.
+    pub fn size_unit(&self) -> &'static str {
+        if self.is_3d() {
+            "mm³"
+        } else {
+            "mm²"
+        }
+    }
+}
+
+/// Region of interest drawn in a slice view.
+///
+/// Points are given in mm relative to the corner of the volume like the
+/// measurement points. The 2D shapes cover the voxels of the slice through
+/// the first point whose centers lie inside the shape.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Roi {
+    pub shape: RoiShape,
+    /// Volume axis normal to the slice the region was drawn in.
+    pub normal_axis: usize,
+    /// Two corners, or the outline of a freehand region.
+    pub points: Vec<Point3<f32>>,
+}
+
+impl Roi {
+    pub fn new(shape: RoiShape, normal_axis: usize) -> Self {
+        Self {
+            shape,
+            normal_axis,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self.shape {
+            RoiShape::Freehand => self.points.len() >= 3,
+            _ => self.points.len() >= 2,
+        }
+    }
+
+    /// First and last voxel index along `axis` within the bounding box of
+    /// the region, `None` if the region misses the volume.
+    pub fn index_range(
+        &self,
+        axis: usize,
+        dimensions: (u32, u32, u32),
+        spacing: (f32, f32, f32),
+    ) -> Option<(u32, u32)> {
+        let size = [dimensions.0, dimensions.1, dimensions.2][axis];
+        let spacing = [spacing.0, spacing.1, spacing.2][axis];
+        if self.points.is_empty() || size == 0 {
+            return None;
+        }
+        let last = size as f32 - 1.0;
+        if axis == self.normal_axis && !self.shape.is_3d() {
+            // the slice through the first point
+            let index = (self.points[0][axis] / spacing).floor().clamp(0.0, last);
+            return Some((index as u32, index as u32));
+        }
+        let (min, max) = self
+            .points
+            .iter()
+            .map(|point| point[axis] / spacing - 0.5)
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), index| {
+                (min.min(index), max.max(index))
+            });
+        // corners placed at voxel centers must not lose their voxel to rounding
+        let (first, last) = ((min - 1e-3).ceil().max(0.0), (max + 1e-3).floor().min(last));
+        (first <= last).then_some((first as u32, last as u32))
+    }
+
+    /// Indices of the voxels inside the region.
+    pub fn voxels(&self, dimensions: (u32, u32, u32), spacing: (f32, f32, f32)) -> Vec<[u32; 3]> {
+        if !self.is_complete() {
+            return Vec::new();
+        }
+        let mut ranges = [(0, 0); 3];
+        for (axis, range) in ranges.iter_mut().enumerate() {
+            match self.index_range(axis, dimensions, spacing) {
+                Some(index_range) => *range = index_range,
+                None => return Vec::new(),
+            }
+        }
+        let spacing = [spacing.0, spacing.1, spacing.2];
+
+        let [u, v, _] = SliceOrientation::from_normal_axis(self.normal_axis).axes();
+        let outline: Vec<[f32; 2]> = self.points.iter().map(|p| [p[u], p[v]]).collect();
+        let center = |axis: usize, index: u32| (index as f32 + 0.5) * spacing[axis];
+        let mut voxels = Vec::new();
+        for z in ranges[2].0..=ranges[2].1 {
+            for y in ranges[1].0..=ranges[1].1 {
+                for x in ranges[0].0..=ranges[0].1 {
+                    let index = [x, y, z];
+                    let position = [center(u, index[u]), center(v, index[v])];
+                    let inside = match self.shape {
+                        RoiShape::Rectangle | RoiShape::Box => true,
+                        RoiShape::Ellipse => inside_ellipse(position, outline[0], outline[1]),
+                        RoiShape::Freehand => inside_polygon(position, &outline),
+                    };
+                    if inside {
+                        voxels.push(index);
+                    }
+                }
+            }
+        }
+        voxels
+    }
+}
+
+fn inside_ellipse(position: [f32; 2], corner: [f32; 2], opposite: [f32; 2]) -> bool {
+    let mut distance = 0.0;
+    for axis in 0..2 {
+        let center = (corner[axis] + opposite[axis]) / 2.0;
+        let radius = (corner[axis] - opposite[axis]).abs() / 2.0;
+        if radius == 0.0 {
+            return false;
+        }
+        distance += ((position[axis] - center) / radius).powi(2);
+    }
+    distance <= 1.0
+}
+
+/// Even-odd rule, the outline is closed implicitly.
+fn inside_polygon(position: [f32; 2], outline: &[[f32; 2]]) -> bool {
+    let [x, y] = position;
+    let mut inside = false;
+    let mut previous = outline[outline.len() - 1];
+    for &point in outline {
+        if (point[1] > y) != (previous[1] > y)
+            && x < (previous[0] - point[0]) * (y - point[1]) / (previous[1] - point[1]) + point[0]
+        {
+            inside = !inside;
+        }
+        previous = point;
+    }
+    inside
+}
+
+/// Statistics of the voxel values inside a region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoiStatistics {
+    pub count: usize,
+    /// Area in mm² for 2D regions or volume in mm³ for 3D regions.
+    pub size: f32,
+    pub mean: f32,
+    /// Population standard deviation.
+    pub std: f32,
+    pub min: f32,
+    pub max: f32,
+    pub median: f32,
+    /// Values at `RoiStatistics::PERCENTILES`.
+    pub percentiles: [f32; 4],
+}
+
+impl RoiStatistics {
+    pub const PERCENTILES: [f32; 4] = [5.0, 25.0, 75.0, 95.0];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMENSIONS: (u32, u32, u32) = (10, 10, 4);
+    const SPACING: (f32, f32, f32) = (1.0, 1.0, 2.0);
+
+    fn roi(shape: RoiShape, points: &[[f32; 3]]) -> Roi {
+        Roi {
+            shape,
+            normal_axis: 2,
+            points: points.iter().map(|&point| Point3::from(point)).collect(),
+        }
+    }
+
+    #[test]
+    fn rectangle_covers_voxel_centers_in_one_slice() {
+        let rectangle = roi(RoiShape::Rectangle, &[[1.0, 2.0, 3.0], [4.0, 4.0, 3.0]]);
+        let voxels = rectangle.voxels(DIMENSIONS, SPACING);
+        // centers 1.5, 2.5, 3.5 along x and 2.5, 3.5 along y in slice 1
+        assert_eq!(voxels.len(), 6);
+        assert!(voxels.iter().all(|voxel| voxel[2] == 1));
+    }
+
+    #[test]
+    fn box_spans_slices() {
+        let cuboid = roi(RoiShape::Box, &[[4.0, 4.0, 0.5], [1.0, 2.0, 4.5]]);
+        // 3 x 2 voxels in the slices with centers at 1 and 3 mm
+        assert_eq!(cuboid.voxels(DIMENSIONS, SPACING).len(), 12);
+    }
+
+    #[test]
+    fn freehand_and_ellipse_stay_inside_their_bounds() {
+        let triangle = roi(
+            RoiShape::Freehand,
+            &[[0.0, 0.0, 1.0], [10.0, 0.0, 1.0], [0.0, 10.0, 1.0]],
+        );
+        // voxels with x + y + 1 < 10 below the diagonal
+        assert_eq!(triangle.voxels(DIMENSIONS, SPACING).len(), 45);
+
+        let ellipse = roi(RoiShape::Ellipse, &[[0.0, 0.0, 1.0], [10.0, 10.0, 1.0]]);
+        let count = ellipse.voxels(DIMENSIONS, SPACING).len();
+        // close to the area of a circle with radius 5
+        assert!((count as f32 - std::f32::consts::PI * 25.0).abs() < 6.0);
+    }
+}
@@ -0,0 +1,252 @@
+use cgmath::{Vector2, Vector3, Zero};
+
+/// Orientation of the slice views aligned with the volume axes.
+///
+/// A position in [0, 1] of the view, with y pointing up, and a depth in
+/// [0, 1] map to volume texture coordinates through `axes`, mirroring
+/// `get_value_at` in `shader.wgsl`. Everything else about the view follows
+/// from the same table: the physical size of the slice, the slices to step
+/// through and the inverse mapping used by the overlays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceOrientation {
+    Axial,
+    Coronal,
+    Sagittal,
+}
+
+impl SliceOrientation {
+    /// The orientation of the slices normal to volume `axis`.
+    pub fn from_normal_axis(axis: usize) -> Self {
+        match axis {
+            0 => Self::Sagittal,
+            1 => Self::Coronal,
+            _ => Self::Axial,
+        }
+    }
+
+    /// Volume axes (0 = x, 1 = y, 2 = z) along the horizontal and vertical
+    /// view axes and along the slice normal.
+    pub fn axes(self) -> [usize; 3] {
+        match self {
+            Self::Axial => [0, 1, 2],
+            Self::Coronal => [0, 2, 1],
+            Self::Sagittal => [1, 2, 0],
+        }
+    }
+
+    pub fn horizontal_axis(self) -> usize {
+        self.axes()[0]
+    }
+
+    pub fn vertical_axis(self) -> usize {
+        self.axes()[1]
+    }
+
+    /// The axis the slice position moves along.
+    pub fn normal_axis(self) -> usize {
+        self.axes()[2]
+    }
+
+    /// Value of `axis` in the shader.
+    pub fn shader_axis(self) -> i32 {
+        match self {
+            Self::Axial => 0,
+            Self::Coronal => 1,
+            Self::Sagittal => 2,
+        }
+    }
+
+    /// Texture coordinates shown at `position` of the view for a slice at `depth`.
+    pub fn texture_coordinates(self, position: Vector2<f32>, depth: f32) -> Vector3<f32> {
+        let [horizontal, vertical, normal] = self.axes();
+        let mut coordinates = Vector3::zero();
+        coordinates[horizontal] = position.x;
+        coordinates[vertical] = position.y;
+        coordinates[normal] = depth;
+        coordinates
+    }
+
+    /// Position of the view showing `coordinates`, ignoring the depth.
+    pub fn view_position(self, coordinates: Vector3<f32>) -> Vector2<f32> {
+        Vector2::new(
+            coordinates[self.horizontal_axis()],
+            coordinates[self.vertical_axis()],
+        )
+    }
+
+    /// Depth of the slice through `coordinates`.
+    pub fn depth(self, coordinates: Vector3<f32>) -> f32 {
+        coordinates[self.normal_axis()]
+    }
+
+    /// Width and height of the slice in mm.
+    pub fn extent(self, dimensions: (u32, u32, u32), spacing: (f32, f32, f32)) -> Vector2<f32> {
+        let size = |axis| component(dimensions, axis) as f32 * component(spacing, axis);
+        Vector2::new(size(self.horizontal_axis()), size(self.vertical_axis()))
+    }
+
+    /// Width and height of the slice in voxels.
+    pub fn size(self, dimensions: (u32, u32, u32)) -> Vector2<u32> {
+        Vector2::new(
+            component(dimensions, self.horizontal_axis()),
+            component(dimensions, self.vertical_axis()),
+        )
+    }
+
+    /// Number of slices along the normal.
+    pub fn slice_count(self, dimensions: (u32, u32, u32)) -> u32 {
+        component(dimensions, self.normal_axis())
+    }
+
+    /// Distance between two slices in mm.
+    pub fn slice_spacing(self, spacing: (f32, f32, f32)) -> f32 {
+        component(spacing, self.normal_axis())
+    }
+}
+
+fn component<T: Copy>(tuple: (T, T, T), axis: usize) -> T {
+    [tuple.0, tuple.1, tuple.2][axis]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Volume;
+
+    const ALL: [SliceOrientation; 3] = [
+        SliceOrientation::Axial,
+        SliceOrientation::Coronal,
+        SliceOrientation::Sagittal,
+    ];
+
+    const DIMENSIONS: (u32, u32, u32) = (5, 3, 2);
+    const SPACING: (f32, f32, f32) = (0.5, 1.0, 4.0);
+
+    /// Volume with different dimensions along each axis whose values encode
+    /// the voxel index as `x + 10 y + 100 z`.
+    fn asymmetric_volume() -> Volume {
+        let mut values = Vec::new();
+        for z in 0..DIMENSIONS.2 {
+            for y in 0..DIMENSIONS.1 {
+                for x in 0..DIMENSIONS.0 {
+                    values.push((x + 10 * y + 100 * z) as u16);
+                }
+            }
+        }
+        Volume {
+            dimensions: DIMENSIONS,
+            spacing: SPACING,
+            values: values.into(),
+            ..Default::default()
+        }
+    }
+
+    /// `get_value_at` in `shader.wgsl` for the axis aligned views.
+    fn shader_texture_coordinates(axis: i32, position: Vector2<f32>, depth: f32) -> Vector3<f32> {
+        match axis {
+            0 => Vector3::new(position.x, position.y, depth),
+            1 => Vector3::new(position.x, depth, position.y),
+            2 => Vector3::new(depth, position.x, position.y),
+            _ => unreachable!(),
+        }
+    }
+
+    fn voxel(coordinates: Vector3<f32>) -> [u32; 3] {
+        let dimensions = [DIMENSIONS.0, DIMENSIONS.1, DIMENSIONS.2];
+        std::array::from_fn(|axis| (coordinates[axis] * dimensions[axis] as f32) as u32)
+    }
+
+    #[test]
+    fn axes_are_permutations() {
+        for orientation in ALL {
+            let mut axes = orientation.axes();
+            axes.sort();
+            assert_eq!(axes, [0, 1, 2], "{orientation:?}");
+        }
+        let normals = ALL.map(SliceOrientation::normal_axis);
+        assert_eq!(normals, [2, 1, 0]);
+        for orientation in ALL {
+            assert_eq!(
+                SliceOrientation::from_normal_axis(orientation.normal_axis()),
+                orientation
+            );
+        }
+    }
+
+    #[test]
+    fn matches_shader() {
+        let position = Vector2::new(0.2, 0.7);
+        for orientation in ALL {
+            assert_eq!(
+                orientation.texture_coordinates(position, 0.4),
+                shader_texture_coordinates(orientation.shader_axis(), position, 0.4),
+                "{orientation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn view_position_inverts_texture_coordinates() {
+        let position = Vector2::new(0.1, 0.9);
+        for orientation in ALL {
+            let coordinates = orientation.texture_coordinates(position, 0.3);
+            assert_eq!(orientation.view_position(coordinates), position);
+            assert_eq!(orientation.depth(coordinates), 0.3);
+        }
+    }
+
+    #[test]
+    fn extent_follows_in_plane_axes() {
+        let extents = ALL.map(|orientation| orientation.extent(DIMENSIONS, SPACING));
+        assert_eq!(extents[0], Vector2::new(2.5, 3.0));
+        assert_eq!(extents[1], Vector2::new(2.5, 8.0));
+        assert_eq!(extents[2], Vector2::new(3.0, 8.0));
+
+        let sizes = ALL.map(|orientation| orientation.size(DIMENSIONS));
+        assert_eq!(
+            sizes,
+            [Vector2::new(5, 3), Vector2::new(5, 2), Vector2::new(3, 2)]
+        );
+    }
+
+    #[test]
+    fn slice_range_follows_normal() {
+        let counts = ALL.map(|orientation| orientation.slice_count(DIMENSIONS));
+        assert_eq!(counts, [2, 3, 5]);
+        let spacings = ALL.map(|orientation| orientation.slice_spacing(SPACING));
+        assert_eq!(spacings, [4.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn every_pixel_shows_its_voxel() {
+        let volume = asymmetric_volume();
+        for orientation in ALL {
+            let [horizontal, vertical, normal] = orientation.axes();
+            let size = orientation.size(DIMENSIONS);
+            for slice in 0..orientation.slice_count(DIMENSIONS) {
+                // the center of the slice, where the cursor puts it
+                let depth = (slice as f32 + 0.5) / orientation.slice_count(DIMENSIONS) as f32;
+
+                for row in 0..size.y {
+                    for column in 0..size.x {
+                        let position = Vector2::new(
+                            (column as f32 + 0.5) / size.x as f32,
+                            (row as f32 + 0.5) / size.y as f32,
+                        );
+                        let [x, y, z] = voxel(orientation.texture_coordinates(position, depth));
+
+                        let mut expected = [0; 3];
+                        expected[horizontal] = column;
+                        expected[vertical] = row;
+                        expected[normal] = slice;
+                        assert_eq!([x, y, z], expected, "{orientation:?}");
+                        assert_eq!(
+                            volume.value(x, y, z) as u32,
+                            expected[0] + 10 * expected[1] + 100 * expected[2]
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
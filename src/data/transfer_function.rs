@@ -0,0 +1,182 @@
+/// A single node of a 1D transfer function.
+///
+/// `value` is given in original voxel units, so saved transfer functions can be
+/// reused for other datasets of the same modality.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ControlPoint {
+    pub value: f32,
+    pub color: [u8; 3],
+    pub opacity: f32,
+}
+
+/// Piecewise linear mapping from voxel value to color and opacity.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TransferFunction {
+    points: Vec<ControlPoint>,
+}
+
+impl Default for TransferFunction {
+    fn default() -> Self {
+        Self::ramp(0.0, u16::MAX as f32)
+    }
+}
+
+impl TransferFunction {
+    /// Number of entries of the lookup table uploaded to the GPU.
+    pub const LOOKUP_TABLE_SIZE: usize = 4096;
+
+    /// Linear grayscale ramp from fully transparent black to opaque white.
+    pub fn ramp(min: f32, max: f32) -> Self {
+        Self {
+            points: vec![
+                ControlPoint {
+                    value: min,
+                    color: [0, 0, 0],
+                    opacity: 0.0,
+                },
+                ControlPoint {
+                    value: max,
+                    color: [255, 255, 255],
+                    opacity: 1.0,
+                },
+            ],
+        }
+    }
+
+    /// Panics if `points` is empty.
+    // only used when loading transfer function files
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_points(mut points: Vec<ControlPoint>) -> Self {
+        assert!(!points.is_empty());
+        points.sort_by(|a, b| a.value.total_cmp(&b.value));
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[ControlPoint] {
+        &self.points
+    }
+
+    /// Inserts a point and returns its index after sorting.
+    pub fn insert(&mut self, point: ControlPoint) -> usize {
+        let index = self.points.partition_point(|p| p.value <= point.value);
+        self.points.insert(index, point);
+        index
+    }
+
+    /// Removes a point, the last remaining point can't be removed.
+    pub fn remove(&mut self, index: usize) {
+        if self.points.len() > 1 && index < self.points.len() {
+            self.points.remove(index);
+        }
+    }
+
+    /// Replaces a point and returns its new index, as moving it may change the order.
+    pub fn update(&mut self, index: usize, point: ControlPoint) -> usize {
+        self.points.remove(index);
+        self.insert(point)
+    }
+
+    /// Interpolated color (sRGB) and opacity at `value`.
+    pub fn sample(&self, value: f32) -> ([u8; 3], f32) {
+        let upper = self.points.partition_point(|p| p.value <= value);
+
+        if upper == 0 {
+            let first = self.points[0];
+            return (first.color, first.opacity);
+        }
+        if upper == self.points.len() {
+            let last = self.points[upper - 1];
+            return (last.color, last.opacity);
+        }
+
+        let a = self.points[upper - 1];
+        let b = self.points[upper];
+        let t = if b.value > a.value {
+            (value - a.value) / (b.value - a.value)
+        } else {
+            0.0
+        };
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let color = [
+            lerp(a.color[0] as f32, b.color[0] as f32).round() as u8,
+            lerp(a.color[1] as f32, b.color[1] as f32).round() as u8,
+            lerp(a.color[2] as f32, b.color[2] as f32).round() as u8,
+        ];
+
+        (color, lerp(a.opacity, b.opacity))
+    }
+
+    /// RGBA8 lookup table spanning `range`, usually the value range of the
+    /// volume, so the entries aren't spent on values that don't occur.
+    pub fn to_lookup_table(&self, range: (f32, f32)) -> Vec<u8> {
+        let (min, max) = range;
+        let step = (max - min) / (Self::LOOKUP_TABLE_SIZE - 1) as f32;
+        (0..Self::LOOKUP_TABLE_SIZE)
+            .flat_map(|i| {
+                let (color, opacity) = self.sample(min + i as f32 * step);
+                [
+                    color[0],
+                    color[1],
+                    color[2],
+                    (opacity.clamp(0.0, 1.0) * 255.0).round() as u8,
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(value: f32, gray: u8, opacity: f32) -> ControlPoint {
+        ControlPoint {
+            value,
+            color: [gray; 3],
+            opacity,
+        }
+    }
+
+    #[test]
+    fn sample_interpolates_and_clamps() {
+        let transfer_function = TransferFunction::ramp(100.0, 200.0);
+        assert_eq!(transfer_function.sample(150.0), ([128, 128, 128], 0.5));
+        assert_eq!(transfer_function.sample(125.0).1, 0.25);
+        // constant outside of the control points
+        assert_eq!(transfer_function.sample(0.0), ([0, 0, 0], 0.0));
+        assert_eq!(transfer_function.sample(1000.0), ([255, 255, 255], 1.0));
+    }
+
+    #[test]
+    fn insert_and_update_keep_the_points_sorted() {
+        let mut transfer_function = TransferFunction::ramp(0.0, 100.0);
+        assert_eq!(transfer_function.insert(point(50.0, 10, 0.2)), 1);
+        assert_eq!(transfer_function.insert(point(-10.0, 20, 0.3)), 0);
+        // moving the middle point past the last one
+        assert_eq!(transfer_function.update(2, point(150.0, 30, 0.4)), 3);
+        let values: Vec<f32> = transfer_function.points().iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![-10.0, 0.0, 100.0, 150.0]);
+
+        transfer_function.remove(0);
+        transfer_function.remove(0);
+        transfer_function.remove(0);
+        transfer_function.remove(0);
+        assert_eq!(transfer_function.points().len(), 1);
+    }
+
+    #[test]
+    fn lookup_table_covers_the_value_range() {
+        let table = TransferFunction::default().to_lookup_table((0.0, u16::MAX as f32));
+        assert_eq!(table.len(), 4 * TransferFunction::LOOKUP_TABLE_SIZE);
+        assert_eq!(table[..4], [0, 0, 0, 0]);
+        assert_eq!(table[table.len() - 4..], [255, 255, 255, 255]);
+
+        // a narrow range gets the full resolution of the table
+        let table = TransferFunction::ramp(1000.0, 1100.0).to_lookup_table((1000.0, 1100.0));
+        assert_eq!(table[..4], [0, 0, 0, 0]);
+        assert_eq!(table[4 * 2047 + 3], 127);
+        assert_eq!(table[table.len() - 4..], [255, 255, 255, 255]);
+    }
+}
@@ -0,0 +1,210 @@
+use std::{path::PathBuf, sync::Arc};
+
+use cgmath::{Matrix3, Matrix4, Point3, Vector3};
+
+use super::CoordinateSystem;
+
+/// CPU-side copy of the loaded volume in its original precision.
+///
+/// The GPU texture only holds normalized f16 values, so everything that needs
+/// the real voxel values (histograms, probes, statistics) reads from here.
+pub struct Volume {
+    pub dimensions: (u32, u32, u32),
+    pub spacing: (f32, f32, f32),
+    /// World position of the center of voxel (0, 0, 0) in mm, LPS.
+    pub origin: (f32, f32, f32),
+    /// Columns are the unit LPS directions of the x, y and z index axes.
+    pub direction: Matrix3<f32>,
+    /// Shared with the threads computing statistics, which would otherwise
+    /// need a copy.
+    pub values: Arc<[u16]>,
+    /// File the volume was loaded from, sidecar files are stored next to it.
+    pub path: Option<PathBuf>,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        // matches the placeholder volume of `apps::Texture::default`
+        Self {
+            dimensions: (1, 1, 1),
+            spacing: (1.0, 1.0, 1.0),
+            origin: (0.0, 0.0, 0.0),
+            direction: Matrix3::from_scale(1.0),
+            values: Arc::new([u16::from_le_bytes([127, 127])]),
+            path: None,
+        }
+    }
+}
+
+impl Volume {
+    pub fn from_u16_bytes(
+        bytes: &[u8],
+        dimensions: (u32, u32, u32),
+        spacing: (f32, f32, f32),
+    ) -> Self {
+        let values = bytes
+            .chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+
+        Self {
+            dimensions,
+            spacing,
+            values,
+            ..Default::default()
+        }
+    }
+
+    /// Position of voxel `(x, y, z)` in `values`.
+    pub fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        let (width, height, _) = self.dimensions;
+        x as usize + width as usize * (y as usize + height as usize * z as usize)
+    }
+
+    pub fn value(&self, x: u32, y: u32, z: u32) -> u16 {
+        self.values[self.index(x, y, z)]
+    }
+
+    /// Trilinear interpolation at a position in voxel index coordinates,
+    /// clamped to the volume.
+    pub fn interpolate(&self, position: [f32; 3]) -> f32 {
+        let (width, height, depth) = self.dimensions;
+        let max = [width, height, depth].map(|d| d.saturating_sub(1) as f32);
+        let position: [f32; 3] = std::array::from_fn(|i| position[i].clamp(0.0, max[i]));
+        let lower = position.map(|c| c.floor() as u32);
+        let upper: [u32; 3] = std::array::from_fn(|i| (lower[i] + 1).min(max[i] as u32));
+        let t: [f32; 3] = std::array::from_fn(|i| position[i] - lower[i] as f32);
+
+        let mut value = 0.0;
+        for corner in 0..8 {
+            let mut weight = 1.0;
+            let mut voxel = [0; 3];
+            for axis in 0..3 {
+                if corner >> axis & 1 == 1 {
+                    voxel[axis] = upper[axis];
+                    weight *= t[axis];
+                } else {
+                    voxel[axis] = lower[axis];
+                    weight *= 1.0 - t[axis];
+                }
+            }
+            value += weight * self.value(voxel[0], voxel[1], voxel[2]) as f32;
+        }
+        value
+    }
+
+    /// Maps voxel index coordinates to LPS world coordinates in mm.
+    pub fn index_to_world(&self) -> Matrix4<f32> {
+        let (x, y, z) = self.spacing;
+        let mut transform = Matrix4::from(Matrix3::from_cols(
+            self.direction.x * x,
+            self.direction.y * y,
+            self.direction.z * z,
+        ));
+        transform.w = Vector3::new(self.origin.0, self.origin.1, self.origin.2).extend(1.0);
+        transform
+    }
+
+    /// Voxel index coordinates of `position` given in mm relative to the
+    /// corner of the volume.
+    pub fn voxel_coordinates(&self, position: Point3<f32>) -> [f32; 3] {
+        [
+            position.x / self.spacing.0 - 0.5,
+            position.y / self.spacing.1 - 0.5,
+            position.z / self.spacing.2 - 0.5,
+        ]
+    }
+
+    /// Position in mm relative to the corner of the volume at voxel index
+    /// coordinates `index`, the inverse of `voxel_coordinates`.
+    pub fn voxel_position(&self, index: [f32; 3]) -> Point3<f32> {
+        Point3::new(
+            (index[0] + 0.5) * self.spacing.0,
+            (index[1] + 0.5) * self.spacing.1,
+            (index[2] + 0.5) * self.spacing.2,
+        )
+    }
+
+    /// Position at voxel index coordinates `index` in `system`.
+    pub fn position_in(&self, index: [f32; 3], system: CoordinateSystem) -> [f32; 3] {
+        if system == CoordinateSystem::Voxel {
+            return index;
+        }
+        let world = self.index_to_world() * Vector3::from(index).extend(1.0);
+        system.transform_lps(world.truncate().into())
+    }
+
+    /// Direction in `system` of `direction` given along the index axes.
+    pub fn direction_in(&self, direction: Vector3<f32>, system: CoordinateSystem) -> Vector3<f32> {
+        if system == CoordinateSystem::Voxel {
+            return direction;
+        }
+        system
+            .transform_lps((self.direction * direction).into())
+            .into()
+    }
+
+    /// Smallest and largest voxel value.
+    pub fn range(&self) -> (u16, u16) {
+        self.values
+            .iter()
+            .fold((u16::MAX, u16::MIN), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Volume lying on its side: x runs anterior, y superior and z left.
+    fn rotated_volume() -> Volume {
+        Volume {
+            dimensions: (4, 3, 2),
+            spacing: (0.5, 1.0, 2.0),
+            origin: (10.0, 20.0, 30.0),
+            direction: Matrix3::from_cols(
+                Vector3::new(0.0, -1.0, 0.0),
+                Vector3::new(0.0, 0.0, 1.0),
+                Vector3::new(1.0, 0.0, 0.0),
+            ),
+            values: vec![0; 24].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn origin_is_first_voxel() {
+        let volume = rotated_volume();
+        let lps = volume.position_in([0.0, 0.0, 0.0], CoordinateSystem::Lps);
+        assert_eq!(lps, [10.0, 20.0, 30.0]);
+        let ras = volume.position_in([0.0, 0.0, 0.0], CoordinateSystem::Ras);
+        assert_eq!(ras, [-10.0, -20.0, 30.0]);
+    }
+
+    #[test]
+    fn index_steps_follow_direction_and_spacing() {
+        let volume = rotated_volume();
+        let lps = volume.position_in([2.0, 1.0, 1.0], CoordinateSystem::Lps);
+        // 2 * 0.5 mm anterior, 1 mm superior, 2 mm left
+        assert_eq!(lps, [12.0, 19.0, 31.0]);
+        let voxel = volume.position_in([2.0, 1.0, 1.0], CoordinateSystem::Voxel);
+        assert_eq!(voxel, [2.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn voxel_position_inverts_voxel_coordinates() {
+        let volume = rotated_volume();
+        let position = Point3::new(1.0, 4.5, 3.0);
+        let index = volume.voxel_coordinates(position);
+        assert_eq!(volume.voxel_position(index), position);
+    }
+
+    #[test]
+    fn directions_ignore_origin() {
+        let volume = rotated_volume();
+        let ras = volume.direction_in(Vector3::new(1.0, 0.0, 0.0), CoordinateSystem::Ras);
+        assert_eq!(ras, Vector3::new(0.0, 1.0, 0.0));
+    }
+}
@@ -0,0 +1,29 @@
+/// Value range mapped from black to white in the grayscale slice views.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowLevel {
+    /// Value shown mid gray.
+    pub level: f32,
+    /// Width of the value range between black and white.
+    pub width: f32,
+}
+
+impl Default for WindowLevel {
+    /// The full range of the 16 bit values.
+    fn default() -> Self {
+        Self::from_range(0.0, u16::MAX as f32)
+    }
+}
+
+impl WindowLevel {
+    pub fn from_range(lower: f32, upper: f32) -> Self {
+        Self {
+            level: (lower + upper) / 2.0,
+            width: (upper - lower).max(1.0),
+        }
+    }
+
+    /// Values shown black and white.
+    pub fn range(&self) -> (f32, f32) {
+        (self.level - self.width / 2.0, self.level + self.width / 2.0)
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::*;
+use std::path::{Path, PathBuf};
+
+use crate::data::{Annotation, AnnotationKind, CoordinateSystem, Volume};
+
+/// Annotation as stored in the sidecar file.
+///
+/// Positions are voxel index coordinates, which stay valid whatever the
+/// spacing and orientation of the volume. The world positions are written
+/// for other tools and ignored when loading.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct AnnotationRecord {
+    kind: AnnotationKind,
+    #[serde(default)]
+    text: String,
+    voxel: [f32; 3],
+    #[serde(default)]
+    tail_voxel: Option<[f32; 3]>,
+    #[serde(default)]
+    world_lps: Option<[f32; 3]>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct AnnotationFile {
+    annotations: Vec<AnnotationRecord>,
+}
+
+/// Sidecar file holding the annotations of the volume at `volume_path`,
+/// e.g. `head.raw.annotations.json` for `head.raw`.
+pub fn annotation_sidecar_path(volume_path: &Path) -> PathBuf {
+    let mut name = volume_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".annotations.json");
+    volume_path.with_file_name(name)
+}
+
+pub fn save_annotations(path: &Path, annotations: &[Annotation], volume: &Volume) -> Result<()> {
+    let file = AnnotationFile {
+        annotations: annotations
+            .iter()
+            .map(|annotation| {
+                let voxel = volume.voxel_coordinates(annotation.position);
+                AnnotationRecord {
+                    kind: annotation.kind,
+                    text: annotation.text.clone(),
+                    voxel,
+                    tail_voxel: annotation.tail.map(|tail| volume.voxel_coordinates(tail)),
+                    world_lps: Some(volume.position_in(voxel, CoordinateSystem::Lps)),
+                }
+            })
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&file)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+pub fn load_annotations(path: &Path, volume: &Volume) -> Result<Vec<Annotation>> {
+    let json = std::fs::read_to_string(path)?;
+    let file: AnnotationFile = serde_json::from_str(&json)?;
+    let annotations = file
+        .annotations
+        .into_iter()
+        .map(|record| Annotation {
+            kind: record.kind,
+            position: volume.voxel_position(record.voxel),
+            tail: record.tail_voxel.map(|tail| volume.voxel_position(tail)),
+            text: record.text,
+        })
+        .collect::<Vec<_>>();
+    ensure!(
+        annotations.iter().all(Annotation::is_complete),
+        "arrows need a tail and text annotations a text"
+    );
+    Ok(annotations)
+}
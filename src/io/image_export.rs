@@ -0,0 +1,68 @@
+use anyhow::*;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::data::Image;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFileFormat {
+    /// 16 bit grayscale with the original voxel values.
+    Pgm,
+    /// 8 bit RGB as displayed.
+    Ppm,
+}
+
+impl ImageFileFormat {
+    pub const ALL: [Self; 2] = [Self::Pgm, Self::Ppm];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pgm => "PGM (16 bit values)",
+            Self::Ppm => "PPM (displayed colors)",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pgm => "pgm",
+            Self::Ppm => "ppm",
+        }
+    }
+}
+
+/// Writes `image` to `path` as binary netpbm. `colors` hold one RGB value per
+/// pixel and are only used by PPM.
+pub fn export_image(
+    path: &Path,
+    image: &Image,
+    format: ImageFileFormat,
+    colors: &[[u8; 3]],
+) -> Result<()> {
+    let (width, height) = image.dimensions;
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        ImageFileFormat::Pgm => {
+            writeln!(writer, "P5\n{width} {height}\n{}", u16::MAX)?;
+            for value in &image.values {
+                // netpbm stores 16 bit samples big endian
+                let value = value.round().clamp(0.0, u16::MAX as f32) as u16;
+                writer.write_all(&value.to_be_bytes())?;
+            }
+        }
+        ImageFileFormat::Ppm => {
+            ensure!(
+                colors.len() == image.values.len(),
+                "expected one color per pixel"
+            );
+            writeln!(writer, "P6\n{width} {height}\n255")?;
+            for color in colors {
+                writer.write_all(color)?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -14,6 +14,10 @@ pub struct ImportItem {
     // endianness: Option<Endianness>,
     pub dimensions: Option<(u32, u32, u32)>,
     pub spacing: Option<(f32, f32, f32)>,
+    /// World position of the first voxel in mm, LPS.
+    pub origin: Option<(f32, f32, f32)>,
+    /// LPS directions of the x, y and z index axes.
+    pub direction: Option<[[f32; 3]; 3]>,
     pub data: Option<Vec<u8>>,
 }
 
@@ -149,6 +153,12 @@ impl Importer {
             1.0
         };
 
+        let (mut origin_x, mut origin_y, mut origin_z) = self.item.origin.unwrap_or_default();
+        let mut direction =
+            self.item
+                .direction
+                .unwrap_or([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
         egui::Window::new("Import raw 3D volume data")
             .open(&mut visible)
             .resizable(false)
@@ -184,6 +194,24 @@ impl Importer {
                         ui.add(egui::DragValue::new(&mut spacing_y));
                         ui.add(egui::DragValue::new(&mut spacing_z));
                     });
+                    ui.horizontal(|ui| {
+                        ui.label("Origin in mm, LPS (x,y,z):");
+                        ui.add(egui::DragValue::new(&mut origin_x));
+                        ui.add(egui::DragValue::new(&mut origin_y));
+                        ui.add(egui::DragValue::new(&mut origin_z));
+                    });
+                    for (axis, name) in ["x", "y", "z"].iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Direction of {name} axis, LPS:"));
+                            for component in &mut direction[axis] {
+                                ui.add(
+                                    egui::DragValue::new(component)
+                                        .clamp_range(-1.0..=1.0)
+                                        .speed(0.01),
+                                );
+                            }
+                        });
+                    }
                 });
 
                 ui.separator();
@@ -214,5 +242,7 @@ impl Importer {
         self.item.bits = Some(bits as u8);
         self.item.dimensions = Some((dimension_x, dimension_y, dimension_z));
         self.item.spacing = Some((spacing_x, spacing_y, spacing_z));
+        self.item.origin = Some((origin_x, origin_y, origin_z));
+        self.item.direction = Some(direction);
     }
 }
@@ -0,0 +1,41 @@
+use anyhow::*;
+use std::path::Path;
+
+use crate::data::LabelMap;
+
+/// Reads a RAW label volume with the dimensions of the loaded volume. 8 and
+/// 16 bit little endian labels are told apart by the file size.
+pub fn load_label_map(path: &Path, dimensions: (u32, u32, u32)) -> Result<LabelMap> {
+    let bytes = std::fs::read(path)?;
+    let (width, height, depth) = dimensions;
+    let voxel_count = width as usize * height as usize * depth as usize;
+    let values = if bytes.len() == voxel_count {
+        bytes.iter().map(|&value| value as u16).collect()
+    } else if bytes.len() == 2 * voxel_count {
+        bytes
+            .chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect()
+    } else {
+        bail!(
+            "{} bytes do not hold 8 or 16 bit labels of a {width}x{height}x{depth} volume",
+            bytes.len()
+        );
+    };
+    Ok(LabelMap { dimensions, values })
+}
+
+/// Writes the labels as RAW, 8 bit if `max_label` fits and 16 bit little
+/// endian otherwise.
+pub fn save_label_map(path: &Path, map: &LabelMap, max_label: u16) -> Result<()> {
+    let bytes: Vec<u8> = if max_label > u8::MAX as u16 {
+        map.values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    } else {
+        map.values.iter().map(|&value| value as u8).collect()
+    };
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
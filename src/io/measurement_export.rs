@@ -0,0 +1,50 @@
+use anyhow::*;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::data::{CoordinateSystem, Measurement, MeasurementKind, Volume};
+
+/// Writes one CSV row per measurement with its value and its points in
+/// `system`, separated by semicolons.
+pub fn export_measurements(
+    path: &Path,
+    measurements: &[Measurement],
+    volume: &Volume,
+    system: CoordinateSystem,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(
+        writer,
+        "index,type,value,unit,closed,points ({} {})",
+        system.label(),
+        system.unit()
+    )?;
+    for (index, measurement) in measurements.iter().enumerate() {
+        let unit = match measurement.kind {
+            MeasurementKind::Angle => "degrees",
+            _ => measurement.kind.unit(),
+        };
+        let points: Vec<_> = measurement
+            .points
+            .iter()
+            .map(|&point| {
+                let [x, y, z] = volume.position_in(volume.voxel_coordinates(point), system);
+                format!("{x:.3} {y:.3} {z:.3}")
+            })
+            .collect();
+        writeln!(
+            writer,
+            "{},{},{:.3},{unit},{},{}",
+            index + 1,
+            measurement.kind.label(),
+            measurement.value(),
+            measurement.closed,
+            points.join(";")
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
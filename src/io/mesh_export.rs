@@ -0,0 +1,250 @@
+use anyhow::*;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::data::Mesh;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeshFileFormat {
+    StlBinary,
+    StlAscii,
+    Obj,
+    Ply,
+}
+
+impl MeshFileFormat {
+    pub const ALL: [Self; 4] = [Self::StlBinary, Self::StlAscii, Self::Obj, Self::Ply];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::StlBinary => "STL (binary)",
+            Self::StlAscii => "STL (ASCII)",
+            Self::Obj => "Wavefront OBJ",
+            Self::Ply => "Stanford PLY",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::StlBinary | Self::StlAscii => "stl",
+            Self::Obj => "obj",
+            Self::Ply => "ply",
+        }
+    }
+}
+
+/// Writes `mesh` to `path`. `scalars` hold one value per vertex and are only
+/// stored by PLY.
+pub fn export_mesh(
+    path: &Path,
+    mesh: &Mesh,
+    format: MeshFileFormat,
+    scalars: Option<&[f32]>,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        MeshFileFormat::StlBinary => write_stl_binary(&mut writer, mesh)?,
+        MeshFileFormat::StlAscii => write_stl_ascii(&mut writer, mesh)?,
+        MeshFileFormat::Obj => write_obj(&mut writer, mesh)?,
+        MeshFileFormat::Ply => write_ply(&mut writer, mesh, scalars)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn unit_face_normal(mesh: &Mesh, triangle: &[u32]) -> [f32; 3] {
+    let normal = mesh.face_normal([triangle[0], triangle[1], triangle[2]]);
+    let length = normal.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if length > 0.0 {
+        normal.map(|c| c / length)
+    } else {
+        normal
+    }
+}
+
+fn write_stl_binary(writer: &mut impl Write, mesh: &Mesh) -> Result<()> {
+    let mut header = [0u8; 80];
+    let name = b"vds isosurface";
+    header[..name.len()].copy_from_slice(name);
+    writer.write_all(&header)?;
+    writer.write_all(&(mesh.triangle_count() as u32).to_le_bytes())?;
+
+    for triangle in mesh.indices.chunks_exact(3) {
+        let normal = unit_face_normal(mesh, triangle);
+        let vertices = triangle.iter().map(|&i| mesh.positions[i as usize]);
+        for vector in std::iter::once(normal).chain(vertices) {
+            for component in vector {
+                writer.write_all(&component.to_le_bytes())?;
+            }
+        }
+        // attribute byte count
+        writer.write_all(&0u16.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_stl_ascii(writer: &mut impl Write, mesh: &Mesh) -> Result<()> {
+    writeln!(writer, "solid isosurface")?;
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [nx, ny, nz] = unit_face_normal(mesh, triangle);
+        writeln!(writer, "  facet normal {nx:e} {ny:e} {nz:e}")?;
+        writeln!(writer, "    outer loop")?;
+        for &i in triangle {
+            let [x, y, z] = mesh.positions[i as usize];
+            writeln!(writer, "      vertex {x:e} {y:e} {z:e}")?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid isosurface")?;
+    Ok(())
+}
+
+fn write_obj(writer: &mut impl Write, mesh: &Mesh) -> Result<()> {
+    for [x, y, z] in &mesh.positions {
+        writeln!(writer, "v {x} {y} {z}")?;
+    }
+    for [x, y, z] in &mesh.normals {
+        writeln!(writer, "vn {x} {y} {z}")?;
+    }
+    // indices are 1-based, normals share the vertex index
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i] + 1);
+        writeln!(writer, "f {a}//{a} {b}//{b} {c}//{c}")?;
+    }
+    Ok(())
+}
+
+fn write_ply(writer: &mut impl Write, mesh: &Mesh, scalars: Option<&[f32]>) -> Result<()> {
+    if let Some(scalars) = scalars {
+        ensure!(
+            scalars.len() == mesh.positions.len(),
+            "expected one scalar per vertex"
+        );
+    }
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format binary_little_endian 1.0")?;
+    writeln!(writer, "element vertex {}", mesh.positions.len())?;
+    for property in ["x", "y", "z", "nx", "ny", "nz"] {
+        writeln!(writer, "property float {property}")?;
+    }
+    if scalars.is_some() {
+        writeln!(writer, "property float value")?;
+    }
+    writeln!(writer, "element face {}", mesh.triangle_count())?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    for (i, (position, normal)) in mesh.positions.iter().zip(&mesh.normals).enumerate() {
+        for component in position.iter().chain(normal) {
+            writer.write_all(&component.to_le_bytes())?;
+        }
+        if let Some(scalars) = scalars {
+            writer.write_all(&scalars[i].to_le_bytes())?;
+        }
+    }
+    for triangle in mesh.indices.chunks_exact(3) {
+        writer.write_all(&[3])?;
+        for index in triangle {
+            writer.write_all(&index.to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two triangles of the unit square in the xy plane, facing +z.
+    fn square() -> Mesh {
+        Mesh {
+            positions: vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ],
+            normals: vec![[0.0, 0.0, 1.0]; 4],
+            indices: vec![0, 1, 2, 0, 2, 3],
+        }
+    }
+
+    fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn binary_stl_has_header_count_and_facets() {
+        let mut bytes = Vec::new();
+        write_stl_binary(&mut bytes, &square()).unwrap();
+        assert_eq!(bytes.len(), 80 + 4 + 2 * 50);
+        assert!(bytes[..80].starts_with(b"vds isosurface"));
+        assert_eq!(u32::from_le_bytes(bytes[80..84].try_into().unwrap()), 2);
+        // normal and last vertex of the first facet
+        assert_eq!(f32_at(&bytes, 84 + 8), 1.0);
+        assert_eq!(f32_at(&bytes, 84 + 36), 1.0);
+        assert_eq!(f32_at(&bytes, 84 + 40), 1.0);
+    }
+
+    #[test]
+    fn ascii_stl_lists_the_facets() {
+        let mut bytes = Vec::new();
+        write_stl_ascii(&mut bytes, &square()).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.starts_with("solid isosurface\n"));
+        assert!(text.trim_end().ends_with("endsolid isosurface"));
+        assert_eq!(text.matches("facet normal 0e0 0e0 1e0").count(), 2);
+        assert_eq!(text.matches("vertex ").count(), 6);
+    }
+
+    #[test]
+    fn obj_faces_are_one_based() {
+        let mut bytes = Vec::new();
+        write_obj(&mut bytes, &square()).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4 + 4 + 2);
+        assert_eq!(lines[2], "v 1 1 0");
+        assert_eq!(lines[4], "vn 0 0 1");
+        assert_eq!(lines[8], "f 1//1 2//2 3//3");
+        assert_eq!(lines[9], "f 1//1 3//3 4//4");
+    }
+
+    #[test]
+    fn ply_header_lists_the_scalar_if_given() {
+        let header_end = b"end_header\n";
+        let split = |bytes: &[u8]| {
+            let end = bytes
+                .windows(header_end.len())
+                .position(|window| window == header_end)
+                .unwrap()
+                + header_end.len();
+            (String::from_utf8(bytes[..end].to_vec()).unwrap(), end)
+        };
+        let face_bytes = 2 * (1 + 3 * 4);
+
+        let mut bytes = Vec::new();
+        write_ply(&mut bytes, &square(), None).unwrap();
+        let (header, end) = split(&bytes);
+        assert!(header.starts_with("ply\nformat binary_little_endian 1.0\n"));
+        assert!(header.contains("element vertex 4\n"));
+        assert!(header.contains("element face 2\n"));
+        assert!(!header.contains("property float value"));
+        assert_eq!(bytes.len() - end, 4 * 6 * 4 + face_bytes);
+
+        let mut bytes = Vec::new();
+        let scalars = [1.0, 2.0, 3.0, 4.0];
+        write_ply(&mut bytes, &square(), Some(&scalars)).unwrap();
+        let (header, end) = split(&bytes);
+        assert!(header.contains("property float nz\nproperty float value\nelement face"));
+        assert_eq!(bytes.len() - end, 4 * 7 * 4 + face_bytes);
+        assert_eq!(f32_at(&bytes, end + 6 * 4), 1.0);
+
+        assert!(write_ply(&mut Vec::new(), &square(), Some(&scalars[..3])).is_err());
+    }
+}
@@ -1,5 +1,37 @@
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+mod annotations;
 mod common;
+#[cfg(not(target_arch = "wasm32"))]
+mod image_export;
 mod import;
+#[cfg(not(target_arch = "wasm32"))]
+mod label_map;
+#[cfg(not(target_arch = "wasm32"))]
+mod measurement_export;
+#[cfg(not(target_arch = "wasm32"))]
+mod mesh_export;
+#[cfg(not(target_arch = "wasm32"))]
+mod profile_export;
+#[cfg(not(target_arch = "wasm32"))]
+mod roi_export;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+mod transfer_function;
 
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub use annotations::*;
 pub use common::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use image_export::*;
 pub use import::Importer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use label_map::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use measurement_export::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use mesh_export::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use profile_export::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use roi_export::*;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub use transfer_function::*;
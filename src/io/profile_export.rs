@@ -0,0 +1,20 @@
+use anyhow::*;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Writes one CSV row per sample of each profile, profiles are numbered
+/// like the measurements they come from.
+pub fn export_line_profiles(path: &Path, profiles: &[(usize, Vec<[f32; 2]>)]) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "profile,distance (mm),value")?;
+    for (number, samples) in profiles {
+        for [distance, value] in samples {
+            writeln!(writer, "{number},{distance:.4},{value:.4}")?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anyhow::*;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::data::{Roi, RoiStatistics};
+
+/// Writes one CSV row per region with its shape and statistics, the
+/// statistics are left empty for regions without voxels.
+pub fn export_roi_statistics(
+    path: &Path,
+    rois: &[Roi],
+    statistics: &[Option<RoiStatistics>],
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let percentiles: Vec<_> = RoiStatistics::PERCENTILES
+        .iter()
+        .map(|percent| format!("p{percent}"))
+        .collect();
+    writeln!(
+        writer,
+        "index,shape,voxels,size,unit,mean,std,min,max,median,{}",
+        percentiles.join(",")
+    )?;
+    for (index, (roi, statistics)) in rois.iter().zip(statistics).enumerate() {
+        let unit = if roi.shape.is_3d() { "mm3" } else { "mm2" };
+        write!(writer, "{},{}", index + 1, roi.shape.label())?;
+        match statistics {
+            Some(s) => {
+                let percentiles: Vec<_> = s.percentiles.iter().map(|p| format!("{p:.3}")).collect();
+                writeln!(
+                    writer,
+                    ",{},{:.3},{unit},{:.3},{:.3},{:.3},{:.3},{:.3},{}",
+                    s.count,
+                    s.size,
+                    s.mean,
+                    s.std,
+                    s.min,
+                    s.max,
+                    s.median,
+                    percentiles.join(",")
+                )?;
+            }
+            None => writeln!(writer, ",0,0,{unit}{}", ",".repeat(5 + percentiles.len()))?,
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
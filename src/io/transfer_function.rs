@@ -0,0 +1,27 @@
+use anyhow::*;
+use std::path::Path;
+
+use crate::data::TransferFunction;
+
+pub fn save_transfer_function(path: &Path, transfer_function: &TransferFunction) -> Result<()> {
+    let json = serde_json::to_string_pretty(transfer_function)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+pub fn load_transfer_function(path: &Path) -> Result<TransferFunction> {
+    let json = std::fs::read_to_string(path)?;
+    TransferFunction::from_json(&json)
+}
+
+impl TransferFunction {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let loaded: TransferFunction = serde_json::from_str(json)?;
+        ensure!(
+            !loaded.points().is_empty(),
+            "transfer function has no control points"
+        );
+        // don't rely on the file being sorted
+        Ok(TransferFunction::from_points(loaded.points().to_vec()))
+    }
+}
@@ -2,8 +2,10 @@
 
 mod apps;
 mod backend_panel;
+mod data;
 mod frame_history;
 mod io;
+mod processing;
 mod wrap_app;
 
 pub use wrap_app::WrapApp;
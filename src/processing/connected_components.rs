@@ -0,0 +1,120 @@
+use super::region_growing::{for_each_neighbor, Connectivity};
+
+/// One connected part of a mask.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Component {
+    pub voxel_count: u64,
+    /// Mean voxel index coordinates.
+    pub centroid: [f32; 3],
+}
+
+/// Connected parts of a mask, largest first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Components {
+    /// Index into `components` plus one for every voxel, 0 outside the mask.
+    pub labels: Vec<u32>,
+    pub components: Vec<Component>,
+}
+
+/// Splits the voxels set in `mask`, in the order of the volume values, into
+/// the parts connected through neighbors of `connectivity`.
+pub fn connected_components(
+    mask: &[bool],
+    dimensions: (u32, u32, u32),
+    connectivity: Connectivity,
+) -> Components {
+    let (width, height, depth) = dimensions;
+    let index = |[x, y, z]: [u32; 3]| {
+        x as usize + width as usize * (y as usize + height as usize * z as usize)
+    };
+    let offsets = connectivity.offsets();
+
+    // label in order of discovery first
+    let mut labels = vec![0u32; mask.len()];
+    let mut found: Vec<(u64, [f64; 3])> = Vec::new();
+    for z in 0..depth {
+        for y in 0..height {
+            for x in 0..width {
+                let start = index([x, y, z]);
+                if !mask[start] || labels[start] != 0 {
+                    continue;
+                }
+                let label = found.len() as u32 + 1;
+                let (mut count, mut sum) = (0, [0.0; 3]);
+                labels[start] = label;
+                let mut stack = vec![[x, y, z]];
+                while let Some(voxel) = stack.pop() {
+                    count += 1;
+                    for axis in 0..3 {
+                        sum[axis] += voxel[axis] as f64;
+                    }
+                    for_each_neighbor(voxel, [width, height, depth], &offsets, |neighbor| {
+                        let i = index(neighbor);
+                        if mask[i] && labels[i] == 0 {
+                            labels[i] = label;
+                            stack.push(neighbor);
+                        }
+                    });
+                }
+                found.push((count, sum));
+            }
+        }
+    }
+
+    // then renumber by size
+    let mut order: Vec<usize> = (0..found.len()).collect();
+    order.sort_by_key(|&component| std::cmp::Reverse(found[component].0));
+    let mut renumbered = vec![0u32; found.len() + 1];
+    for (rank, &component) in order.iter().enumerate() {
+        renumbered[component + 1] = rank as u32 + 1;
+    }
+    for label in &mut labels {
+        *label = renumbered[*label as usize];
+    }
+    let components = order
+        .into_iter()
+        .map(|component| {
+            let (count, sum) = found[component];
+            Component {
+                voxel_count: count,
+                centroid: sum.map(|sum| (sum / count as f64) as f32),
+            }
+        })
+        .collect();
+    Components { labels, components }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_are_sorted_by_size() {
+        // a row of three, a single voxel and a pair touching it diagonally
+        #[rustfmt::skip]
+        let mask = [
+            true,  true,  true,  false,
+            false, false, false, false,
+            true,  false, false, false,
+            false, true,  true,  false,
+        ];
+        let components = connected_components(&mask, (4, 4, 1), Connectivity::Faces);
+        let counts: Vec<u64> = components
+            .components
+            .iter()
+            .map(|c| c.voxel_count)
+            .collect();
+        assert_eq!(counts, vec![3, 2, 1]);
+        assert_eq!(components.components[0].centroid, [1.0, 0.0, 0.0]);
+        assert_eq!(components.labels[13], 2);
+        assert_eq!(components.labels[8], 3);
+
+        let components = connected_components(&mask, (4, 4, 1), Connectivity::Edges);
+        let counts: Vec<u64> = components
+            .components
+            .iter()
+            .map(|c| c.voxel_count)
+            .collect();
+        assert_eq!(counts, vec![3, 3]);
+    }
+}
@@ -0,0 +1,203 @@
+use cgmath::{InnerSpace, Vector3};
+
+use crate::data::{Image, Volume};
+
+/// How the rows of a curved planar reformation are laid out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReformationMode {
+    /// Rows are fixed heights along the z axis, so the curve keeps its course
+    /// in z while its length is unrolled.
+    Stretched,
+    /// Rows are offsets perpendicular to the curve, which becomes the middle row.
+    Straightened,
+}
+
+impl ReformationMode {
+    pub const ALL: [Self; 2] = [Self::Stretched, Self::Straightened];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Stretched => "Stretched",
+            Self::Straightened => "Straightened",
+        }
+    }
+}
+
+/// Resamples `volume` on the surface swept by a line moving along `curve`.
+///
+/// `curve` holds points in mm from the corner of the volume, `spacing` mm
+/// apart, and every point becomes one column of the image. Rows are `spacing`
+/// mm apart as well and cover `height` mm, centered on the middle of the
+/// volume in stretched mode and on the curve in straightened mode. Samples
+/// outside the volume are 0.
+pub fn curved_reformation(
+    volume: &Volume,
+    curve: &[[f32; 3]],
+    mode: ReformationMode,
+    height: f32,
+    spacing: f32,
+) -> Image {
+    let rows = (height / spacing).ceil().max(1.0) as u32;
+    let mut image = Image::new((curve.len() as u32, rows), (spacing, spacing));
+    if curve.is_empty() {
+        return image;
+    }
+
+    let (width, depth, slices) = volume.dimensions;
+    let extent = Vector3::new(
+        width as f32 * volume.spacing.0,
+        depth as f32 * volume.spacing.1,
+        slices as f32 * volume.spacing.2,
+    );
+    let point = |i: usize| Vector3::from(curve[i.min(curve.len() - 1)]);
+    // offset of the top row from the middle
+    let top = 0.5 * (rows - 1) as f32 * spacing;
+
+    let mut previous_up = Vector3::unit_z();
+    for column in 0..curve.len() {
+        let (origin, up) = match mode {
+            ReformationMode::Stretched => (
+                Vector3::new(point(column).x, point(column).y, 0.5 * extent.z),
+                Vector3::unit_z(),
+            ),
+            ReformationMode::Straightened => {
+                let tangent = point(column + 1) - point(column.saturating_sub(1));
+                // the z axis made perpendicular to the curve
+                let up = if tangent.magnitude2() > 0.0 {
+                    let tangent = tangent.normalize();
+                    let up = Vector3::unit_z() - tangent * tangent.z;
+                    if up.magnitude2() > 1e-6 {
+                        up.normalize()
+                    } else {
+                        // running along z, keep the previous direction
+                        previous_up
+                    }
+                } else {
+                    previous_up
+                };
+                previous_up = up;
+                (point(column), up)
+            }
+        };
+
+        for row in 0..rows {
+            let position = origin + up * (top - row as f32 * spacing);
+            let index = image.index(column as u32, row);
+            image.values[index] = sample(volume, position, extent);
+        }
+    }
+
+    image
+}
+
+/// Trilinear sample at `position` in mm, 0 outside the volume.
+fn sample(volume: &Volume, position: Vector3<f32>, extent: Vector3<f32>) -> f32 {
+    if (0..3).any(|i| position[i] < 0.0 || position[i] > extent[i]) {
+        return 0.0;
+    }
+    let spacing = [volume.spacing.0, volume.spacing.1, volume.spacing.2];
+    // voxel centers lie half a voxel from the corner
+    volume.interpolate(std::array::from_fn(|i| position[i] / spacing[i] - 0.5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMENSIONS: (u32, u32, u32) = (8, 6, 5);
+
+    /// Volume of 1 mm voxels whose values encode the voxel index as
+    /// `1 + x + 10 y + 100 z`, so no voxel is 0.
+    fn volume() -> Volume {
+        let (width, height, depth) = DIMENSIONS;
+        let mut values = Vec::new();
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    values.push((1 + x + 10 * y + 100 * z) as u16);
+                }
+            }
+        }
+        Volume {
+            dimensions: DIMENSIONS,
+            spacing: (1.0, 1.0, 1.0),
+            values: values.into(),
+            ..Default::default()
+        }
+    }
+
+    fn pixel(image: &Image, column: u32, row: u32) -> f32 {
+        image.values[image.index(column, row)]
+    }
+
+    #[test]
+    fn straight_path_along_x_reproduces_the_axial_rows() {
+        let volume = volume();
+        // through the voxel centers of row y = 2 in the middle slice z = 2
+        let curve: Vec<[f32; 3]> = (0..8).map(|x| [x as f32 + 0.5, 2.5, 2.5]).collect();
+        for mode in ReformationMode::ALL {
+            let image = curved_reformation(&volume, &curve, mode, 5.0, 1.0);
+            assert_eq!(image.dimensions, (8, 5));
+            for row in 0..5 {
+                // the top row is the top slice
+                let z = 4 - row;
+                for x in 0..8 {
+                    assert_eq!(pixel(&image, x, row), (1 + x + 20 + 100 * z) as f32);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn samples_outside_the_volume_are_zero() {
+        let volume = volume();
+        let curve: Vec<[f32; 3]> = (0..8).map(|x| [x as f32 + 0.5, 2.5, 2.5]).collect();
+        for mode in ReformationMode::ALL {
+            // rows from 6.5 mm down to -1.5 mm
+            let image = curved_reformation(&volume, &curve, mode, 9.0, 1.0);
+            for x in 0..8 {
+                for row in [0, 1, 7, 8] {
+                    assert_eq!(pixel(&image, x, row), 0.0);
+                }
+                assert_eq!(pixel(&image, x, 4), (1 + x + 20 + 200) as f32);
+            }
+        }
+
+        // a path leaving the volume sideways
+        let curve = [[7.5, 2.5, 2.5], [8.5, 2.5, 2.5]];
+        let image = curved_reformation(&volume, &curve, ReformationMode::Stretched, 1.0, 1.0);
+        assert_eq!(image.values, vec![228.0, 0.0]);
+    }
+
+    #[test]
+    fn vertical_and_repeated_points_keep_the_previous_up() {
+        let volume = volume();
+        // along x, then straight up from (2.5, 2.5, 1.5)
+        let curve = [
+            [0.5, 2.5, 1.5],
+            [1.5, 2.5, 1.5],
+            [2.5, 2.5, 1.5],
+            [2.5, 2.5, 2.5],
+            [2.5, 2.5, 3.5],
+            [2.5, 2.5, 3.5],
+        ];
+        let image = curved_reformation(&volume, &curve, ReformationMode::Straightened, 3.0, 1.0);
+        assert!(image.values.iter().all(|value| value.is_finite()));
+
+        let extent = Vector3::new(8.0, 6.0, 5.0);
+        // the corner tangent is diagonal, so its up vector leans back
+        let corner_up = Vector3::new(-1.0, 0.0, 1.0).normalize();
+        for (column, &point) in curve.iter().enumerate().skip(2) {
+            for row in 0..3 {
+                let position = Vector3::from(point) + corner_up * (1.0 - row as f32);
+                let expected = sample(&volume, position, extent);
+                let value = pixel(&image, column as u32, row);
+                assert!((value - expected).abs() < 1e-3, "column {column} row {row}");
+            }
+        }
+        // the repeated last point has no tangent of its own
+        for row in 0..3 {
+            assert_eq!(pixel(&image, 4, row), pixel(&image, 5, row));
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use cgmath::Point3;
+
+use super::Components;
+use crate::data::{LabelMap, SliceOrientation, Volume};
+
+/// First and last slice along z changed by an edit, `None` if nothing changed.
+pub type ChangedSlices = Option<(u32, u32)>;
+
+fn include(changed: &mut ChangedSlices, z: u32) {
+    *changed = Some(match *changed {
+        Some((first, last)) => (first.min(z), last.max(z)),
+        None => (z, z),
+    });
+}
+
+/// Whether a voxel with `value` may be edited, `range` limits edits to
+/// voxels with values inside it.
+fn in_range(value: u16, range: Option<(u16, u16)>) -> bool {
+    match range {
+        Some((lower, upper)) => (lower..=upper).contains(&value),
+        None => true,
+    }
+}
+
+/// Sets the voxels whose centers lie within `radius` mm of `center` to
+/// `label`, the voxel containing `center` always. A brush with `slice_axis`
+/// only paints the slice through `center` normal to that axis.
+///
+/// `center` is given in mm from the corner of the volume like the
+/// measurement points, painting `0` erases.
+pub fn paint_brush(
+    map: &mut LabelMap,
+    volume: &Volume,
+    center: Point3<f32>,
+    radius: f32,
+    slice_axis: Option<usize>,
+    label: u16,
+    range: Option<(u16, u16)>,
+) -> ChangedSlices {
+    let (width, height, depth) = map.dimensions;
+    let dimensions = [width, height, depth];
+    let spacing = [volume.spacing.0, volume.spacing.1, volume.spacing.2];
+    let voxel = volume.voxel_coordinates(center);
+
+    let mut center_index = [0; 3];
+    let mut bounds = [(0, 0); 3];
+    for axis in 0..3 {
+        let last = dimensions[axis] as f32 - 1.0;
+        let index = (voxel[axis] + 0.5).floor();
+        if !(0.0..=last).contains(&index) {
+            return None;
+        }
+        center_index[axis] = index as u32;
+        bounds[axis] = if slice_axis == Some(axis) {
+            (index as u32, index as u32)
+        } else {
+            let reach = radius / spacing[axis];
+            let first = (voxel[axis] - reach).ceil().max(0.0);
+            let last = (voxel[axis] + reach).floor().min(last);
+            (first.min(index) as u32, last.max(index) as u32)
+        };
+    }
+
+    let mut changed = None;
+    for z in bounds[2].0..=bounds[2].1 {
+        for y in bounds[1].0..=bounds[1].1 {
+            for x in bounds[0].0..=bounds[0].1 {
+                let index = [x, y, z];
+                let distance_squared: f32 = (0..3)
+                    .filter(|&axis| slice_axis != Some(axis))
+                    .map(|axis| ((index[axis] as f32 - voxel[axis]) * spacing[axis]).powi(2))
+                    .sum();
+                if distance_squared > radius * radius && index != center_index {
+                    continue;
+                }
+                if !in_range(volume.value(x, y, z), range) {
+                    continue;
+                }
+                let i = map.index(x, y, z);
+                if map.values[i] != label {
+                    map.values[i] = label;
+                    include(&mut changed, z);
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// Sets the connected voxels with the label of `seed` in its slice normal
+/// to `normal_axis` to `label`, growing over edges only.
+pub fn fill_slice(
+    map: &mut LabelMap,
+    volume: &Volume,
+    seed: [u32; 3],
+    normal_axis: usize,
+    label: u16,
+    range: Option<(u16, u16)>,
+) -> ChangedSlices {
+    let (width, height, depth) = map.dimensions;
+    let dimensions = [width, height, depth];
+    let target = map.label(seed[0], seed[1], seed[2]);
+    if target == label {
+        return None;
+    }
+
+    let [u, v, _] = SliceOrientation::from_normal_axis(normal_axis).axes();
+    let mut changed = None;
+    let mut stack = vec![seed];
+    while let Some(voxel) = stack.pop() {
+        let [x, y, z] = voxel;
+        let i = map.index(x, y, z);
+        if map.values[i] != target || !in_range(volume.value(x, y, z), range) {
+            continue;
+        }
+        map.values[i] = label;
+        include(&mut changed, z);
+        for axis in [u, v] {
+            if voxel[axis] > 0 {
+                let mut neighbor = voxel;
+                neighbor[axis] -= 1;
+                stack.push(neighbor);
+            }
+            if voxel[axis] + 1 < dimensions[axis] {
+                let mut neighbor = voxel;
+                neighbor[axis] += 1;
+                stack.push(neighbor);
+            }
+        }
+    }
+    changed
+}
+
+/// Fills the slices normal to `axis` lying between slices that contain
+/// `label`, so outlines drawn every few slices become a solid.
+///
+/// The shape in between blends the signed distances to the outlines of the
+/// two closest painted slices. Only background voxels are changed.
+pub fn interpolate_slices(map: &mut LabelMap, label: u16, axis: usize) -> ChangedSlices {
+    let (width, height, depth) = map.dimensions;
+    let dimensions = [width, height, depth];
+    let [u, v, _] = SliceOrientation::from_normal_axis(axis).axes();
+    let (columns, rows) = (dimensions[u] as usize, dimensions[v] as usize);
+    let voxel = |slice: u32, column: usize, row: usize| {
+        let mut index = [0; 3];
+        index[axis] = slice;
+        index[u] = column as u32;
+        index[v] = row as u32;
+        index
+    };
+    let mask = |map: &LabelMap, slice: u32| -> Vec<bool> {
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let [x, y, z] = voxel(slice, column, row);
+                map.label(x, y, z) == label
+            })
+            .collect()
+    };
+
+    let painted: Vec<u32> = (0..dimensions[axis])
+        .filter(|&slice| mask(map, slice).contains(&true))
+        .collect();
+    let mut changed = None;
+    for pair in painted.windows(2) {
+        let (first, last) = (pair[0], pair[1]);
+        if last - first < 2 {
+            continue;
+        }
+        let first_distance = signed_distance(&mask(map, first), columns, rows);
+        let last_distance = signed_distance(&mask(map, last), columns, rows);
+        for slice in first + 1..last {
+            let t = (slice - first) as f32 / (last - first) as f32;
+            for row in 0..rows {
+                for column in 0..columns {
+                    let pixel = column + row * columns;
+                    let distance = (1.0 - t) * first_distance[pixel] + t * last_distance[pixel];
+                    let [x, y, z] = voxel(slice, column, row);
+                    let i = map.index(x, y, z);
+                    if distance < 0.0 && map.values[i] == 0 {
+                        map.values[i] = label;
+                        include(&mut changed, z);
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// Sets the voxels with values in `range` to `label`. Voxels of other labels
+/// keep them unless `overwrite` is set.
+pub fn apply_threshold(
+    map: &mut LabelMap,
+    volume: &Volume,
+    range: (u16, u16),
+    label: u16,
+    overwrite: bool,
+) -> ChangedSlices {
+    apply_where(map, label, overwrite, |i| {
+        in_range(volume.values[i], Some(range))
+    })
+}
+
+/// Sets the voxels set in `mask`, in the order of the volume values, to
+/// `label`. Voxels of other labels keep them unless `overwrite` is set.
+pub fn apply_mask(map: &mut LabelMap, mask: &[bool], label: u16, overwrite: bool) -> ChangedSlices {
+    apply_where(map, label, overwrite, |i| mask[i])
+}
+
+fn apply_where(
+    map: &mut LabelMap,
+    label: u16,
+    overwrite: bool,
+    selected: impl Fn(usize) -> bool,
+) -> ChangedSlices {
+    let (width, height, _) = map.dimensions;
+    let slice_size = width as usize * height as usize;
+    let mut changed = None;
+    for (i, current) in map.values.iter_mut().enumerate() {
+        if *current != label && (overwrite || *current == 0) && selected(i) {
+            *current = label;
+            include(&mut changed, (i / slice_size) as u32);
+        }
+    }
+    changed
+}
+
+/// Sets the voxels of each of `components` that still carry `source` to
+/// the label at its index in `labels`. Components past the end of `labels`
+/// are left unchanged.
+pub fn relabel_components(
+    map: &mut LabelMap,
+    components: &Components,
+    source: u16,
+    labels: &[u16],
+) -> ChangedSlices {
+    let (width, height, _) = map.dimensions;
+    let slice_size = width as usize * height as usize;
+    let mut changed = None;
+    for (i, (current, &component)) in map.values.iter_mut().zip(&components.labels).enumerate() {
+        if component == 0 || *current != source {
+            continue;
+        }
+        match labels.get(component as usize - 1) {
+            Some(&label) if label != source => {
+                *current = label;
+                include(&mut changed, (i / slice_size) as u32);
+            }
+            _ => (),
+        }
+    }
+    changed
+}
+
+/// Distance in pixels to the outline of `mask`, negative inside.
+fn signed_distance(mask: &[bool], width: usize, height: usize) -> Vec<f32> {
+    let to_inside = chamfer_distance(mask, width, height, true);
+    let to_outside = chamfer_distance(mask, width, height, false);
+    mask.iter()
+        .zip(to_inside.into_iter().zip(to_outside))
+        .map(|(&inside, (to_inside, to_outside))| {
+            // the outline runs half a pixel from the centers of its pixels
+            if inside {
+                0.5 - to_outside
+            } else {
+                to_inside - 0.5
+            }
+        })
+        .collect()
+}
+
+/// Approximate Euclidean distance in pixels from every pixel to the closest
+/// pixel where `mask` is `target`, using two passes of a 3x3 chamfer.
+fn chamfer_distance(mask: &[bool], width: usize, height: usize, target: bool) -> Vec<f32> {
+    const DIAGONAL: f32 = std::f32::consts::SQRT_2;
+    let mut distance: Vec<f32> = mask
+        .iter()
+        .map(|&value| if value == target { 0.0 } else { f32::INFINITY })
+        .collect();
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = x + y * width;
+            let mut d = distance[i];
+            if x > 0 {
+                d = d.min(distance[i - 1] + 1.0);
+            }
+            if y > 0 {
+                d = d.min(distance[i - width] + 1.0);
+                if x > 0 {
+                    d = d.min(distance[i - width - 1] + DIAGONAL);
+                }
+                if x + 1 < width {
+                    d = d.min(distance[i - width + 1] + DIAGONAL);
+                }
+            }
+            distance[i] = d;
+        }
+    }
+    for y in (0..height).rev() {
+        for x in (0..width).rev() {
+            let i = x + y * width;
+            let mut d = distance[i];
+            if x + 1 < width {
+                d = d.min(distance[i + 1] + 1.0);
+            }
+            if y + 1 < height {
+                d = d.min(distance[i + width] + 1.0);
+                if x + 1 < width {
+                    d = d.min(distance[i + width + 1] + DIAGONAL);
+                }
+                if x > 0 {
+                    d = d.min(distance[i + width - 1] + DIAGONAL);
+                }
+            }
+            distance[i] = d;
+        }
+    }
+    distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(dimensions: (u32, u32, u32)) -> Volume {
+        let (width, height, depth) = dimensions;
+        let count = (width * height * depth) as u16;
+        Volume {
+            dimensions,
+            spacing: (1.0, 1.0, 1.0),
+            values: (0..count).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn count(map: &LabelMap, label: u16) -> usize {
+        map.values.iter().filter(|&&value| value == label).count()
+    }
+
+    #[test]
+    fn brush_paints_a_disc_within_the_value_range() {
+        let volume = volume((9, 9, 3));
+        let mut map = LabelMap::new(volume.dimensions);
+        let center = Point3::new(4.5, 4.5, 1.5);
+        let changed = paint_brush(&mut map, &volume, center, 1.0, Some(2), 7, None);
+        assert_eq!(changed, Some((1, 1)));
+        // the center voxel and its four edge neighbors
+        assert_eq!(count(&map, 7), 5);
+
+        // only the voxels before the center in the middle slice
+        let center_value = volume.value(4, 4, 1);
+        let range = Some((0, center_value - 1));
+        paint_brush(&mut map, &volume, center, 1.0, Some(2), 0, range);
+        assert_eq!(count(&map, 7), 3);
+    }
+
+    #[test]
+    fn fill_stops_at_other_labels() {
+        let volume = volume((5, 5, 2));
+        let mut map = LabelMap::new(volume.dimensions);
+        // a wall at x = 2 in slice 0
+        for y in 0..5 {
+            let i = map.index(2, y, 0);
+            map.values[i] = 1;
+        }
+        let changed = fill_slice(&mut map, &volume, [0, 0, 0], 2, 2, None);
+        assert_eq!(changed, Some((0, 0)));
+        assert_eq!(count(&map, 2), 10);
+        assert_eq!(map.label(4, 4, 0), 0);
+        assert_eq!(map.label(0, 0, 1), 0);
+    }
+
+    #[test]
+    fn threshold_keeps_other_labels_unless_overwriting() {
+        let volume = volume((4, 4, 2));
+        let mut map = LabelMap::new(volume.dimensions);
+        map.values[10] = 1;
+        let changed = apply_threshold(&mut map, &volume, (8, 19), 2, false);
+        assert_eq!(changed, Some((0, 1)));
+        assert_eq!(count(&map, 2), 11);
+        assert_eq!(map.values[10], 1);
+
+        apply_threshold(&mut map, &volume, (8, 19), 2, true);
+        assert_eq!(count(&map, 2), 12);
+    }
+
+    #[test]
+    fn relabeling_splits_and_removes_components() {
+        let mut map = LabelMap::new((5, 1, 1));
+        map.values = vec![4, 4, 0, 4, 1];
+        let components = Components {
+            labels: vec![1, 1, 0, 2, 0],
+            ..Default::default()
+        };
+        let changed = relabel_components(&mut map, &components, 4, &[4, 0]);
+        assert_eq!(changed, Some((0, 0)));
+        assert_eq!(map.values, vec![4, 4, 0, 0, 1]);
+        relabel_components(&mut map, &components, 4, &[5]);
+        assert_eq!(map.values, vec![5, 5, 0, 0, 1]);
+    }
+
+    #[test]
+    fn interpolation_fills_between_painted_slices() {
+        let mut map = LabelMap::new((8, 8, 5));
+        for z in [0, 4] {
+            for y in 2..6 {
+                for x in 2..6 {
+                    let i = map.index(x, y, z);
+                    map.values[i] = 3;
+                }
+            }
+        }
+        let changed = interpolate_slices(&mut map, 3, 2);
+        assert_eq!(changed, Some((1, 3)));
+        assert_eq!(count(&map, 3), 5 * 16);
+    }
+}
@@ -0,0 +1,53 @@
+use cgmath::{InnerSpace, Point3};
+
+use crate::data::Volume;
+
+/// Values along the line from `start` to `end`, both in mm from the corner
+/// of the volume, as pairs of distance from `start` in mm and value.
+///
+/// Samples are trilinearly interpolated from the original values, half the
+/// smallest voxel spacing apart so the profile resolves single voxels.
+pub fn line_profile(volume: &Volume, start: Point3<f32>, end: Point3<f32>) -> Vec<[f32; 2]> {
+    let (x, y, z) = volume.spacing;
+    let step = x.min(y).min(z) / 2.0;
+    let length = (end - start).magnitude();
+    let count = (length / step).ceil().max(1.0) as usize;
+
+    (0..=count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            let position = start + (end - start) * t;
+            [
+                t * length,
+                volume.interpolate(volume.voxel_coordinates(position)),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_along_a_ramp() {
+        // values 0, 10, 20, 30 along x with 2 mm voxels
+        let volume = Volume {
+            dimensions: (4, 1, 1),
+            spacing: (2.0, 2.0, 2.0),
+            values: vec![0, 10, 20, 30].into(),
+            ..Default::default()
+        };
+        let profile = line_profile(
+            &volume,
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(7.0, 1.0, 1.0),
+        );
+        // one sample per mm between the first and the last voxel center
+        assert_eq!(profile.len(), 7);
+        for (i, [distance, value]) in profile.into_iter().enumerate() {
+            assert!((distance - i as f32).abs() < 1e-5);
+            assert!((value - 5.0 * i as f32).abs() < 1e-4);
+        }
+    }
+}
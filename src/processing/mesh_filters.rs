@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::data::Mesh;
+
+/// Simplifies the mesh by vertex clustering.
+///
+/// All vertices within a grid cell of `cell_size` are merged into their mean,
+/// triangles that collapse in the process are dropped.
+pub fn decimate(mesh: &Mesh, cell_size: f32) -> Mesh {
+    let mut clusters: HashMap<[i32; 3], u32> = HashMap::new();
+    let mut sums: Vec<([f32; 3], f32)> = Vec::new();
+
+    let remap: Vec<u32> = mesh
+        .positions
+        .iter()
+        .map(|position| {
+            let cell = position.map(|c| (c / cell_size).floor() as i32);
+            let cluster = *clusters.entry(cell).or_insert_with(|| {
+                sums.push(([0.0; 3], 0.0));
+                sums.len() as u32 - 1
+            });
+            let (sum, count) = &mut sums[cluster as usize];
+            for i in 0..3 {
+                sum[i] += position[i];
+            }
+            *count += 1.0;
+            cluster
+        })
+        .collect();
+
+    let mut decimated = Mesh {
+        positions: sums
+            .iter()
+            .map(|(sum, count)| sum.map(|c| c / count))
+            .collect(),
+        ..Default::default()
+    };
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| remap[triangle[i] as usize]);
+        if a != b && b != c && c != a {
+            decimated.indices.extend([a, b, c]);
+        }
+    }
+    decimated.compute_normals();
+
+    decimated
+}
+
+/// Laplacian smoothing, moves every vertex by `factor` towards the mean of
+/// its neighbours in each of the `iterations`. Normals are recomputed.
+pub fn smooth(mesh: &Mesh, iterations: u32, factor: f32) -> Mesh {
+    let mut neighbours: Vec<Vec<u32>> = vec![Vec::new(); mesh.positions.len()];
+    for triangle in mesh.indices.chunks_exact(3) {
+        for i in 0..3 {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            if !neighbours[a as usize].contains(&b) {
+                neighbours[a as usize].push(b);
+                neighbours[b as usize].push(a);
+            }
+        }
+    }
+
+    let mut smoothed = mesh.clone();
+    for _ in 0..iterations {
+        let positions = smoothed.positions.clone();
+        for (vertex, position) in smoothed.positions.iter_mut().enumerate() {
+            let adjacent = &neighbours[vertex];
+            if adjacent.is_empty() {
+                continue;
+            }
+            for i in 0..3 {
+                let mean = adjacent
+                    .iter()
+                    .map(|&neighbour| positions[neighbour as usize][i])
+                    .sum::<f32>()
+                    / adjacent.len() as f32;
+                position[i] += factor * (mean - position[i]);
+            }
+        }
+    }
+
+    smoothed.compute_normals();
+    smoothed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::processing::marching_cubes::{
+        marching_cubes,
+        tests::{assert_closed_sphere, sphere, ISO_VALUE},
+    };
+
+    fn mean_radius(mesh: &Mesh, size: u32) -> f32 {
+        let center = (size - 1) as f32 / 2.0;
+        mesh.positions
+            .iter()
+            .map(|position| {
+                position
+                    .map(|c| c - center)
+                    .iter()
+                    .map(|c| c * c)
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .sum::<f32>()
+            / mesh.positions.len() as f32
+    }
+
+    #[test]
+    fn decimation_merges_vertices_and_keeps_the_surface_closed() {
+        let mesh = marching_cubes(&sphere(32, 10.0), ISO_VALUE);
+        let decimated = decimate(&mesh, 2.0);
+        assert!(decimated.positions.len() < mesh.positions.len() / 2);
+        assert_eq!(decimated.normals.len(), decimated.positions.len());
+        assert!(decimated
+            .indices
+            .iter()
+            .all(|&i| (i as usize) < decimated.positions.len()));
+
+        // collapsed triangles only drop pairs of opposite edges, so every
+        // directed edge is still matched by its reverse
+        let mut balance: HashMap<(u32, u32), i32> = HashMap::new();
+        for triangle in decimated.indices.chunks_exact(3) {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                *balance.entry((a.min(b), a.max(b))).or_default() += if a < b { 1 } else { -1 };
+            }
+        }
+        assert!(balance.values().all(|&count| count == 0));
+        assert!((mean_radius(&decimated, 32) - 10.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn smoothing_keeps_the_topology() {
+        let mesh = marching_cubes(&sphere(24, 7.3), ISO_VALUE);
+        let smoothed = smooth(&mesh, 5, 0.5);
+        assert_eq!(smoothed.positions.len(), mesh.positions.len());
+        assert_eq!(smoothed.indices, mesh.indices);
+        assert_closed_sphere(&smoothed);
+        // Laplacian smoothing shrinks convex surfaces a little
+        let (before, after) = (mean_radius(&mesh, 24), mean_radius(&smoothed, 24));
+        assert!(after < before && after > before - 0.5, "{before} {after}");
+
+        assert_eq!(smooth(&mesh, 0, 0.5).positions, mesh.positions);
+    }
+}
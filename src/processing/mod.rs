@@ -0,0 +1,27 @@
+mod connected_components;
+mod curved_reformation;
+mod label_editing;
+mod line_profile;
+mod marching_cubes;
+// only used by the mesh export
+#[cfg(not(target_arch = "wasm32"))]
+mod mesh_filters;
+mod region_growing;
+mod roi_statistics;
+mod thresholds;
+mod value_statistics;
+
+pub use connected_components::{connected_components, Components};
+pub use curved_reformation::{curved_reformation, ReformationMode};
+pub use label_editing::{
+    apply_mask, apply_threshold, fill_slice, interpolate_slices, paint_brush, relabel_components,
+    ChangedSlices,
+};
+pub use line_profile::line_profile;
+pub use marching_cubes::marching_cubes;
+#[cfg(not(target_arch = "wasm32"))]
+pub use mesh_filters::{decimate, smooth};
+pub use region_growing::{grow_region, Connectivity, GrowingCriterion, RegionGrowing};
+pub use roi_statistics::roi_statistics;
+pub use thresholds::{li_threshold, otsu_thresholds, triangle_threshold};
+pub use value_statistics::ValueStatistics;
@@ -0,0 +1,263 @@
+use crate::data::Volume;
+
+/// Neighbors a region grows to, the voxels sharing a face, an edge or a
+/// corner with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Connectivity {
+    Faces,
+    Edges,
+    Corners,
+}
+
+impl Connectivity {
+    pub const ALL: [Self; 3] = [Self::Faces, Self::Edges, Self::Corners];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Faces => "6-connected",
+            Self::Edges => "18-connected",
+            Self::Corners => "26-connected",
+        }
+    }
+
+    /// Offsets of the neighbors of a voxel.
+    pub fn offsets(&self) -> Vec<[i32; 3]> {
+        let max_nonzero = match self {
+            Self::Faces => 1,
+            Self::Edges => 2,
+            Self::Corners => 3,
+        };
+        let mut offsets = Vec::new();
+        for z in -1..=1 {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    let nonzero = [x, y, z].iter().filter(|&&d| d != 0).count();
+                    if (1..=max_nonzero).contains(&nonzero) {
+                        offsets.push([x, y, z]);
+                    }
+                }
+            }
+        }
+        offsets
+    }
+}
+
+/// Calls `visit` for the neighbors of `voxel` inside `dimensions`.
+pub(crate) fn for_each_neighbor(
+    voxel: [u32; 3],
+    dimensions: [u32; 3],
+    offsets: &[[i32; 3]],
+    mut visit: impl FnMut([u32; 3]),
+) {
+    'offsets: for offset in offsets {
+        let mut neighbor = [0; 3];
+        for axis in 0..3 {
+            let index = voxel[axis] as i64 + offset[axis] as i64;
+            if index < 0 || index >= dimensions[axis] as i64 {
+                continue 'offsets;
+            }
+            neighbor[axis] = index as u32;
+        }
+        visit(neighbor);
+    }
+}
+
+/// Which values a region grows into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GrowingCriterion {
+    /// Values within `tolerance` of the seed value.
+    Tolerance,
+    /// Values within `multiplier` standard deviations of the mean of the
+    /// region, estimated first around the seed and then from the grown
+    /// region for `iterations` rounds (confidence connected).
+    Confidence,
+}
+
+impl GrowingCriterion {
+    pub const ALL: [Self; 2] = [Self::Tolerance, Self::Confidence];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Tolerance => "Tolerance",
+            Self::Confidence => "Confidence",
+        }
+    }
+}
+
+/// Settings of seeded region growing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegionGrowing {
+    pub criterion: GrowingCriterion,
+    pub connectivity: Connectivity,
+    pub tolerance: u16,
+    pub multiplier: f32,
+    pub iterations: u32,
+    /// Half size in voxels of the cube around the seed the first estimate
+    /// of the confidence criterion is taken from.
+    pub radius: u32,
+}
+
+impl Default for RegionGrowing {
+    fn default() -> Self {
+        Self {
+            criterion: GrowingCriterion::Tolerance,
+            connectivity: Connectivity::Faces,
+            tolerance: 100,
+            multiplier: 2.5,
+            iterations: 4,
+            radius: 1,
+        }
+    }
+}
+
+/// Voxels connected to `seed` with values the criterion of `settings`
+/// accepts, in the order of the volume values.
+pub fn grow_region(volume: &Volume, seed: [u32; 3], settings: &RegionGrowing) -> Vec<bool> {
+    let seed_value = volume.value(seed[0], seed[1], seed[2]);
+    match settings.criterion {
+        GrowingCriterion::Tolerance => {
+            let lower = seed_value.saturating_sub(settings.tolerance);
+            let upper = seed_value.saturating_add(settings.tolerance);
+            flood(volume, seed, settings.connectivity, (lower, upper))
+        }
+        GrowingCriterion::Confidence => {
+            let (width, height, depth) = volume.dimensions;
+            let dimensions = [width, height, depth];
+            let neighborhood: Vec<u16> = {
+                let radius = settings.radius;
+                let range = |axis: usize| {
+                    seed[axis].saturating_sub(radius)
+                        ..=(seed[axis] + radius).min(dimensions[axis] - 1)
+                };
+                let mut values = Vec::new();
+                for z in range(2) {
+                    for y in range(1) {
+                        for x in range(0) {
+                            values.push(volume.value(x, y, z));
+                        }
+                    }
+                }
+                values
+            };
+            let mut range = confidence_range(neighborhood.into_iter(), settings.multiplier);
+            let mut region = flood(volume, seed, settings.connectivity, range);
+            for _ in 0..settings.iterations {
+                let values = region
+                    .iter()
+                    .zip(volume.values.iter())
+                    .filter(|(&inside, _)| inside)
+                    .map(|(_, &value)| value);
+                let next = confidence_range(values, settings.multiplier);
+                if next == range {
+                    break;
+                }
+                range = next;
+                region = flood(volume, seed, settings.connectivity, range);
+            }
+            region
+        }
+    }
+}
+
+/// Values within `multiplier` standard deviations of the mean of `values`,
+/// at least the rounded mean itself.
+fn confidence_range(values: impl Iterator<Item = u16>, multiplier: f32) -> (u16, u16) {
+    let (mut count, mut sum, mut sum_of_squares) = (0.0f64, 0.0, 0.0);
+    for value in values {
+        let value = value as f64;
+        count += 1.0;
+        sum += value;
+        sum_of_squares += value * value;
+    }
+    let mean = sum / count.max(1.0);
+    let std = (sum_of_squares / count.max(1.0) - mean * mean)
+        .max(0.0)
+        .sqrt();
+    let spread = multiplier as f64 * std;
+    let clamp = |value: f64| value.clamp(0.0, u16::MAX as f64) as u16;
+    (
+        clamp((mean - spread).ceil().min(mean.round())),
+        clamp((mean + spread).floor().max(mean.round())),
+    )
+}
+
+/// Voxels connected to `seed` with values in `range`, the seed always.
+fn flood(
+    volume: &Volume,
+    seed: [u32; 3],
+    connectivity: Connectivity,
+    (lower, upper): (u16, u16),
+) -> Vec<bool> {
+    let (width, height, depth) = volume.dimensions;
+    let dimensions = [width, height, depth];
+    let offsets = connectivity.offsets();
+    let mut region = vec![false; volume.values.len()];
+    region[volume.index(seed[0], seed[1], seed[2])] = true;
+    let mut stack = vec![seed];
+    while let Some(voxel) = stack.pop() {
+        for_each_neighbor(voxel, dimensions, &offsets, |[x, y, z]| {
+            let i = volume.index(x, y, z);
+            if !region[i] && (lower..=upper).contains(&volume.values[i]) {
+                region[i] = true;
+                stack.push([x, y, z]);
+            }
+        });
+    }
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bright 3x3x3 cube in a dark 7x7x7 volume, with a bright voxel
+    /// touching a corner of the cube only.
+    fn cube() -> Volume {
+        let index = |x: u32, y: u32, z: u32| (x + 7 * (y + 7 * z)) as usize;
+        let mut values = vec![100; 343];
+        for z in 2..5 {
+            for y in 2..5 {
+                for x in 2..5 {
+                    values[index(x, y, z)] = 1000 + (x + y + z) as u16;
+                }
+            }
+        }
+        values[index(5, 5, 5)] = 1000;
+        Volume {
+            dimensions: (7, 7, 7),
+            spacing: (1.0, 1.0, 1.0),
+            values: values.into(),
+            ..Default::default()
+        }
+    }
+
+    fn count(region: &[bool]) -> usize {
+        region.iter().filter(|&&inside| inside).count()
+    }
+
+    #[test]
+    fn tolerance_grows_over_the_chosen_neighbors() {
+        let volume = cube();
+        let mut settings = RegionGrowing {
+            tolerance: 20,
+            ..Default::default()
+        };
+        assert_eq!(count(&grow_region(&volume, [3, 3, 3], &settings)), 27);
+        settings.connectivity = Connectivity::Corners;
+        assert_eq!(count(&grow_region(&volume, [3, 3, 3], &settings)), 28);
+        settings.tolerance = 0;
+        assert_eq!(count(&grow_region(&volume, [3, 3, 3], &settings)), 7);
+    }
+
+    #[test]
+    fn confidence_grows_over_the_object() {
+        let volume = cube();
+        let settings = RegionGrowing {
+            criterion: GrowingCriterion::Confidence,
+            ..Default::default()
+        };
+        assert_eq!(count(&grow_region(&volume, [3, 3, 3], &settings)), 27);
+        assert_eq!(count(&grow_region(&volume, [0, 0, 0], &settings)), 343 - 28);
+        assert_eq!(Connectivity::Edges.offsets().len(), 18);
+    }
+}
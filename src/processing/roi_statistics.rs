@@ -0,0 +1,83 @@
+use crate::data::{Roi, RoiStatistics, SliceOrientation, Volume};
+
+/// Statistics of the values of `volume` inside `roi`, `None` if the region
+/// covers no voxel.
+///
+/// Uses the original values rather than the half precision texture.
+pub fn roi_statistics(volume: &Volume, roi: &Roi) -> Option<RoiStatistics> {
+    let mut values: Vec<f32> = roi
+        .voxels(volume.dimensions, volume.spacing)
+        .into_iter()
+        .map(|[x, y, z]| volume.value(x, y, z) as f32)
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f32::total_cmp);
+
+    let count = values.len();
+    let mean = (values.iter().map(|&value| value as f64).sum::<f64>() / count as f64) as f32;
+    let variance = values
+        .iter()
+        .map(|&value| (value as f64 - mean as f64).powi(2))
+        .sum::<f64>()
+        / count as f64;
+    let (x, y, z) = volume.spacing;
+    let voxel_size = if roi.shape.is_3d() {
+        x * y * z
+    } else {
+        let spacing = [x, y, z];
+        let [u, v, _] = SliceOrientation::from_normal_axis(roi.normal_axis).axes();
+        spacing[u] * spacing[v]
+    };
+
+    Some(RoiStatistics {
+        count,
+        size: count as f32 * voxel_size,
+        mean,
+        std: variance.sqrt() as f32,
+        min: values[0],
+        max: values[count - 1],
+        median: percentile(&values, 50.0),
+        percentiles: RoiStatistics::PERCENTILES.map(|p| percentile(&values, p)),
+    })
+}
+
+/// Linear interpolation between the closest ranks of the sorted `values`.
+fn percentile(values: &[f32], percent: f32) -> f32 {
+    let rank = percent / 100.0 * (values.len() - 1) as f32;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    values[lower] + (values[upper] - values[lower]) * (rank - lower as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Point3;
+
+    use super::*;
+    use crate::data::RoiShape;
+
+    #[test]
+    fn statistics_of_a_row() {
+        // values 0..10 along x, spacing 0.5 x 2 mm in the axial plane
+        let volume = Volume {
+            dimensions: (10, 1, 1),
+            spacing: (0.5, 2.0, 3.0),
+            values: (0..10).collect(),
+            ..Default::default()
+        };
+        let roi = Roi {
+            shape: RoiShape::Rectangle,
+            normal_axis: 2,
+            points: vec![Point3::new(0.0, 0.0, 1.0), Point3::new(5.0, 2.0, 1.0)],
+        };
+        let statistics = roi_statistics(&volume, &roi).unwrap();
+        assert_eq!(statistics.count, 10);
+        assert_eq!(statistics.size, 10.0);
+        assert_eq!(statistics.mean, 4.5);
+        assert!((statistics.std - 8.25f32.sqrt()).abs() < 1e-5);
+        assert_eq!((statistics.min, statistics.max), (0.0, 9.0));
+        assert_eq!(statistics.median, 4.5);
+        assert_eq!(statistics.percentiles[1], 2.25);
+    }
+}
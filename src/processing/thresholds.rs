@@ -0,0 +1,182 @@
+use crate::data::Histogram;
+
+/// Value at the lower edge of `bin`.
+fn bin_start(histogram: &Histogram, bin: usize) -> f32 {
+    histogram.min + bin as f32 * histogram.bin_width()
+}
+
+/// Thresholds splitting the histogram into `classes` classes with the
+/// largest variance between them (Otsu): the first values of the classes
+/// after the first, ascending.
+///
+/// Finds the classes by dynamic programming over the bins, in about
+/// `classes` times the squared bin count steps.
+pub fn otsu_thresholds(histogram: &Histogram, classes: usize) -> Vec<f32> {
+    let bins = &histogram.bins;
+    let bin_count = bins.len();
+    let classes = classes.clamp(2, bin_count.max(2));
+    // prefix sums of the counts and of the counts times the bin index
+    let mut counts = vec![0.0; bin_count + 1];
+    let mut moments = vec![0.0; bin_count + 1];
+    for (bin, &count) in bins.iter().enumerate() {
+        counts[bin + 1] = counts[bin] + count as f64;
+        moments[bin + 1] = moments[bin] + count as f64 * bin as f64;
+    }
+    // the between-class variance grows with the sum of count * mean² of the classes
+    let class_score = |first: usize, end: usize| {
+        let count = counts[end] - counts[first];
+        if count > 0.0 {
+            (moments[end] - moments[first]).powi(2) / count
+        } else {
+            0.0
+        }
+    };
+
+    // best[k][end] is the largest score of bins ..end split into k + 1
+    // classes, starts[k][end] the first bin of the last of them
+    let mut best = vec![vec![f64::NEG_INFINITY; bin_count + 1]; classes];
+    let mut starts = vec![vec![0; bin_count + 1]; classes];
+    for (end, score) in best[0].iter_mut().enumerate().skip(1) {
+        *score = class_score(0, end);
+    }
+    for k in 1..classes {
+        for end in k + 1..=bin_count {
+            for first in k..end {
+                let score = best[k - 1][first] + class_score(first, end);
+                if score > best[k][end] {
+                    best[k][end] = score;
+                    starts[k][end] = first;
+                }
+            }
+        }
+    }
+
+    // follow the class starts back from the last bin
+    let mut thresholds = vec![0.0; classes - 1];
+    let mut end = bin_count;
+    for k in (1..classes).rev() {
+        end = starts[k][end];
+        thresholds[k - 1] = bin_start(histogram, end);
+    }
+    thresholds
+}
+
+/// Threshold at the point of the histogram farthest below the line from its
+/// peak to the end of its longer tail (Zack's triangle method), suited to
+/// a small bright or dark object on a large background. Returns the first
+/// value of the brighter class.
+pub fn triangle_threshold(histogram: &Histogram) -> f32 {
+    let bins = &histogram.bins;
+    let occupied = || bins.iter().enumerate().filter(|(_, &count)| count > 0);
+    let (Some((first, _)), Some((last, _))) = (occupied().next(), occupied().next_back()) else {
+        return histogram.min;
+    };
+    let peak = (first..=last).max_by_key(|&bin| bins[bin]).unwrap_or(first);
+    let tail_is_bright = last - peak >= peak - first;
+    let end = if tail_is_bright { last } else { first };
+
+    let (peak_x, peak_y) = (peak as f64, bins[peak] as f64);
+    let (end_x, end_y) = (end as f64, bins[end] as f64);
+    // unnormalized distance below the line, the normalization is the same for all bins
+    let distance = |bin: usize| {
+        let (x, y) = (bin as f64, bins[bin] as f64);
+        ((end_y - peak_y) * (x - peak_x) - (end_x - peak_x) * (y - peak_y)).abs()
+    };
+    let between = if tail_is_bright {
+        peak..=end
+    } else {
+        end..=peak
+    };
+    let split = between
+        .max_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or(peak);
+    if tail_is_bright {
+        bin_start(histogram, split + 1)
+    } else {
+        bin_start(histogram, split)
+    }
+}
+
+/// Threshold minimizing the cross entropy between the volume and its two
+/// class means (Li and Tam), found iteratively starting from the mean.
+/// Values above it belong to the brighter class.
+pub fn li_threshold(histogram: &Histogram) -> f32 {
+    const MAX_ITERATIONS: usize = 1000;
+
+    let width = histogram.bin_width() as f64;
+    // bin centers relative to the minimum, all positive for the logarithms
+    let centers: Vec<f64> = (0..histogram.bins.len())
+        .map(|bin| (bin as f64 + 0.5) * width)
+        .collect();
+    let mean_of = |below: bool, threshold: f64| {
+        let (mut count, mut sum) = (0.0, 0.0);
+        for (&center, &bin_count) in centers.iter().zip(&histogram.bins) {
+            if (center <= threshold) == below {
+                count += bin_count as f64;
+                sum += bin_count as f64 * center;
+            }
+        }
+        (count > 0.0).then(|| sum / count)
+    };
+
+    let Some(mut threshold) = mean_of(true, f64::INFINITY) else {
+        return histogram.min;
+    };
+    for _ in 0..MAX_ITERATIONS {
+        let (Some(background), Some(foreground)) =
+            (mean_of(true, threshold), mean_of(false, threshold))
+        else {
+            break;
+        };
+        let next = (background - foreground) / (background.ln() - foreground.ln());
+        let converged = (next - threshold).abs() < width / 2.0;
+        threshold = next;
+        if converged {
+            break;
+        }
+    }
+    histogram.min + threshold as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two or more clusters of values with `count` voxels around each center.
+    fn clusters(centers: &[(f32, usize)]) -> Histogram {
+        let values = centers
+            .iter()
+            .flat_map(|&(center, count)| (0..count).map(move |i| center + (i % 5) as f32 - 2.0));
+        Histogram::from_values(values, 100, 0.0, 100.0)
+    }
+
+    #[test]
+    fn otsu_splits_between_clusters() {
+        let histogram = clusters(&[(20.0, 500), (70.0, 300)]);
+        let threshold = otsu_thresholds(&histogram, 2)[0];
+        assert!((23.0..=68.0).contains(&threshold), "{threshold}");
+
+        let histogram = clusters(&[(10.0, 200), (50.0, 200), (90.0, 200)]);
+        let thresholds = otsu_thresholds(&histogram, 3);
+        assert_eq!(thresholds.len(), 2);
+        assert!((13.0..=48.0).contains(&thresholds[0]), "{thresholds:?}");
+        assert!((53.0..=88.0).contains(&thresholds[1]), "{thresholds:?}");
+
+        let histogram = clusters(&[(10.0, 100), (35.0, 300), (60.0, 200), (90.0, 100)]);
+        let thresholds = otsu_thresholds(&histogram, 4);
+        assert_eq!(thresholds.len(), 3);
+        let expected = [13.0..=33.0, 38.0..=58.0, 63.0..=88.0];
+        for (threshold, range) in thresholds.iter().zip(expected) {
+            assert!(range.contains(threshold), "{thresholds:?}");
+        }
+    }
+
+    #[test]
+    fn li_and_triangle_separate_a_bright_object() {
+        let histogram = clusters(&[(20.0, 2000), (80.0, 100)]);
+        let li = li_threshold(&histogram);
+        assert!((23.0..=78.0).contains(&li), "{li}");
+        let triangle = triangle_threshold(&histogram);
+        assert!((23.0..=78.0).contains(&triangle), "{triangle}");
+    }
+}
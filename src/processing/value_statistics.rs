@@ -0,0 +1,127 @@
+use crate::data::Histogram;
+
+/// Distribution of 16 bit values, kept as one count per possible value so
+/// histograms with any number of bins follow without another pass over the data.
+#[derive(Clone)]
+pub struct ValueStatistics {
+    counts: Vec<u64>,
+    pub count: u64,
+    pub zeros: u64,
+    pub min: u16,
+    pub max: u16,
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std: f64,
+    /// Values at `ValueStatistics::PERCENTILES`.
+    pub percentiles: [f32; 7],
+}
+
+impl ValueStatistics {
+    pub const PERCENTILES: [f32; 7] = [1.0, 5.0, 25.0, 50.0, 75.0, 95.0, 99.0];
+
+    pub fn new(values: impl Iterator<Item = u16>) -> Self {
+        let mut counts = vec![0_u64; u16::MAX as usize + 1];
+        for value in values {
+            counts[value as usize] += 1;
+        }
+
+        let count: u64 = counts.iter().sum();
+        let occupied = || counts.iter().enumerate().filter(|(_, &c)| c > 0);
+        let min = occupied().next().map_or(0, |(value, _)| value as u16);
+        let max = occupied().next_back().map_or(0, |(value, _)| value as u16);
+        let (sum, sum_of_squares) = occupied().fold((0.0, 0.0), |(sum, squares), (value, &c)| {
+            let (value, c) = (value as f64, c as f64);
+            (sum + value * c, squares + value * value * c)
+        });
+        let mean = if count > 0 { sum / count as f64 } else { 0.0 };
+        let variance = if count > 0 {
+            (sum_of_squares / count as f64 - mean * mean).max(0.0)
+        } else {
+            0.0
+        };
+
+        let mut statistics = Self {
+            zeros: counts[0],
+            counts,
+            count,
+            min,
+            max,
+            mean,
+            std: variance.sqrt(),
+            percentiles: [0.0; 7],
+        };
+        statistics.percentiles = Self::PERCENTILES.map(|p| statistics.percentile(p));
+        statistics
+    }
+
+    /// Linear interpolation between the closest ranks like the ROI statistics.
+    pub fn percentile(&self, percent: f32) -> f32 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = percent as f64 / 100.0 * (self.count - 1) as f64;
+        let lower = self.value_at_rank(rank.floor() as u64) as f64;
+        let upper = self.value_at_rank(rank.ceil() as u64) as f64;
+        (lower + (upper - lower) * rank.fract()) as f32
+    }
+
+    /// Value at position `rank` of the sorted values.
+    fn value_at_rank(&self, rank: u64) -> u16 {
+        let mut seen = 0;
+        for (value, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen > rank {
+                return value as u16;
+            }
+        }
+        self.max
+    }
+
+    /// Number of values in `[lower, upper]`.
+    pub fn count_between(&self, lower: u16, upper: u16) -> u64 {
+        if lower > upper {
+            return 0;
+        }
+        self.counts[lower as usize..=upper as usize].iter().sum()
+    }
+
+    /// Histogram with `bin_count` bins over `[min, max]`.
+    pub fn histogram(&self, bin_count: usize) -> Histogram {
+        let mut histogram = Histogram {
+            bins: vec![0; bin_count.max(1)],
+            min: self.min as f32,
+            max: self.max as f32,
+        };
+        for value in self.min..=self.max {
+            let count = self.counts[value as usize];
+            if count > 0 {
+                if let Some(bin) = histogram.bin(value as f32) {
+                    histogram.bins[bin] += count;
+                }
+            }
+        }
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_of_a_ramp() {
+        let statistics = ValueStatistics::new((0..=100).chain([0, 0]));
+        assert_eq!(statistics.count, 103);
+        assert_eq!(statistics.zeros, 3);
+        assert_eq!((statistics.min, statistics.max), (0, 100));
+        assert!((statistics.mean - 5050.0 / 103.0).abs() < 1e-9);
+        // ranks 0 to 2 hold the zeros, the median is the value at rank 51
+        assert_eq!(statistics.percentile(50.0), 49.0);
+        assert_eq!(statistics.percentile(100.0), 100.0);
+        assert_eq!(statistics.count_between(0, 10), 13);
+
+        let histogram = statistics.histogram(4);
+        assert_eq!(histogram.bins.iter().sum::<u64>(), 103);
+        assert_eq!(histogram.bins[0], 27);
+    }
+}
@@ -1,10 +1,20 @@
+use cgmath::InnerSpace;
 #[cfg(target_arch = "wasm32")]
 use core::any::Any;
 use egui::{Id, Margin};
 use egui_dock::{DockArea, NodeIndex, Style, Tree};
-use std::str::FromStr;
-
-use crate::{apps::SliceRenderer, io::VolumeDataFileType};
+use std::{cell::RefCell, rc::Rc, str::FromStr, sync::Arc};
+
+use crate::{
+    apps::{
+        AnnotationsPanel, CurvedPath, CurvedReformationView, HistogramPanel, LabelOverlay,
+        LabelsPanel, LineProfilePanel, MeasurementsPanel, MeshRenderer, RegionsPanel, RoiPanel,
+        SharedSliceState, SliceAnnotations, SliceRenderer, ThresholdPanel, TransferFunctionEditor,
+        VolumeRenderer,
+    },
+    data::{CoordinateSystem, TransferFunction},
+    io::VolumeDataFileType,
+};
 
 // Docking GUI
 
@@ -12,6 +22,7 @@ trait TabUi {
     fn ui(&mut self, ui: &mut egui::Ui);
     fn title(&self) -> String;
     fn show_settings_oberlay(&mut self, _show: bool) {}
+    fn show_annotations(&mut self, _annotations: SliceAnnotations) {}
 }
 struct SliceViewAxial {
     slice_renderer: Option<SliceRenderer>,
@@ -20,8 +31,17 @@ impl SliceViewAxial {
     fn new(
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
     ) -> Self {
-        let slice_renderer = crate::apps::SliceRenderer::axial(wgpu_render_state, volume_texture);
+        let slice_renderer = crate::apps::SliceRenderer::axial(
+            wgpu_render_state,
+            volume_texture,
+            lookup_table,
+            volume,
+            shared,
+        );
 
         Self { slice_renderer }
     }
@@ -37,6 +57,9 @@ impl TabUi for SliceViewAxial {
     fn show_settings_oberlay(&mut self, show: bool) {
         self.slice_renderer.as_mut().unwrap().show_settings_oberlay = show;
     }
+    fn show_annotations(&mut self, annotations: SliceAnnotations) {
+        self.slice_renderer.as_mut().unwrap().annotations = annotations;
+    }
 }
 struct SliceViewCoronal {
     slice_renderer: Option<SliceRenderer>,
@@ -45,8 +68,17 @@ impl SliceViewCoronal {
     fn new(
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
     ) -> Self {
-        let slice_renderer = crate::apps::SliceRenderer::coronal(wgpu_render_state, volume_texture);
+        let slice_renderer = crate::apps::SliceRenderer::coronal(
+            wgpu_render_state,
+            volume_texture,
+            lookup_table,
+            volume,
+            shared,
+        );
 
         Self { slice_renderer }
     }
@@ -62,6 +94,9 @@ impl TabUi for SliceViewCoronal {
     fn show_settings_oberlay(&mut self, show: bool) {
         self.slice_renderer.as_mut().unwrap().show_settings_oberlay = show;
     }
+    fn show_annotations(&mut self, annotations: SliceAnnotations) {
+        self.slice_renderer.as_mut().unwrap().annotations = annotations;
+    }
 }
 struct SliceViewSaggital {
     slice_renderer: Option<SliceRenderer>,
@@ -70,9 +105,17 @@ impl SliceViewSaggital {
     fn new(
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
     ) -> Self {
-        let slice_renderer =
-            crate::apps::SliceRenderer::saggital(wgpu_render_state, volume_texture);
+        let slice_renderer = crate::apps::SliceRenderer::saggital(
+            wgpu_render_state,
+            volume_texture,
+            lookup_table,
+            volume,
+            shared,
+        );
 
         Self { slice_renderer }
     }
@@ -88,6 +131,290 @@ impl TabUi for SliceViewSaggital {
     fn show_settings_oberlay(&mut self, show: bool) {
         self.slice_renderer.as_mut().unwrap().show_settings_oberlay = show;
     }
+    fn show_annotations(&mut self, annotations: SliceAnnotations) {
+        self.slice_renderer.as_mut().unwrap().annotations = annotations;
+    }
+}
+struct SliceViewOblique {
+    slice_renderer: Option<SliceRenderer>,
+}
+impl SliceViewOblique {
+    fn new(
+        wgpu_render_state: &eframe::egui_wgpu::RenderState,
+        volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        let slice_renderer = crate::apps::SliceRenderer::oblique(
+            wgpu_render_state,
+            volume_texture,
+            lookup_table,
+            volume,
+            shared,
+        );
+
+        Self { slice_renderer }
+    }
+}
+impl TabUi for SliceViewOblique {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let renderer = self.slice_renderer.as_mut().unwrap();
+        renderer.custom_painting(ui);
+    }
+    fn title(&self) -> String {
+        "Oblique".to_owned()
+    }
+    fn show_settings_oberlay(&mut self, show: bool) {
+        self.slice_renderer.as_mut().unwrap().show_settings_oberlay = show;
+    }
+    fn show_annotations(&mut self, annotations: SliceAnnotations) {
+        self.slice_renderer.as_mut().unwrap().annotations = annotations;
+    }
+}
+struct VolumeView {
+    volume_renderer: Option<VolumeRenderer>,
+}
+impl VolumeView {
+    fn new(
+        wgpu_render_state: &eframe::egui_wgpu::RenderState,
+        volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: &crate::data::Volume,
+    ) -> Self {
+        let volume_renderer = crate::apps::VolumeRenderer::new(
+            wgpu_render_state,
+            volume_texture,
+            lookup_table,
+            volume,
+        );
+
+        Self { volume_renderer }
+    }
+}
+impl TabUi for VolumeView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let renderer = self.volume_renderer.as_mut().unwrap();
+        renderer.custom_painting(ui);
+    }
+    fn title(&self) -> String {
+        "3D".to_owned()
+    }
+    fn show_settings_oberlay(&mut self, show: bool) {
+        self.volume_renderer.as_mut().unwrap().show_settings_oberlay = show;
+    }
+}
+struct MeshView {
+    mesh_renderer: Option<MeshRenderer>,
+}
+impl MeshView {
+    fn new(
+        wgpu_render_state: &eframe::egui_wgpu::RenderState,
+        volume: Rc<crate::data::Volume>,
+    ) -> Self {
+        let mesh_renderer = crate::apps::MeshRenderer::new(wgpu_render_state, volume);
+
+        Self { mesh_renderer }
+    }
+}
+impl TabUi for MeshView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let renderer = self.mesh_renderer.as_mut().unwrap();
+        renderer.custom_painting(ui);
+    }
+    fn title(&self) -> String {
+        "Surface".to_owned()
+    }
+    fn show_settings_oberlay(&mut self, show: bool) {
+        self.mesh_renderer.as_mut().unwrap().show_settings_oberlay = show;
+    }
+}
+struct CurvedView {
+    view: CurvedReformationView,
+}
+impl CurvedView {
+    fn new(
+        volume: Rc<crate::data::Volume>,
+        curved_path: Rc<RefCell<CurvedPath>>,
+        transfer_function: Rc<RefCell<TransferFunction>>,
+    ) -> Self {
+        Self {
+            view: CurvedReformationView::new(volume, curved_path, transfer_function),
+        }
+    }
+}
+impl TabUi for CurvedView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.view.custom_painting(ui);
+    }
+    fn title(&self) -> String {
+        "Curved".to_owned()
+    }
+    fn show_settings_oberlay(&mut self, show: bool) {
+        self.view.show_settings_oberlay = show;
+    }
+}
+struct TransferFunctionView {
+    editor: TransferFunctionEditor,
+}
+impl TransferFunctionView {
+    fn new(transfer_function: Rc<RefCell<TransferFunction>>, volume: &crate::data::Volume) -> Self {
+        Self {
+            editor: TransferFunctionEditor::new(transfer_function, volume),
+        }
+    }
+}
+impl TabUi for TransferFunctionView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.editor.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Transfer Function".to_owned()
+    }
+}
+struct MeasurementsView {
+    panel: MeasurementsPanel,
+}
+impl MeasurementsView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: MeasurementsPanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for MeasurementsView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Measurements".to_owned()
+    }
+}
+struct HistogramView {
+    panel: HistogramPanel,
+}
+impl HistogramView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: HistogramPanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for HistogramView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Histogram".to_owned()
+    }
+}
+struct LineProfileView {
+    panel: LineProfilePanel,
+}
+impl LineProfileView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: LineProfilePanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for LineProfileView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Line Profile".to_owned()
+    }
+}
+struct AnnotationsView {
+    panel: AnnotationsPanel,
+}
+impl AnnotationsView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: AnnotationsPanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for AnnotationsView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Annotations".to_owned()
+    }
+}
+struct LabelsView {
+    panel: LabelsPanel,
+}
+impl LabelsView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: LabelsPanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for LabelsView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Labels".to_owned()
+    }
+}
+struct RegionsView {
+    panel: RegionsPanel,
+}
+impl RegionsView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: RegionsPanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for RegionsView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Regions".to_owned()
+    }
+}
+struct ThresholdView {
+    panel: ThresholdPanel,
+}
+impl ThresholdView {
+    fn new(volume: Rc<crate::data::Volume>, shared: SharedSliceState) -> Self {
+        Self {
+            panel: ThresholdPanel::new(volume, shared),
+        }
+    }
+}
+impl TabUi for ThresholdView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "Threshold".to_owned()
+    }
+}
+struct RoiView {
+    panel: RoiPanel,
+}
+impl RoiView {
+    fn new(shared: SharedSliceState) -> Self {
+        Self {
+            panel: RoiPanel::new(shared),
+        }
+    }
+}
+impl TabUi for RoiView {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        self.panel.ui(ui);
+    }
+    fn title(&self) -> String {
+        "ROI Statistics".to_owned()
+    }
 }
 
 struct Tab {
@@ -99,10 +426,19 @@ impl Tab {
         node_index: usize,
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
     ) -> Self {
         Self {
             node: NodeIndex(node_index),
-            content: Box::new(SliceViewAxial::new(wgpu_render_state, volume_texture)),
+            content: Box::new(SliceViewAxial::new(
+                wgpu_render_state,
+                volume_texture,
+                lookup_table,
+                volume,
+                shared,
+            )),
         }
     }
 
@@ -110,10 +446,19 @@ impl Tab {
         node_index: usize,
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
     ) -> Self {
         Self {
             node: NodeIndex(node_index),
-            content: Box::new(SliceViewCoronal::new(wgpu_render_state, volume_texture)),
+            content: Box::new(SliceViewCoronal::new(
+                wgpu_render_state,
+                volume_texture,
+                lookup_table,
+                volume,
+                shared,
+            )),
         }
     }
 
@@ -121,10 +466,175 @@ impl Tab {
         node_index: usize,
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
     ) -> Self {
         Self {
             node: NodeIndex(node_index),
-            content: Box::new(SliceViewSaggital::new(wgpu_render_state, volume_texture)),
+            content: Box::new(SliceViewSaggital::new(
+                wgpu_render_state,
+                volume_texture,
+                lookup_table,
+                volume,
+                shared,
+            )),
+        }
+    }
+
+    fn slice_view_oblique(
+        node_index: usize,
+        wgpu_render_state: &eframe::egui_wgpu::RenderState,
+        volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(SliceViewOblique::new(
+                wgpu_render_state,
+                volume_texture,
+                lookup_table,
+                volume,
+                shared,
+            )),
+        }
+    }
+
+    fn volume_view(
+        node_index: usize,
+        wgpu_render_state: &eframe::egui_wgpu::RenderState,
+        volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: &crate::data::Volume,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(VolumeView::new(
+                wgpu_render_state,
+                volume_texture,
+                lookup_table,
+                volume,
+            )),
+        }
+    }
+
+    fn mesh_view(
+        node_index: usize,
+        wgpu_render_state: &eframe::egui_wgpu::RenderState,
+        volume: Rc<crate::data::Volume>,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(MeshView::new(wgpu_render_state, volume)),
+        }
+    }
+
+    fn curved_view(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        curved_path: Rc<RefCell<CurvedPath>>,
+        transfer_function: Rc<RefCell<TransferFunction>>,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(CurvedView::new(volume, curved_path, transfer_function)),
+        }
+    }
+
+    fn transfer_function(
+        node_index: usize,
+        transfer_function: Rc<RefCell<TransferFunction>>,
+        volume: &crate::data::Volume,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(TransferFunctionView::new(transfer_function, volume)),
+        }
+    }
+
+    fn measurements(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(MeasurementsView::new(volume, shared)),
+        }
+    }
+
+    fn histogram(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(HistogramView::new(volume, shared)),
+        }
+    }
+
+    fn line_profile(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(LineProfileView::new(volume, shared)),
+        }
+    }
+
+    fn annotations(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(AnnotationsView::new(volume, shared)),
+        }
+    }
+
+    fn labels(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(LabelsView::new(volume, shared)),
+        }
+    }
+
+    fn threshold(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(ThresholdView::new(volume, shared)),
+        }
+    }
+
+    fn regions(
+        node_index: usize,
+        volume: Rc<crate::data::Volume>,
+        shared: SharedSliceState,
+    ) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(RegionsView::new(volume, shared)),
+        }
+    }
+
+    fn rois(node_index: usize, shared: SharedSliceState) -> Self {
+        Self {
+            node: NodeIndex(node_index),
+            content: Box::new(RoiView::new(shared)),
         }
     }
 
@@ -141,6 +651,10 @@ struct TabViewer<'a> {
     added_nodes: &'a mut Vec<Tab>,
     wgpu_render_state: &'a eframe::egui_wgpu::RenderState,
     volume_texture: &'a crate::apps::Texture,
+    volume: &'a Rc<crate::data::Volume>,
+    transfer_function: &'a Rc<RefCell<TransferFunction>>,
+    lookup_table: &'a crate::apps::LookupTable,
+    shared: &'a SharedSliceState,
 }
 
 impl egui_dock::TabViewer for TabViewer<'_> {
@@ -167,6 +681,9 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                 node.0,
                 self.wgpu_render_state,
                 self.volume_texture,
+                self.lookup_table,
+                self.volume.clone(),
+                self.shared.clone(),
             ));
         }
 
@@ -175,6 +692,9 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                 node.0,
                 self.wgpu_render_state,
                 self.volume_texture,
+                self.lookup_table,
+                self.volume.clone(),
+                self.shared.clone(),
             ));
         }
 
@@ -183,8 +703,120 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                 node.0,
                 self.wgpu_render_state,
                 self.volume_texture,
+                self.lookup_table,
+                self.volume.clone(),
+                self.shared.clone(),
             ));
         }
+
+        if ui.button("Oblique").clicked() {
+            self.added_nodes.push(Tab::slice_view_oblique(
+                node.0,
+                self.wgpu_render_state,
+                self.volume_texture,
+                self.lookup_table,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Curved").clicked() {
+            self.added_nodes.push(Tab::curved_view(
+                node.0,
+                self.volume.clone(),
+                self.shared.curved_path.clone(),
+                self.transfer_function.clone(),
+            ));
+        }
+
+        if ui.button("3D").clicked() {
+            self.added_nodes.push(Tab::volume_view(
+                node.0,
+                self.wgpu_render_state,
+                self.volume_texture,
+                self.lookup_table,
+                self.volume,
+            ));
+        }
+
+        if ui.button("Surface").clicked() {
+            self.added_nodes.push(Tab::mesh_view(
+                node.0,
+                self.wgpu_render_state,
+                self.volume.clone(),
+            ));
+        }
+
+        ui.separator();
+
+        if ui.button("Transfer Function").clicked() {
+            self.added_nodes.push(Tab::transfer_function(
+                node.0,
+                self.transfer_function.clone(),
+                self.volume,
+            ));
+        }
+
+        if ui.button("Measurements").clicked() {
+            self.added_nodes.push(Tab::measurements(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Histogram").clicked() {
+            self.added_nodes.push(Tab::histogram(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Line Profile").clicked() {
+            self.added_nodes.push(Tab::line_profile(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Annotations").clicked() {
+            self.added_nodes.push(Tab::annotations(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Labels").clicked() {
+            self.added_nodes.push(Tab::labels(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Threshold").clicked() {
+            self.added_nodes.push(Tab::threshold(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("Regions").clicked() {
+            self.added_nodes.push(Tab::regions(
+                node.0,
+                self.volume.clone(),
+                self.shared.clone(),
+            ));
+        }
+
+        if ui.button("ROI Statistics").clicked() {
+            self.added_nodes
+                .push(Tab::rois(node.0, self.shared.clone()));
+        }
     }
 }
 
@@ -197,6 +829,7 @@ pub struct State {
     #[cfg_attr(feature = "serde", serde(skip))]
     importer: super::io::Importer,
     hide_settings_oberlay: bool,
+    slice_annotations: SliceAnnotations,
 }
 
 /// Wraps many rendering apps into one for grid views and shared memory.
@@ -206,13 +839,39 @@ pub struct WrapApp {
     node_counter: usize,
 
     volume_texture: crate::apps::Texture,
+    volume: Rc<crate::data::Volume>,
+    transfer_function: Rc<RefCell<TransferFunction>>,
+    lookup_table: crate::apps::LookupTable,
+    /// Cursor, oblique plane and curved path linking the slice views.
+    shared_slice_state: SharedSliceState,
 }
 
 impl WrapApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let volume_texture = crate::apps::Texture::default(cc).unwrap();
+        let volume = Rc::new(crate::data::Volume::default());
         let wgpu_render_state = cc.wgpu_render_state.as_ref().unwrap();
-        let tree = Self::default_dock(wgpu_render_state, &volume_texture);
+        let transfer_function = TransferFunction::default();
+        let lookup_table = crate::apps::LookupTable::new(
+            &wgpu_render_state.device,
+            &wgpu_render_state.queue,
+            &transfer_function,
+            (0.0, u16::MAX as f32),
+        );
+        LabelOverlay::register(wgpu_render_state);
+        let shared_slice_state =
+            SharedSliceState::new(volume_texture.dimensions, volume_texture.spacing);
+        shared_slice_state
+            .volume_statistics
+            .borrow_mut()
+            .start(Arc::clone(&volume.values));
+        let tree = Self::default_dock(
+            wgpu_render_state,
+            &volume_texture,
+            &lookup_table,
+            &volume,
+            &shared_slice_state,
+        );
 
         #[allow(unused_mut)]
         let mut slf = Self {
@@ -220,6 +879,10 @@ impl WrapApp {
             tree,
             node_counter: 4,
             volume_texture,
+            volume,
+            transfer_function: Rc::new(RefCell::new(transfer_function)),
+            lookup_table,
+            shared_slice_state,
         };
 
         #[cfg(feature = "persistence")]
@@ -228,18 +891,37 @@ impl WrapApp {
                 slf.state = state;
             }
         }
+        slf.show_slice_annotations();
 
         slf
     }
 
+    /// Passes the persisted annotation settings to the slice views.
+    fn show_slice_annotations(&mut self) {
+        let annotations = self.state.slice_annotations;
+        for node in self.tree.iter_mut() {
+            if let egui_dock::Node::Leaf { tabs, .. } = node {
+                for tab in tabs {
+                    tab.content.show_annotations(annotations);
+                }
+            }
+        }
+    }
+
     fn default_dock(
         wgpu_render_state: &eframe::egui_wgpu::RenderState,
         volume_texture: &crate::apps::Texture,
+        lookup_table: &crate::apps::LookupTable,
+        volume: &Rc<crate::data::Volume>,
+        shared: &SharedSliceState,
     ) -> Tree<Tab> {
         let mut tree = Tree::new(vec![Tab::slice_view_axial(
             0,
             wgpu_render_state,
             volume_texture,
+            lookup_table,
+            volume.clone(),
+            shared.clone(),
         )]);
 
         // Modify the tree before constructing the dock
@@ -250,6 +932,9 @@ impl WrapApp {
                 1,
                 wgpu_render_state,
                 volume_texture,
+                lookup_table,
+                volume.clone(),
+                shared.clone(),
             )],
         );
         let [_, _] = tree.split_below(
@@ -259,12 +944,35 @@ impl WrapApp {
                 2,
                 wgpu_render_state,
                 volume_texture,
+                lookup_table,
+                volume.clone(),
+                shared.clone(),
             )],
         );
 
         tree
     }
 
+    /// Loads the annotations stored next to the volume file, if any.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    fn load_annotation_sidecar(&mut self) {
+        let Some(path) = self
+            .volume
+            .path
+            .as_deref()
+            .map(crate::io::annotation_sidecar_path)
+        else {
+            return;
+        };
+        if !path.exists() {
+            return;
+        }
+        match crate::io::load_annotations(&path, &self.volume) {
+            Ok(items) => self.shared_slice_state.annotations.borrow_mut().items = items,
+            Err(err) => log::error!("Failed to load annotations: {err}"),
+        }
+    }
+
     pub fn update_volume_texture(&mut self, frame: &mut eframe::Frame) {
         let wgpu_render_state = eframe::Frame::wgpu_render_state(frame).unwrap();
         let device = &wgpu_render_state.device;
@@ -275,11 +983,50 @@ impl WrapApp {
         let spacing = self.state.importer.item.spacing.unwrap();
         let label: Option<&str> = Some("Volume Texture");
 
+        // keep the original values, the texture conversion overwrites `bytes`
+        let mut volume = crate::data::Volume::from_u16_bytes(bytes, dimensions, spacing);
+        if let Some(origin) = self.state.importer.item.origin {
+            volume.origin = origin;
+        }
+        if let Some(direction) = self.state.importer.item.direction {
+            let columns = direction.map(cgmath::Vector3::from);
+            // keep the axes unit length for the index-to-world transform
+            if columns.iter().all(|column| column.magnitude2() > 0.0) {
+                let [x, y, z] = columns.map(|column| column.normalize());
+                volume.direction = cgmath::Matrix3::from_cols(x, y, z);
+            }
+        }
+        volume.path = self.state.importer.item.path.clone();
+        self.volume = Rc::new(volume);
+        let (min, max) = self.volume.range();
+        *self.transfer_function.borrow_mut() = TransferFunction::ramp(min as f32, max as f32);
+        self.lookup_table.set_range(queue, (min as f32, max as f32));
+
         self.volume_texture =
             crate::apps::Texture::from_u16_bytes(device, queue, bytes, dimensions, spacing, label)
                 .unwrap();
 
-        self.tree = Self::default_dock(wgpu_render_state, &self.volume_texture);
+        let coordinate_system = self.shared_slice_state.coordinate_system.get();
+        self.shared_slice_state = SharedSliceState::new(dimensions, spacing);
+        self.shared_slice_state
+            .coordinate_system
+            .set(coordinate_system);
+        // counted on a worker thread while the views come up
+        self.shared_slice_state
+            .volume_statistics
+            .borrow_mut()
+            .start(Arc::clone(&self.volume.values));
+        #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+        self.load_annotation_sidecar();
+
+        self.tree = Self::default_dock(
+            wgpu_render_state,
+            &self.volume_texture,
+            &self.lookup_table,
+            &self.volume,
+            &self.shared_slice_state,
+        );
+        self.show_slice_annotations();
 
         self.state.importer = crate::io::Importer::default();
     }
@@ -314,6 +1061,10 @@ impl eframe::App for WrapApp {
 
         self.show_dock(ctx, frame);
 
+        self.update_lookup_table(frame);
+
+        self.update_label_overlay(frame);
+
         if self.state.importer.new_data_available {
             self.update_volume_texture(frame);
         }
@@ -372,6 +1123,7 @@ impl WrapApp {
 
             if ui.button("Reset everything").clicked() {
                 self.state = Default::default();
+                self.show_slice_annotations();
                 ui.ctx().memory_mut(|mem| *mem = Default::default());
                 ui.close_menu();
             }
@@ -382,6 +1134,10 @@ impl WrapApp {
         let mut added_nodes = Vec::new();
         let wgpu_render_state = eframe::Frame::wgpu_render_state(frame).unwrap();
         let volume_texture = &self.volume_texture;
+        let volume = &self.volume;
+        let transfer_function = &self.transfer_function;
+        let lookup_table = &self.lookup_table;
+        let shared = &self.shared_slice_state;
 
         let mut style = Style::from_egui(ctx.style().as_ref());
         style.tabs.inner_margin = Margin::same(0.0);
@@ -397,10 +1153,15 @@ impl WrapApp {
                     added_nodes: &mut added_nodes,
                     wgpu_render_state,
                     volume_texture,
+                    volume,
+                    transfer_function,
+                    lookup_table,
+                    shared,
                 },
             );
 
-        added_nodes.drain(..).for_each(|node| {
+        added_nodes.drain(..).for_each(|mut node| {
+            node.content.show_annotations(self.state.slice_annotations);
             self.tree.set_focused_node(node.node);
             self.tree.push_to_focused_leaf(Tab {
                 node: NodeIndex(self.node_counter),
@@ -410,6 +1171,23 @@ impl WrapApp {
         });
     }
 
+    fn update_lookup_table(&mut self, frame: &mut eframe::Frame) {
+        let wgpu_render_state = eframe::Frame::wgpu_render_state(frame).unwrap();
+        self.lookup_table
+            .update(&wgpu_render_state.queue, &self.transfer_function.borrow());
+    }
+
+    fn update_label_overlay(&mut self, frame: &mut eframe::Frame) {
+        let wgpu_render_state = eframe::Frame::wgpu_render_state(frame).unwrap();
+        let mut renderer = wgpu_render_state.renderer.write();
+        let label_overlay: &mut LabelOverlay = renderer.paint_callback_resources.get_mut().unwrap();
+        label_overlay.update(
+            &wgpu_render_state.device,
+            &wgpu_render_state.queue,
+            &mut self.shared_slice_state.labels.borrow_mut(),
+        );
+    }
+
     fn show_importer(&mut self, ctx: &egui::Context) {
         self.state.importer.show(ctx);
     }
@@ -480,6 +1258,28 @@ impl WrapApp {
 
                 ui.close_menu();
             }
+
+            ui.separator();
+            let mut coordinate_system = self.shared_slice_state.coordinate_system.get();
+            ui.horizontal(|ui| {
+                ui.label("Coordinates:");
+                for system in CoordinateSystem::ALL {
+                    ui.radio_value(&mut coordinate_system, system, system.label());
+                }
+            });
+            self.shared_slice_state
+                .coordinate_system
+                .set(coordinate_system);
+
+            ui.separator();
+            let mut annotations = self.state.slice_annotations;
+            ui.checkbox(&mut annotations.orientation_labels, "Orientation Labels");
+            ui.checkbox(&mut annotations.scale_bar, "Scale Bars");
+            ui.checkbox(&mut annotations.slice_info, "Slice Info");
+            if annotations != self.state.slice_annotations {
+                self.state.slice_annotations = annotations;
+                self.show_slice_annotations();
+            }
         });
     }
 